@@ -1,10 +1,12 @@
+use anyhow::bail;
+use formality_core::Set;
 use formality_prove::Env;
-use formality_rust::grammar::{Adt, AdtBoundData, Field, Variant};
-use formality_types::grammar::Fallible;
+use formality_rust::grammar::{Adt, AdtBoundData, Field, FieldName, Variant, VariantId};
+use formality_types::grammar::{AdtId, Fallible};
 
 impl super::Check<'_> {
     pub(super) fn check_adt(&self, adt: &Adt) -> Fallible<()> {
-        let Adt { id: _, binder } = adt;
+        let Adt { id, binder } = adt;
 
         let mut env = Env::default();
 
@@ -15,7 +17,14 @@ impl super::Check<'_> {
 
         self.prove_where_clauses_well_formed(&env, &where_clauses, &where_clauses)?;
 
-        // FIXME: check names are unique or integers from 0..n
+        let mut variant_names: Set<&VariantId> = Set::new();
+        for Variant { name, fields } in &variants {
+            if !variant_names.insert(name) {
+                bail!("the variant `{name:?}` is defined multiple times in `{id:?}`")
+            }
+
+            self.check_field_names(id, name, fields)?;
+        }
 
         for Variant { name: _, fields } in &variants {
             for Field { name: _, ty } in fields {
@@ -25,4 +34,40 @@ impl super::Check<'_> {
 
         Ok(())
     }
+
+    /// Fields are either all named, in which case the names must be unique,
+    /// or all positional (a tuple struct/variant), in which case they must be `0..n` in order.
+    fn check_field_names(
+        &self,
+        adt_id: &AdtId,
+        variant: &VariantId,
+        fields: &[Field],
+    ) -> Fallible<()> {
+        let owner = if *variant == VariantId::for_struct() {
+            format!("{adt_id:?}")
+        } else {
+            format!("{adt_id:?}::{variant:?}")
+        };
+
+        if fields.iter().any(|f| matches!(f.name, FieldName::Index(_))) {
+            for (index, field) in fields.iter().enumerate() {
+                if field.name != FieldName::Index(index) {
+                    bail!("the fields of tuple `{owner}` must be numbered `0..{}` in order, but field {index} is named `{:?}`{}", fields.len(), field.name, self.at(field))
+                }
+            }
+        } else {
+            let mut field_names: Set<&FieldName> = Set::new();
+            for field in fields {
+                if !field_names.insert(&field.name) {
+                    bail!(
+                        "the field `{:?}` is defined multiple times in `{owner}`{}",
+                        field.name,
+                        self.at(field)
+                    )
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -6,7 +6,7 @@ use formality_rust::grammar::{Crate, NegTraitImpl, TraitImpl};
 use formality_types::grammar::{Fallible, Wc, Wcs};
 use itertools::Itertools;
 
-use crate::Check;
+use crate::{parallel::par_check, Check, Overflow};
 
 impl Check<'_> {
     pub(crate) fn check_coherence(&self, current_crate: &Crate) -> Fallible<()> {
@@ -16,35 +16,38 @@ impl Check<'_> {
         let current_crate_neg_impls: Vec<NegTraitImpl> =
             current_crate.items.iter().downcasted().collect();
 
-        for impl_a in &current_crate_impls {
-            self.orphan_check(impl_a)?;
-        }
+        par_check(&current_crate_impls, |impl_a| self.orphan_check(impl_a))?;
 
-        for impl_a in &current_crate_neg_impls {
-            self.orphan_check_neg(impl_a)?;
-        }
+        par_check(&current_crate_neg_impls, |impl_a| {
+            self.orphan_check_neg(impl_a)
+        })?;
 
         // check for duplicate impls in the current crate
         for (impl_a, i) in current_crate_impls.iter().zip(0..) {
             if current_crate_impls[i + 1..].contains(impl_a) {
-                bail!("duplicate impl in current crate: {:?}", impl_a)
+                bail!(
+                    "duplicate impl in current crate: {:?}{}",
+                    impl_a,
+                    self.at(impl_a)
+                )
             }
         }
 
         // check each impl in current crate against impls in all other crates
-        for (impl_a, impl_b) in current_crate_impls
+        let pairs: Vec<(&TraitImpl, &TraitImpl)> = current_crate_impls
             .iter()
             .cartesian_product(&all_crate_impls)
             .filter(|(impl_a, impl_b)| impl_a != impl_b)
             .filter(|(impl_a, impl_b)| impl_a.trait_id() == impl_b.trait_id())
-        {
-            self.overlap_check(impl_a, impl_b)?;
-        }
+            .collect();
+        par_check(&pairs, |(impl_a, impl_b)| {
+            self.overlap_check(impl_a, impl_b)
+        })?;
 
         Ok(())
     }
 
-    #[context("orphan_check({impl_a:?})")]
+    #[context("orphan_check({impl_a:?}){}", self.at(impl_a))]
     fn orphan_check(&self, impl_a: &TraitImpl) -> Fallible<()> {
         let mut env = Env::default();
 
@@ -58,7 +61,7 @@ impl Check<'_> {
         )
     }
 
-    #[context("orphan_check_neg({impl_a:?})")]
+    #[context("orphan_check_neg({impl_a:?}){}", self.at(impl_a))]
     fn orphan_check_neg(&self, impl_a: &NegTraitImpl) -> Fallible<()> {
         let mut env = Env::default();
 
@@ -97,7 +100,7 @@ impl Check<'_> {
         // in coherence mode, then they do not overlap.
         //
         // ∀P_a, ∀P_b. ⌐ (coherence_mode => (Ts_a = Ts_b && WC_a && WC_b))
-        if let Ok(()) = self.prove_not_goal(
+        let disproved = self.prove_not_goal(
             &env.with_coherence_mode(true),
             (),
             (
@@ -105,7 +108,8 @@ impl Check<'_> {
                 &a.where_clauses,
                 &b.where_clauses,
             ),
-        ) {
+        );
+        if let Ok(()) = disproved {
             tracing::debug!(
                 "proved not {:?}",
                 (
@@ -154,6 +158,23 @@ impl Check<'_> {
 
             return Ok(());
         }
-        bail!("impls may overlap:\n{impl_a:?}\n{impl_b:?}")
+
+        // If the solver gave up, we do not know whether the impls overlap,
+        // so report that rather than claiming that they may.
+        if let Err(e) = disproved {
+            if e.is::<Overflow>() {
+                return Err(e.context(format!(
+                    "could not decide whether impls overlap:\n{impl_a:?}{}\n{impl_b:?}{}",
+                    self.at(impl_a),
+                    self.at(impl_b),
+                )));
+            }
+        }
+
+        bail!(
+            "impls may overlap:\n{impl_a:?}{}\n{impl_b:?}{}",
+            self.at(impl_a),
+            self.at(impl_b)
+        )
     }
 }
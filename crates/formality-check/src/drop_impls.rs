@@ -0,0 +1,64 @@
+use anyhow::bail;
+
+use fn_error_context::context;
+use formality_core::Upcasted;
+use formality_prove::Env;
+use formality_rust::grammar::{TraitImpl, TraitImplBoundData};
+use formality_types::grammar::{Fallible, Parameter, RigidName, RigidTy, TyData};
+
+impl super::Check<'_> {
+    /// Impls of the `Drop` lang item carry extra restrictions, because the destructor
+    /// is run for *every* value of the type:
+    ///
+    /// * the self type must be a struct or enum from the current crate;
+    /// * the impl must be generic over exactly the parameters of that type, in order;
+    /// * the impl where-clauses must be implied by the where-clauses of the type;
+    /// * `may_dangle` may only name generic parameters of the impl.
+    #[context("check_drop_impl({v:?})")]
+    pub(super) fn check_drop_impl(&self, v: &TraitImpl) -> Fallible<()> {
+        let TraitImpl { binder } = v;
+
+        let (env, impl_vars) = Env::default().universal_substitution(binder);
+        let impl_parameters: Vec<Parameter> = impl_vars.iter().upcasted().collect();
+
+        let TraitImplBoundData {
+            trait_id: _,
+            self_ty,
+            trait_parameters: _,
+            may_dangle,
+            where_clauses,
+            impl_items: _,
+        } = binder.instantiate_with(&impl_vars)?;
+
+        let TyData::RigidTy(RigidTy {
+            name: RigidName::AdtId(adt_id),
+            parameters,
+        }) = self_ty.data()
+        else {
+            bail!("`Drop` impls must be for a struct or enum, not `{self_ty:?}`")
+        };
+
+        if !self.decls.is_local_adt_id(adt_id) {
+            bail!("`Drop` impl for `{adt_id:?}` must be in the crate that defines `{adt_id:?}`")
+        }
+
+        if *parameters != impl_parameters {
+            bail!("`Drop` impl for `{self_ty:?}` must be generic over exactly the parameters of `{adt_id:?}`")
+        }
+
+        for p in &may_dangle {
+            if !impl_parameters.contains(p) {
+                bail!("`may_dangle` can only name generic parameters of the impl, not `{p:?}`")
+            }
+        }
+
+        let adt = self
+            .decls
+            .adt_decl(adt_id)
+            .binder
+            .instantiate_with(parameters)?;
+        self.prove_goal(&env, adt.where_clause, &where_clauses)?;
+
+        Ok(())
+    }
+}
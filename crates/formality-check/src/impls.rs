@@ -1,13 +1,13 @@
 use anyhow::bail;
 
 use fn_error_context::context;
-use formality_core::Downcasted;
+use formality_core::{Downcasted, Set};
 use formality_prove::Env;
 use formality_rust::{
     grammar::{
         AssociatedTy, AssociatedTyBoundData, AssociatedTyValue, AssociatedTyValueBoundData, Fn,
-        FnBoundData, ImplItem, NegTraitImpl, NegTraitImplBoundData, TraitBoundData, TraitImpl,
-        TraitImplBoundData, TraitItem,
+        FnBoundData, ImplItem, LangItem, NegTraitImpl, NegTraitImplBoundData, TraitBoundData,
+        TraitImpl, TraitImplBoundData, TraitItem,
     },
     prove::ToWcs,
 };
@@ -27,10 +27,17 @@ impl super::Check<'_> {
             trait_id,
             self_ty,
             trait_parameters,
+            may_dangle,
             where_clauses,
             impl_items,
         } = env.instantiate_universally(binder);
 
+        if self.program.is_lang_item_trait(&trait_id, LangItem::Drop) {
+            self.check_drop_impl(v)?;
+        } else if !may_dangle.is_empty() {
+            bail!("`may_dangle` is only permitted on impls of the `Drop` lang item")
+        }
+
         let trait_ref = trait_id.with(self_ty, trait_parameters);
 
         self.prove_where_clauses_well_formed(&env, &where_clauses, &where_clauses)?;
@@ -45,6 +52,8 @@ impl super::Check<'_> {
             trait_items,
         } = trait_decl.binder.instantiate_with(&trait_ref.parameters)?;
 
+        self.check_impl_items_have_unique_names(&impl_items)?;
+
         for impl_item in &impl_items {
             self.check_trait_impl_item(&env, &where_clauses, &trait_items, impl_item)?;
         }
@@ -52,6 +61,30 @@ impl super::Check<'_> {
         Ok(())
     }
 
+    /// As with trait items, associated type values and fns are in separate namespaces.
+    fn check_impl_items_have_unique_names(&self, impl_items: &[ImplItem]) -> Fallible<()> {
+        let mut type_names: Set<&str> = Set::new();
+        let mut value_names: Set<&str> = Set::new();
+        for impl_item in impl_items {
+            match impl_item {
+                ImplItem::Fn(f) => {
+                    if !value_names.insert(&f.id) {
+                        bail!("the fn `{:?}` is defined multiple times in the impl", f.id)
+                    }
+                }
+                ImplItem::AssociatedTyValue(a) => {
+                    if !type_names.insert(&a.id) {
+                        bail!(
+                            "the associated type `{:?}` is defined multiple times in the impl",
+                            a.id
+                        )
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub(super) fn check_neg_trait_impl(&self, i: &NegTraitImpl) -> Fallible<()> {
         let mut env = Env::default();
 
@@ -164,7 +197,7 @@ impl super::Check<'_> {
         Ok(())
     }
 
-    #[context("check_associated_ty_value({impl_value:?})")]
+    #[context("check_associated_ty_value({impl_value:?}){}", self.at(impl_value))]
     fn check_associated_ty_value(
         &self,
         impl_env: &Env,
@@ -1,9 +1,14 @@
 #![allow(dead_code)]
 
-use std::{collections::VecDeque, fmt::Debug};
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+};
 
-use anyhow::bail;
-use formality_prove::{Decls, Env};
+use anyhow::{bail, Context};
+use formality_core::{judgment::failure::FailedJudgment, parse::SourceMap, Set};
+use formality_prove::{Constraints, Decls, Env};
 use formality_rust::{
     grammar::{Crate, CrateItem, Program},
     prove::ToWcs,
@@ -13,41 +18,82 @@ use formality_types::grammar::{Fallible, Substitution, Wcs};
 /// Check all crates in the program. The crates must be in dependency order
 /// such that any prefix of the crates is a complete program.
 pub fn check_all_crates(program: &Program) -> Fallible<()> {
+    check_crates(program, None)
+}
+
+/// Like [`check_all_crates`], for a program parsed from a file:
+/// errors report where in the file the offending items are.
+pub fn check_all_crates_from_file(program: &Program, source_map: &SourceMap) -> Fallible<()> {
+    check_crates(program, Some(source_map))
+}
+
+fn check_crates(program: &Program, source_map: Option<&SourceMap>) -> Fallible<()> {
     let Program { crates } = program;
     let mut crates: VecDeque<_> = crates.iter().cloned().collect();
 
     let mut prefix_program = Program { crates: vec![] };
     while let Some(c) = crates.pop_front() {
         prefix_program.crates.push(c);
-        check_current_crate(&prefix_program)?;
+        check_current_crate(&prefix_program, source_map)?;
     }
 
     Ok(())
 }
 
 /// Checks the current crate in the program, assuming all other crates are valid.
-fn check_current_crate(program: &Program) -> Fallible<()> {
+fn check_current_crate(program: &Program, source_map: Option<&SourceMap>) -> Fallible<()> {
     let decls = program.to_prove_decls();
     Check {
         program,
         decls: &decls,
+        source_map,
     }
     .check()
 }
 
 mod adts;
 mod coherence;
+mod drop_impls;
 mod fns;
 mod impls;
+mod parallel;
 mod traits;
 mod where_clauses;
 
+/// The solver gave up while proving `goal` because it exceeded the size or
+/// depth limits of the [`Decls`]. This is reported instead of the usual failure
+/// since it means that we do not know whether the goal holds.
+#[derive(Debug)]
+pub struct Overflow {
+    pub goal: Wcs,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "overflow while proving {:?}", self.goal)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
 struct Check<'p> {
     program: &'p Program,
     decls: &'p Decls,
+
+    /// Where the terms in `program` were parsed from, if it was parsed from a file.
+    source_map: Option<&'p SourceMap>,
 }
 
 impl Check<'_> {
+    /// Describes where `term` was parsed from, for use in error messages.
+    /// Empty if the program was not parsed from a file.
+    fn at<T: Hash + Eq + 'static>(&self, term: &T) -> String {
+        match self.source_map.and_then(|m| m.span_of(term)) {
+            Some(span) => format!(" at {span}"),
+            None => String::new(),
+        }
+    }
+
     fn check(&self) -> Fallible<()> {
         let Program { crates } = &self.program;
         if let Some(current_crate) = crates.last() {
@@ -59,30 +105,66 @@ impl Check<'_> {
     fn check_current_crate(&self, c: &Crate) -> Fallible<()> {
         let Crate { id: _, items } = c;
 
-        self.check_for_duplicate_items()?;
+        self.check_for_duplicate_items(c)?;
 
-        for item in items {
-            self.check_crate_item(item)?;
-        }
+        parallel::par_check(items, |item| self.check_crate_item(item))?;
 
         self.check_coherence(c)?;
 
         Ok(())
     }
 
-    fn check_for_duplicate_items(&self) -> Fallible<()> {
-        // FIXME: check for items with duplicate names, respecting the various Rust rules about namespaces
+    /// Items live in two namespaces: structs, enums and traits are in the type namespace,
+    /// functions are in the value namespace. Tuple structs are in both, since their
+    /// constructor is a value. Impls are unnamed.
+    fn check_for_duplicate_items(&self, c: &Crate) -> Fallible<()> {
+        let Crate {
+            id: crate_id,
+            items,
+        } = c;
+
+        let mut type_names: Set<&str> = Set::new();
+        let mut value_names: Set<&str> = Set::new();
+
+        for item in items {
+            let (type_name, value_name) = match item {
+                CrateItem::Struct(s) if s.is_tuple_struct() => (Some(&s.id[..]), Some(&s.id[..])),
+                CrateItem::Struct(s) => (Some(&s.id[..]), None),
+                CrateItem::Enum(e) => (Some(&e.id[..]), None),
+                CrateItem::Trait(t) => (Some(&t.id[..]), None),
+                CrateItem::Fn(f) => (None, Some(&f.id[..])),
+                CrateItem::TraitImpl(_) | CrateItem::NegTraitImpl(_) => (None, None),
+            };
+
+            if let Some(name) = type_name {
+                if !type_names.insert(name) {
+                    bail!("the name `{name}` is defined multiple times in the type namespace of crate `{crate_id:?}`")
+                }
+            }
+
+            if let Some(name) = value_name {
+                if !value_names.insert(name) {
+                    bail!("the name `{name}` is defined multiple times in the value namespace of crate `{crate_id:?}`")
+                }
+            }
+        }
+
         Ok(())
     }
 
     fn check_crate_item(&self, c: &CrateItem) -> Fallible<()> {
-        match c {
+        let result = match c {
             CrateItem::Trait(v) => self.check_trait(v),
             CrateItem::TraitImpl(v) => self.check_trait_impl(v),
             CrateItem::Struct(s) => self.check_adt(&s.to_adt()),
             CrateItem::Enum(e) => self.check_adt(&e.to_adt()),
             CrateItem::Fn(f) => self.check_free_fn(f),
             CrateItem::NegTraitImpl(i) => self.check_neg_trait_impl(i),
+        };
+
+        match self.source_map.and_then(|m| m.span_of(c)) {
+            Some(span) => result.with_context(|| format!("in the item at {span}")),
+            None => result,
         }
     }
 
@@ -103,7 +185,36 @@ impl Check<'_> {
             return Ok(());
         }
 
-        bail!("failed to prove {goal:?} given {assumptions:?}, got {cs:?}")
+        if let Some(overflow) = cs.iter().find_map(|c| c.overflowed()) {
+            return Err(anyhow::Error::new(Overflow {
+                goal: overflow.clone(),
+            })
+            .context(format!("failed to prove {goal:?} given {assumptions:?}")));
+        }
+
+        if cs.is_empty() {
+            let failures = formality_prove::prove_failures(self.decls, env, &assumptions, &goal);
+            bail!(
+                "failed to prove {goal:?} given {assumptions:?}, got {cs:?}\n{}",
+                self.explain_failures(&failures)
+            )
+        }
+
+        let collapsed = Constraints::collapse(env, &cs).unwrap();
+        if collapsed.ambiguity.is_empty() {
+            bail!("failed to prove {goal:?} given {assumptions:?}, got {cs:?}")
+        }
+        let mut reason = format!("ambiguous because of {:?}", collapsed.ambiguity);
+        if !collapsed.substitution.is_empty() {
+            reason += &format!(" with guidance {:?}", collapsed.substitution);
+        }
+        bail!("failed to prove {goal:?} given {assumptions:?}, got {cs:?}\n{reason}")
+    }
+
+    /// Renders the failure trees from [`formality_prove::prove_failures`].
+    fn explain_failures(&self, failures: &[FailedJudgment]) -> String {
+        let explanation: String = failures.iter().map(|f| f.to_string()).collect();
+        explanation.trim_end().to_string()
     }
 
     fn prove_not_goal(
@@ -142,13 +253,20 @@ impl Check<'_> {
             self.decls,
             &existential_env,
             existential_assumptions.to_wcs(),
-            &existential_goal,
+            existential_goal,
         );
 
         if cs.is_empty() {
             return Ok(());
         }
 
+        if let Some(overflow) = cs.iter().find_map(|c| c.overflowed()) {
+            return Err(anyhow::Error::new(Overflow {
+                goal: overflow.clone(),
+            })
+            .context(format!("failed to disprove {goal:?} given {assumptions:?}")));
+        }
+
         bail!("failed to disprove\n    {goal:?}\ngiven\n    {assumptions:?}\ngot\n{cs:#?}")
     }
 }
@@ -0,0 +1,65 @@
+//! Running independent checks in parallel.
+//!
+//! The solver keeps its state (judgment stacks and caches) in thread-locals,
+//! so each worker thread proves goals independently of the others. The error that is
+//! reported is always that of the first failing input, so it does not depend on
+//! how the work was scheduled.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use formality_types::grammar::Fallible;
+
+/// Stack size of the worker threads; the solver recurses deeply.
+const STACK_SIZE: usize = 16 << 20;
+
+/// Runs `check` on each of `items` in parallel, returning the error
+/// of the first item (in order) that fails.
+///
+/// Like a sequential loop, this stops early: once some item has failed, no further
+/// items are started. Items are started in order, so every item before a failing one
+/// has been started, and waiting for those to finish finds the first failure.
+pub(crate) fn par_check<T: Sync>(
+    items: &[T],
+    check: impl Fn(&T) -> Fallible<()> + Sync,
+) -> Fallible<()> {
+    let threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(items.len());
+    if threads <= 1 {
+        return items.iter().try_for_each(check);
+    }
+
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let errors: Mutex<Vec<(usize, anyhow::Error)>> = Mutex::new(vec![]);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn_scoped(scope, || {
+                    while !failed.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        if let Err(error) = check(item) {
+                            failed.store(true, Ordering::Relaxed);
+                            errors.lock().unwrap().push((index, error));
+                        }
+                    }
+                })
+                .unwrap();
+        }
+    });
+    match errors
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .min_by_key(|(index, _)| *index)
+    {
+        Some((_, error)) => Err(error),
+        None => Ok(()),
+    }
+}
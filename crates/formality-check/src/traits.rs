@@ -1,22 +1,37 @@
+use anyhow::bail;
 use fn_error_context::context;
+use formality_core::Set;
 use formality_prove::Env;
 use formality_rust::grammar::{
-    AssociatedTy, AssociatedTyBoundData, Fn, Trait, TraitBoundData, TraitItem, WhereClause,
+    AssociatedTy, AssociatedTyBoundData, Fn, Trait, TraitAttribute, TraitBoundData, TraitItem,
+    WhereClause,
 };
-use formality_types::grammar::Fallible;
+use formality_types::grammar::{Fallible, TraitId};
 
 impl super::Check<'_> {
     #[context("check_trait({:?})", t.id)]
     pub(super) fn check_trait(&self, t: &Trait) -> Fallible<()> {
-        let Trait { id: _, binder } = t;
+        let Trait {
+            attributes,
+            id,
+            binder,
+        } = t;
         let mut env = Env::default();
 
+        for attribute in attributes {
+            match attribute {
+                TraitAttribute::Lang(lang_item) => {
+                    self.program.lang_item_trait(*lang_item)?;
+                }
+            }
+        }
+
         let TraitBoundData {
             where_clauses,
             trait_items,
         } = env.instantiate_universally(&binder.explicit_binder);
 
-        self.check_trait_items_have_unique_names(&trait_items)?;
+        self.check_trait_items_have_unique_names(id, &trait_items)?;
 
         self.prove_where_clauses_well_formed(&env, &where_clauses, &where_clauses)?;
 
@@ -27,8 +42,32 @@ impl super::Check<'_> {
         Ok(())
     }
 
-    fn check_trait_items_have_unique_names(&self, _trait_items: &[TraitItem]) -> Fallible<()> {
-        // FIXME:
+    /// Associated types are in the type namespace and fns in the value namespace,
+    /// so a trait may have an associated type and a fn with the same name.
+    fn check_trait_items_have_unique_names(
+        &self,
+        trait_id: &TraitId,
+        trait_items: &[TraitItem],
+    ) -> Fallible<()> {
+        let mut type_names: Set<&str> = Set::new();
+        let mut value_names: Set<&str> = Set::new();
+        for trait_item in trait_items {
+            match trait_item {
+                TraitItem::Fn(f) => {
+                    if !value_names.insert(&f.id) {
+                        bail!(
+                            "the fn `{:?}` is defined multiple times in trait `{trait_id:?}`",
+                            f.id
+                        )
+                    }
+                }
+                TraitItem::AssociatedTy(a) => {
+                    if !type_names.insert(&a.id) {
+                        bail!("the associated type `{:?}` is defined multiple times in trait `{trait_id:?}`", a.id)
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
@@ -1,3 +1,4 @@
+use anyhow::bail;
 use fn_error_context::context;
 use formality_core::Upcast;
 use formality_prove::Env;
@@ -5,7 +6,7 @@ use formality_rust::{
     grammar::{WhereClause, WhereClauseData},
     prove::ToWcs,
 };
-use formality_types::grammar::{ConstData, Fallible, Parameter, Relation, TraitRef};
+use formality_types::grammar::{Const, ConstData, Fallible, Parameter, Relation, TraitRef};
 
 impl super::Check<'_> {
     pub(crate) fn prove_where_clauses_well_formed(
@@ -20,7 +21,7 @@ impl super::Check<'_> {
         Ok(())
     }
 
-    #[context("prove_where_clause_well_formed({where_clause:?})")]
+    #[context("prove_where_clause_well_formed({where_clause:?}){}", self.at(where_clause))]
     // FIXME(oli-obk): figure out why is this a function and not a `judgment_fn`.
     fn prove_where_clause_well_formed(
         &self,
@@ -53,15 +54,31 @@ impl super::Check<'_> {
                     ConstData::Value(_, t) => {
                         self.prove_goal(in_env, &assumptions, Relation::eq(ty, t))?
                     }
-                    ConstData::Variable(_) => {}
+                    ConstData::Variable(_) => {
+                        self.prove_type_of_const_unique(in_env, &assumptions, ct)?
+                    }
                 }
-                // FIXME(oli-obk): prove that there is no `TypeOfConst` bound for a different type.
                 self.prove_parameter_well_formed(in_env, &assumptions, ct.clone())?;
                 self.prove_parameter_well_formed(in_env, assumptions, ty.clone())
             }
         }
     }
 
+    /// A const variable must have exactly one type: reject assumptions like
+    /// `type_of_const C is bool, type_of_const C is u32`.
+    fn prove_type_of_const_unique(
+        &self,
+        env: &Env,
+        assumptions: impl ToWcs,
+        ct: &Const,
+    ) -> Fallible<()> {
+        let tys = formality_prove::type_of_const(self.decls, env, assumptions.to_wcs(), ct);
+        if tys.len() > 1 {
+            bail!("const `{ct:?}` has conflicting types: {tys:?}")
+        }
+        Ok(())
+    }
+
     fn prove_parameter_well_formed(
         &self,
         env: &Env,
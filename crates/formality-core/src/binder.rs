@@ -1,5 +1,7 @@
 //! Manages binders so that the main rules can be nice and simple.
 
+use std::cell::RefCell;
+use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use anyhow::bail;
@@ -11,14 +13,31 @@ use crate::{
     fold::SubstitutionFn,
     language::{CoreKind, CoreParameter, HasKind, Language},
     substitution::CoreSubstitution,
-    variable::{CoreBoundVar, CoreVariable, DebruijnIndex, VarIndex},
+    variable::{CoreBoundVar, CoreVariable, DebruijnIndex, VarIndex, VarName},
     visit::CoreVisit,
     Fallible,
 };
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+/// Binds variables in a term.
+/// The names of the variables are ignored when comparing, ordering and hashing binders,
+/// so that binders that differ only in those names are equal.
+#[derive(Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "CoreKind<L>: serde::Serialize, T: serde::Serialize",
+        deserialize = "CoreKind<L>: serde::Deserialize<'de>, T: serde::Deserialize<'de>"
+    ))
+)]
 pub struct CoreBinder<L: Language, T> {
     kinds: Vec<CoreKind<L>>,
+
+    /// The names of the bound variables (e.g., as written in the source), one per kind.
+    /// They are only used for debug output; see [`VarName`]. The variables in `term`
+    /// themselves are unnamed, since bound variables are shared between binders.
+    names: Vec<VarName>,
+
     term: T,
 }
 
@@ -33,14 +52,16 @@ impl<L: Language, T: CoreFold<L>> CoreBinder<L, T> {
         let (bound_vars, substitution): (Vec<CoreBoundVar<L>>, CoreSubstitution<L>) = self
             .kinds
             .iter()
+            .zip(&self.names)
             .zip(0..)
-            .map(|(kind, index)| {
+            .map(|((&kind, &name), index)| {
                 let old_bound_var = CoreBoundVar {
                     debruijn: Some(DebruijnIndex::INNERMOST),
                     var_index: VarIndex { index },
-                    kind: *kind,
+                    kind,
+                    name: VarName::NONE,
                 };
-                let new_bound_var = CoreBoundVar::fresh(*kind);
+                let new_bound_var = CoreBoundVar::fresh_named(kind, name);
                 (new_bound_var, (old_bound_var, new_bound_var))
             })
             .unzip();
@@ -55,8 +76,12 @@ impl<L: Language, T: CoreFold<L>> CoreBinder<L, T> {
 
     /// Given a set of variables (X, Y, Z) and a term referecing some subset of them,
     /// create a binder where exactly those variables are bound (even the ones not used).
+    ///
+    /// The bound variables keep their names, except that a suffix is added to a name
+    /// that is already used by another bound variable or a free variable of the term.
     pub fn new(variables: impl Upcast<Vec<CoreVariable<L>>>, term: T) -> Self {
         let variables: Vec<CoreVariable<L>> = variables.upcast();
+        let names = Self::disambiguate_names(&variables, &term);
         let (kinds, substitution): (Vec<CoreKind<L>>, CoreSubstitution<L>) = variables
             .iter()
             .zip(0..)
@@ -67,6 +92,7 @@ impl<L: Language, T: CoreFold<L>> CoreBinder<L, T> {
                     debruijn: Some(DebruijnIndex::INNERMOST),
                     var_index: VarIndex { index },
                     kind: old_bound_var.kind(),
+                    name: VarName::NONE,
                 }
                 .upcast();
                 (old_bound_var.kind(), (old_bound_var, new_bound_var))
@@ -74,7 +100,31 @@ impl<L: Language, T: CoreFold<L>> CoreBinder<L, T> {
             .unzip();
 
         let term = substitution.apply(&term);
-        CoreBinder { kinds, term }
+        CoreBinder { kinds, names, term }
+    }
+
+    /// The names for binding `variables` in `term`, see [`CoreBinder::new`].
+    fn disambiguate_names(variables: &[CoreVariable<L>], term: &T) -> Vec<VarName> {
+        if variables.iter().all(|v| v.name().is_none()) {
+            return variables.iter().map(|v| v.name()).collect();
+        }
+
+        let mut taken: Vec<VarName> = term
+            .free_variables()
+            .into_iter()
+            .filter(|v| !variables.contains(v))
+            .map(|v| v.name())
+            .collect();
+        variables
+            .iter()
+            .map(|v| {
+                let name = v
+                    .name()
+                    .disambiguate(|name| taken.iter().any(|t| t.same_as(name)));
+                taken.push(name);
+                name
+            })
+            .collect()
     }
 
     /// Given a set of variables (X, Y, Z) and a term referecing some subset of them,
@@ -93,6 +143,7 @@ impl<L: Language, T: CoreFold<L>> CoreBinder<L, T> {
     {
         CoreBinder {
             kinds: self.kinds,
+            names: self.names,
             term: self.term.into(),
         }
     }
@@ -140,7 +191,7 @@ impl<L: Language, T: CoreFold<L>> CoreBinder<L, T> {
             CoreVariable::BoundVar(CoreBoundVar {
                 debruijn: Some(DebruijnIndex::INNERMOST),
                 var_index,
-                kind: _,
+                ..
             }) => Some(substitution[var_index.index].clone()),
 
             _ => None,
@@ -158,6 +209,11 @@ impl<L: Language, T: CoreFold<L>> CoreBinder<L, T> {
         &self.kinds
     }
 
+    /// Returns the names of each variable bound by this binder
+    pub fn names(&self) -> &[VarName] {
+        &self.names
+    }
+
     pub fn map<U: CoreFold<L>>(&self, op: impl FnOnce(T) -> U) -> CoreBinder<L, U> {
         let (vars, t) = self.open();
         let u = op(t);
@@ -169,6 +225,11 @@ impl<L: Language> CoreBoundVar<L> {
     /// Creates a fresh bound var of the given kind that is not yet part of a binder.
     /// You can put this into a term and then use `Binder::new`.
     pub fn fresh(kind: CoreKind<L>) -> Self {
+        Self::fresh_named(kind, VarName::NONE)
+    }
+
+    /// Like [`CoreBoundVar::fresh`], but for a variable with the given name.
+    pub fn fresh_named(kind: CoreKind<L>, name: VarName) -> Self {
         lazy_static! {
             static ref COUNTER: AtomicUsize = AtomicUsize::new(0);
         }
@@ -179,6 +240,7 @@ impl<L: Language> CoreBoundVar<L> {
             debruijn: None,
             var_index,
             kind,
+            name,
         }
     }
 }
@@ -214,6 +276,7 @@ impl<L: Language, T: CoreFold<L>> CoreFold<L> for CoreBinder<L, T> {
 
         CoreBinder {
             kinds: self.kinds.clone(),
+            names: self.names.clone(),
             term,
         }
     }
@@ -222,6 +285,7 @@ impl<L: Language, T: CoreFold<L>> CoreFold<L> for CoreBinder<L, T> {
         let term = self.term.shift_in();
         CoreBinder {
             kinds: self.kinds.clone(),
+            names: self.names.clone(),
             term,
         }
     }
@@ -234,9 +298,10 @@ where
     T: Upcast<U>,
 {
     fn upcast_from(term: CoreBinder<L, T>) -> Self {
-        let CoreBinder { kinds, term } = term;
+        let CoreBinder { kinds, names, term } = term;
         CoreBinder {
             kinds,
+            names,
             term: term.upcast(),
         }
     }
@@ -247,15 +312,43 @@ where
     T: DowncastFrom<U>,
 {
     fn downcast_to(&self) -> Option<CoreBinder<L, T>> {
-        let CoreBinder { kinds, term } = self;
+        let CoreBinder { kinds, names, term } = self;
         let term = term.downcast()?;
         Some(CoreBinder {
             kinds: kinds.clone(),
+            names: names.clone(),
             term,
         })
     }
 }
 
+impl<L: Language, T: PartialEq> PartialEq for CoreBinder<L, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.kinds == other.kinds && self.term == other.term
+    }
+}
+
+impl<L: Language, T: Eq> Eq for CoreBinder<L, T> {}
+
+impl<L: Language, T: Ord> PartialOrd for CoreBinder<L, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<L: Language, T: Ord> Ord for CoreBinder<L, T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.kinds, &self.term).cmp(&(&other.kinds, &other.term))
+    }
+}
+
+impl<L: Language, T: Hash> Hash for CoreBinder<L, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kinds.hash(state);
+        self.term.hash(state);
+    }
+}
+
 impl<L: Language, T> std::fmt::Debug for CoreBinder<L, T>
 where
     T: std::fmt::Debug,
@@ -263,15 +356,54 @@ where
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if !self.kinds.is_empty() {
             write!(f, "{}", L::BINDING_OPEN)?;
-            for (kind, i) in self.kinds.iter().zip(0..) {
+            for ((kind, name), i) in self.kinds.iter().zip(&self.names).zip(0..) {
                 if i > 0 {
                     write!(f, ", ")?;
                 }
                 write!(f, "{:?}", kind)?;
+                if let Some(name) = name.as_str() {
+                    write!(f, " {name}")?;
+                }
             }
             write!(f, "{} ", L::BINDING_CLOSE)?;
         }
+        let _guard = BinderNamesGuard::push(self.names.clone());
         write!(f, "{:?}", &self.term)?;
         Ok(())
     }
 }
+
+thread_local! {
+    /// The names of the binders enclosing the term currently being debug-printed,
+    /// innermost last.
+    static BINDER_NAMES: RefCell<Vec<Vec<VarName>>> = RefCell::new(vec![]);
+}
+
+/// Keeps the names of a binder on `BINDER_NAMES` while its term is being printed.
+struct BinderNamesGuard;
+
+impl BinderNamesGuard {
+    fn push(names: Vec<VarName>) -> Self {
+        BINDER_NAMES.with(|stack| stack.borrow_mut().push(names));
+        BinderNamesGuard
+    }
+}
+
+impl Drop for BinderNamesGuard {
+    fn drop(&mut self) {
+        BINDER_NAMES.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+/// The name of the variable with the given index in the `debruijn`-th enclosing binder
+/// of the term currently being debug-printed, if any.
+pub(crate) fn bound_var_name(debruijn: DebruijnIndex, var_index: VarIndex) -> VarName {
+    BINDER_NAMES.with(|stack| {
+        let stack = stack.borrow();
+        stack
+            .len()
+            .checked_sub(debruijn.index + 1)
+            .and_then(|i| stack[i].get(var_index.index).copied())
+            .unwrap_or(VarName::NONE)
+    })
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use crate::collections::Set;
+use crate::interner::{Internable, Interned};
 
 pub trait To {
     fn to<T>(&self) -> T
@@ -142,6 +143,16 @@ where
     }
 }
 
+impl<T, U> DowncastFrom<Interned<U>> for Interned<T>
+where
+    T: DowncastFrom<U> + Internable,
+{
+    fn downcast_from(u: &Interned<U>) -> Option<Self> {
+        let t: T = T::downcast_from(u)?;
+        Some(Interned::new(t))
+    }
+}
+
 impl<A, B, A1, B1> DowncastFrom<(A1, B1)> for (A, B)
 where
     A: DowncastFrom<A1>,
@@ -251,6 +262,17 @@ where
     }
 }
 
+impl<T: Clone, U> UpcastFrom<Interned<T>> for Interned<U>
+where
+    T: Upcast<U>,
+    U: Internable,
+{
+    fn upcast_from(term: Interned<T>) -> Self {
+        let term: &T = &term;
+        Interned::new(term.to())
+    }
+}
+
 impl<A, B, A1, B1> UpcastFrom<(A1, B1)> for (A, B)
 where
     A1: Upcast<A>,
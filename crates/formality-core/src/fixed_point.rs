@@ -3,7 +3,9 @@ use std::fmt::Debug;
 use std::hash::Hash;
 use std::thread::LocalKey;
 
+mod cache;
 mod stack;
+pub use cache::{cache_stats, clear_cache, uncacheable, without_cache, CacheStats};
 pub use stack::FixedPointStack;
 
 pub fn fixed_point<Input, Output>(
@@ -52,14 +54,29 @@ where
     TracingSpan: Fn(&Input) -> tracing::Span,
 {
     fn apply(&self, input: Input) -> Output {
-        if let Some(r) = self.with_stack(|stack| stack.search(&input)) {
+        if cache::enabled() {
+            if let Some(r) = self.with_stack(|stack| stack.cached(&input)) {
+                tracing::debug!("cached result for {:?}, yielding {:?}", input, r);
+                cache::record_hit();
+                return r;
+            }
+        }
+
+        if let Some((depth, r)) = self.with_stack(|stack| stack.search(&input)) {
             tracing::debug!("recursive call to {:?}, yielding {:?}", input, r);
+            cache::observed_in_progress(depth);
             return r;
         }
 
+        let depth = cache::enter();
+
+        // Leave the computation before we return, even if `next_value` panics,
+        // so that the enclosing computations see the right depth.
+        final_fn::final_fn!(cache::leave(depth));
+
         self.with_stack(|stack| {
             let default_value = (self.default_value)(&input);
-            stack.push(&input, default_value);
+            stack.push(&input, default_value, depth);
         });
 
         loop {
@@ -74,7 +91,15 @@ where
             }
         }
 
-        self.with_stack(|stack| stack.pop(&input))
+        let output = self.with_stack(|stack| stack.pop(&input));
+
+        // The output can only be reused if it did not depend on the (provisional)
+        // output of some computation that was still in progress.
+        if cache::exit(depth) {
+            self.with_stack(|stack| stack.insert_cached(input, output.clone()));
+        }
+
+        output
     }
 
     fn with_stack<R>(&self, f: impl FnOnce(&mut FixedPointStack<Input, Output>) -> R) -> R {
@@ -0,0 +1,162 @@
+//! Bookkeeping for the cache of completed fixed-point computations.
+//!
+//! Each judgment keeps its own cache (see [`FixedPointStack`](`super::FixedPointStack`)),
+//! but deciding whether an output may be cached requires knowing about all in-progress
+//! computations on this thread, since cycles can span several judgments.
+//! An output that observed the provisional output of a computation lower on the stack
+//! (a cycle head) may change once that computation reaches its fixed point, so it is
+//! not cached; the cycle head itself is cached once it completes.
+//!
+//! Outputs are cached under the judgment's input as given. Judgments that want
+//! equivalent inputs to share entries should normalize their inputs first: for example,
+//! `prove` in `formality-prove` minimizes the environment and renumbers the variables
+//! before proving the goal. We deliberately do not go further and erase the names of
+//! variables, as the names appear in the outputs and in error messages.
+
+use std::cell::{Cell, RefCell};
+
+/// Statistics about the use of the cache on the current thread.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of computations answered from the cache.
+    pub hits: u64,
+
+    /// Number of computations that were evaluated and whose output was cached.
+    pub misses: u64,
+
+    /// Number of computations that were evaluated but whose output could not be cached
+    /// because it depended on a computation that was still in progress.
+    pub provisional: u64,
+}
+
+impl CacheStats {
+    /// Fraction of computations that were answered from the cache.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses + self.provisional;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+impl std::fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses, {} provisional ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.provisional,
+            self.hit_rate() * 100.0,
+        )
+    }
+}
+
+thread_local! {
+    /// For each in-progress computation (innermost last), the smallest depth of an
+    /// in-progress computation whose output it has observed, or `usize::MAX` if none.
+    /// Depths start at 1; a dependency of 0 means that the output depends on something
+    /// outside of any computation (see [`uncacheable`]).
+    static ACTIVE: RefCell<Vec<usize>> = const { RefCell::new(vec![]) };
+
+    /// Incremented by [`clear_cache`]; caches from older generations are discarded.
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+
+    /// Nonzero while inside [`without_cache`].
+    static DISABLED: Cell<usize> = const { Cell::new(0) };
+
+    static STATS: Cell<CacheStats> = Cell::new(CacheStats::default());
+}
+
+/// Returns the cache statistics for the current thread.
+pub fn cache_stats() -> CacheStats {
+    STATS.with(|s| s.get())
+}
+
+/// Discards all cached outputs on the current thread and resets the statistics.
+pub fn clear_cache() {
+    GENERATION.with(|g| g.set(g.get() + 1));
+    STATS.with(|s| s.set(CacheStats::default()));
+}
+
+/// Runs `op` without answering computations from the cache, so that
+/// every judgment is evaluated (e.g., to record how its outputs were derived).
+pub fn without_cache<R>(op: impl FnOnce() -> R) -> R {
+    DISABLED.with(|d| d.set(d.get() + 1));
+    final_fn::final_fn!(DISABLED.with(|d| d.set(d.get() - 1)));
+    op()
+}
+
+pub(super) fn enabled() -> bool {
+    DISABLED.with(|d| d.get() == 0)
+}
+
+pub(super) fn generation() -> u64 {
+    GENERATION.with(|g| g.get())
+}
+
+pub(super) fn record_hit() {
+    update_stats(|s| s.hits += 1);
+}
+
+/// Marks the output of the innermost computation, and hence of every computation
+/// enclosing it, as depending on more than its inputs (e.g., on how deeply nested it is),
+/// so that none of them are cached.
+pub fn uncacheable() {
+    observed_in_progress(0)
+}
+
+/// Starts a new computation, returning its depth.
+pub(super) fn enter() -> usize {
+    ACTIVE.with(|a| {
+        let mut a = a.borrow_mut();
+        a.push(usize::MAX);
+        a.len()
+    })
+}
+
+/// The innermost computation observed the output of the in-progress computation at `depth`.
+pub(super) fn observed_in_progress(depth: usize) {
+    ACTIVE.with(|a| {
+        if let Some(dependency) = a.borrow_mut().last_mut() {
+            *dependency = (*dependency).min(depth);
+        }
+    })
+}
+
+/// Completes the computation at `depth`, which must be the innermost one.
+/// Returns true if its output is final and can be cached.
+pub(super) fn exit(depth: usize) -> bool {
+    ACTIVE.with(|a| {
+        let mut a = a.borrow_mut();
+        assert_eq!(a.len(), depth);
+        let dependency = a.pop().unwrap();
+        if dependency < depth {
+            // The parent (transitively) depends on the same in-progress computation.
+            if let Some(parent) = a.last_mut() {
+                *parent = (*parent).min(dependency);
+            }
+            update_stats(|s| s.provisional += 1);
+            false
+        } else {
+            update_stats(|s| s.misses += 1);
+            true
+        }
+    })
+}
+
+/// Discards the computation at `depth`, and any computations nested within it,
+/// if they have not exited (e.g., because one of them panicked).
+pub(super) fn leave(depth: usize) {
+    ACTIVE.with(|a| a.borrow_mut().truncate(depth - 1))
+}
+
+fn update_stats(op: impl FnOnce(&mut CacheStats)) {
+    STATS.with(|s| {
+        let mut stats = s.get();
+        op(&mut stats);
+        s.set(stats);
+    })
+}
@@ -1,13 +1,24 @@
-use super::Value;
+use std::collections::HashMap;
+
+use super::{cache, Value};
 
 pub struct FixedPointStack<Input, Output> {
     entries: Vec<StackEntry<Input, Output>>,
+
+    /// Outputs of completed computations that did not depend on any
+    /// computation that was still in progress.
+    cache: HashMap<Input, Output>,
+
+    /// The [`cache::generation`] that `cache` belongs to.
+    cache_generation: u64,
 }
 
 impl<Input, Output> Default for FixedPointStack<Input, Output> {
     fn default() -> Self {
         Self {
             entries: Default::default(),
+            cache: Default::default(),
+            cache_generation: cache::generation(),
         }
     }
 }
@@ -22,6 +33,10 @@ struct StackEntry<Input, Output> {
     /// Initially false; set to true when the outputs of this rule
     /// are observed while it is being evaluated.
     has_dependents: bool,
+
+    /// Depth of this computation among all in-progress fixed-point computations
+    /// (across all judgments), used to track which outputs can be cached.
+    depth: usize,
 }
 
 impl<Input, Output> FixedPointStack<Input, Output>
@@ -38,18 +53,18 @@ where
 
     /// Search backwards through the stack, looking for the given input.
     ///
-    /// If it is found, return `Some` with the current outputs, and mark it
+    /// If it is found, return `Some` with its depth and current outputs, and mark it
     /// as needing fixed point iteration.
     ///
     /// If not, return `None`.
     ///
     /// The fixed-point mark is returned when the stack is [popped](`Self::pop`) and is used
     /// as part of the fixed point algorithm.
-    pub fn search(&mut self, input: &Input) -> Option<Output> {
+    pub fn search(&mut self, input: &Input) -> Option<(usize, Output)> {
         for entry in &mut self.entries {
             if entry.input == *input {
                 entry.has_dependents = true;
-                return Some(entry.output.clone());
+                return Some((entry.depth, entry.output.clone()));
             }
         }
 
@@ -58,16 +73,37 @@ where
 
     /// Push an entry onto the stack, indicating it is currently being evaluated.
     /// There must not already be an entry for `input`.
-    pub fn push(&mut self, input: &Input, output: Output) {
+    pub fn push(&mut self, input: &Input, output: Output, depth: usize) {
         assert!(self.search(input).is_none());
 
         self.entries.push(StackEntry {
             input: input.clone(),
             output,
             has_dependents: false,
+            depth,
         });
     }
 
+    /// Returns the cached output for `input`, if any.
+    pub fn cached(&mut self, input: &Input) -> Option<Output> {
+        self.discard_stale_cache();
+        self.cache.get(input).cloned()
+    }
+
+    /// Caches the final output for `input`.
+    pub fn insert_cached(&mut self, input: Input, output: Output) {
+        self.discard_stale_cache();
+        self.cache.insert(input, output);
+    }
+
+    fn discard_stale_cache(&mut self) {
+        let generation = cache::generation();
+        if self.cache_generation != generation {
+            self.cache.clear();
+            self.cache_generation = generation;
+        }
+    }
+
     /// Add outputs to the top-most stack entry, which must be for `input`.
     /// Returns true if another iteration is needed before reaching a fixed point.
     pub fn update_output(&mut self, input: &Input, output: Output) -> bool {
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use crate::{
     cast::Upcast,
     collections::Set,
+    interner::{Internable, Interned},
     language::{CoreParameter, HasKind, Language},
     variable::CoreVariable,
     visit::CoreVisit,
@@ -64,6 +65,13 @@ impl<L: Language, T: CoreFold<L>> CoreFold<L> for Arc<T> {
     }
 }
 
+impl<L: Language, T: CoreFold<L> + Internable> CoreFold<L> for Interned<T> {
+    fn substitute(&self, substitution_fn: SubstitutionFn<'_, L>) -> Self {
+        let data = T::substitute(self, substitution_fn);
+        Interned::new(data)
+    }
+}
+
 impl<L: Language> CoreFold<L> for usize {
     fn substitute(&self, _substitution_fn: SubstitutionFn<'_, L>) -> Self {
         *self
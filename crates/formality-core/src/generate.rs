@@ -0,0 +1,279 @@
+//! Generating random terms, e.g. to fuzz the parser, printer and folder of a language.
+//!
+//! Typically [`CoreGenerate`] is auto-generated by the `#[term]` procedural macro.
+//! Generated terms are well-kinded: a `#[variable]` variant is only ever
+//! produced from a variable that is in scope, either one bound by an enclosing
+//! binder or one of the free variables given to the [`Generator`], and only
+//! if that variable has the right kind.
+
+use std::sync::Arc;
+
+use crate::{
+    binder::CoreBinder,
+    cast::{DowncastFrom, Upcast},
+    collections::Set,
+    fold::CoreFold,
+    interner::{Internable, Interned},
+    language::{CoreKind, CoreParameter, Language},
+    term::CoreTerm,
+    variable::{CoreBoundVar, CoreUniversalVar, CoreVariable, VarIndex, VarName},
+};
+
+/// Trait for generating a random [`Term<L>`](`crate::term::Term`).
+pub trait CoreGenerate<L: Language>: Sized {
+    /// Generate a random instance of this type, or `None` if none can be
+    /// generated within the remaining budget (or with the variables in scope).
+    fn generate(g: &mut Generator<L>) -> Option<Self>;
+}
+
+/// Source of randomness and bookkeeping for [`CoreGenerate`].
+///
+/// The size of the generated terms is bounded by a *budget*: every term built
+/// from other terms (e.g., a struct with fields, or an enum variant with fields
+/// that is not a `#[cast]`) spends one unit of it. Once the budget is exhausted,
+/// only leaves (keywords, identifiers, numbers, variables, empty lists) are generated.
+pub struct Generator<L: Language> {
+    /// State of the pseudo-random number generator (splitmix64).
+    state: u64,
+
+    /// Number of compound terms that may still be generated.
+    budget: usize,
+
+    /// Variables that may appear in the term, innermost binder last.
+    in_scope: Vec<CoreVariable<L>>,
+}
+
+/// Identifiers that generated terms use, unless they are keywords of the language.
+/// There are few of them so that the same name is often used twice.
+const IDENTIFIERS: &[&str] = &["a", "b", "c", "x", "y", "z"];
+
+/// One of the alternatives given to [`Generator::one_of`].
+pub type Alternative<'a, L, T> = &'a dyn Fn(&mut Generator<L>) -> Option<T>;
+
+impl<L: Language> Generator<L> {
+    /// Creates a generator that produces the same terms every time for the same `seed`.
+    pub fn new(seed: u64, budget: usize) -> Self {
+        Generator {
+            state: seed,
+            budget,
+            in_scope: vec![],
+        }
+    }
+
+    /// Puts free universal variables of the given kinds in scope.
+    pub fn with_free_variables(mut self, kinds: impl IntoIterator<Item = CoreKind<L>>) -> Self {
+        for (kind, index) in kinds.into_iter().zip(0..) {
+            let var = CoreUniversalVar {
+                kind,
+                var_index: VarIndex { index },
+                name: VarName::NONE,
+            };
+            self.in_scope.push(var.upcast());
+        }
+        self
+    }
+
+    /// Generate a `T`.
+    pub fn generate<T: CoreGenerate<L>>(&mut self) -> Option<T> {
+        T::generate(self)
+    }
+
+    /// A random number in `0..n`, which must not be empty.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0);
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        (z % n as u64) as usize
+    }
+
+    /// Spends one unit of the budget, returning false if it is exhausted.
+    pub fn spend(&mut self) -> bool {
+        match self.budget.checked_sub(1) {
+            Some(budget) => {
+                self.budget = budget;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Tries the alternatives in a random order, returning the first term generated.
+    pub fn one_of<T>(&mut self, alternatives: &[Alternative<'_, L, T>]) -> Option<T> {
+        let mut order: Vec<usize> = (0..alternatives.len()).collect();
+        for i in (1..order.len()).rev() {
+            let j = self.below(i + 1);
+            order.swap(i, j);
+        }
+        order.into_iter().find_map(|i| alternatives[i](self))
+    }
+
+    /// A random variable in scope that can be downcast to `R`
+    /// (i.e., one of the right kind), if there is any.
+    pub fn variable<R>(&mut self) -> Option<R>
+    where
+        R: DowncastFrom<CoreParameter<L>>,
+    {
+        let mut candidates: Vec<R> = self
+            .in_scope
+            .iter()
+            .filter_map(|v| {
+                let parameter: CoreParameter<L> = v.upcast();
+                R::downcast_from(&parameter)
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let index = self.below(candidates.len());
+        Some(candidates.swap_remove(index))
+    }
+
+    /// A random identifier that is not a keyword.
+    pub fn identifier(&mut self) -> String {
+        let identifiers: Vec<&str> = IDENTIFIERS
+            .iter()
+            .copied()
+            .filter(|id| !L::KEYWORDS.contains(id))
+            .collect();
+        identifiers[self.below(identifiers.len())].to_string()
+    }
+
+    /// A list of up to three `T`s (fewer if the budget runs out).
+    pub fn list<T: CoreGenerate<L>>(&mut self) -> Vec<T> {
+        let len = self.below(4);
+        (0..len).map_while(|_| T::generate(self)).collect()
+    }
+
+    /// Generate a binder over fresh variables of the given kinds, using `op`
+    /// to generate the bound term while those variables are in scope.
+    pub fn binder<T: CoreFold<L>>(
+        &mut self,
+        kinds: Vec<CoreKind<L>>,
+        op: impl FnOnce(&mut Self) -> Option<T>,
+    ) -> Option<CoreBinder<L, T>> {
+        let bound_vars: Vec<CoreBoundVar<L>> = kinds.into_iter().map(CoreBoundVar::fresh).collect();
+        let len = self.in_scope.len();
+        self.in_scope
+            .extend(bound_vars.iter().map(|&v| CoreVariable::BoundVar(v)));
+        let term = op(self);
+        self.in_scope.truncate(len);
+        Some(CoreBinder::new(bound_vars, term?))
+    }
+
+    /// Up to two random kinds, for the variables of a binder.
+    pub fn kinds(&mut self) -> Vec<CoreKind<L>> {
+        let len = self.below(3);
+        (0..len)
+            .map_while(|_| CoreKind::<L>::generate(self))
+            .collect()
+    }
+}
+
+/// Panics unless the `CoreFold` and `CoreVisit` impls of `term` agree on its
+/// free variables, and `term` has no bound variables that escape their binders.
+#[track_caller]
+pub fn core_assert_fold_visit_agree<L, T>(term: &T)
+where
+    L: Language,
+    T: CoreTerm<L>,
+{
+    let visited: Set<CoreVariable<L>> = term.free_variables().into_iter().collect();
+
+    let mut folded: Set<CoreVariable<L>> = Set::new();
+    let unchanged = term.substitute(&mut |v| {
+        folded.insert(v);
+        None
+    });
+    assert_eq!(
+        visited, folded,
+        "free variables of `{term:?}` differ between visit (left) and fold (right)"
+    );
+    assert_eq!(&unchanged, term, "empty substitution changed `{term:?}`");
+
+    assert_eq!(
+        &term.shift_in(),
+        term,
+        "shifting `{term:?}` in changed it, so it has bound variables that escape their binders"
+    );
+}
+
+impl<L: Language, T: CoreGenerate<L>> CoreGenerate<L> for Vec<T> {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        Some(g.list())
+    }
+}
+
+impl<L: Language, T: CoreGenerate<L> + Ord> CoreGenerate<L> for Set<T> {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        Some(g.list::<T>().into_iter().collect())
+    }
+}
+
+impl<L: Language, T: CoreGenerate<L>> CoreGenerate<L> for Option<T> {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        match g.below(2) {
+            0 => Some(None),
+            _ => Some(T::generate(g)),
+        }
+    }
+}
+
+impl<L: Language, T: CoreGenerate<L>> CoreGenerate<L> for Arc<T> {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        T::generate(g).map(Arc::new)
+    }
+}
+
+impl<L: Language, T: CoreGenerate<L> + Internable> CoreGenerate<L> for Interned<T> {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        T::generate(g).map(Interned::new)
+    }
+}
+
+impl<L: Language, T: CoreTerm<L>> CoreGenerate<L> for CoreBinder<L, T> {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        let kinds = g.kinds();
+        g.binder(kinds, T::generate)
+    }
+}
+
+impl<L: Language> CoreGenerate<L> for usize {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        Some(g.below(100))
+    }
+}
+
+impl<L: Language> CoreGenerate<L> for u32 {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        Some(g.below(100) as u32)
+    }
+}
+
+impl<L: Language> CoreGenerate<L> for u64 {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        Some(g.below(100) as u64)
+    }
+}
+
+impl<L: Language> CoreGenerate<L> for () {
+    fn generate(_g: &mut Generator<L>) -> Option<Self> {
+        Some(())
+    }
+}
+
+impl<L: Language, A: CoreGenerate<L>, B: CoreGenerate<L>> CoreGenerate<L> for (A, B) {
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        Some((A::generate(g)?, B::generate(g)?))
+    }
+}
+
+impl<L: Language, A: CoreGenerate<L>, B: CoreGenerate<L>, C: CoreGenerate<L>> CoreGenerate<L>
+    for (A, B, C)
+{
+    fn generate(g: &mut Generator<L>) -> Option<Self> {
+        Some((A::generate(g)?, B::generate(g)?, C::generate(g)?))
+    }
+}
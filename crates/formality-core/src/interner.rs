@@ -0,0 +1,161 @@
+//! Hash-consing of term data.
+//!
+//! An [`Interned<T>`] is a pointer to a value of type `T` that was stored in the
+//! global [`Interner`] for `T`. Structurally equal values are only stored once, so
+//! interned values are compared and hashed by pointer. They are ordered structurally
+//! (with a fast path for identical pointers) so that the order of sets of terms, and
+//! hence debug output, does not depend on allocation order.
+//!
+//! Interned values are never freed.
+//!
+//! To make a type internable, implement [`Internable`] with the [`internable!`](`crate::internable`) macro.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Mutex, OnceLock};
+
+/// Types that can be [`Interned`].
+pub trait Internable: Hash + Eq + Ord + Send + Sync + Sized + 'static {
+    /// The interner that stores values of this type.
+    fn interner() -> &'static Interner<Self>;
+}
+
+/// Implements [`Internable`] for a type, declaring a global interner for it.
+#[macro_export]
+macro_rules! internable {
+    ($t:ty) => {
+        impl $crate::interner::Internable for $t {
+            fn interner() -> &'static $crate::interner::Interner<Self> {
+                static INTERNER: $crate::interner::Interner<$t> = $crate::interner::Interner::new();
+                &INTERNER
+            }
+        }
+    };
+}
+
+/// The set of all interned values of some type.
+pub struct Interner<T: 'static> {
+    values: OnceLock<Mutex<HashSet<&'static T>>>,
+}
+
+impl<T: Internable> Interner<T> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Interner {
+            values: OnceLock::new(),
+        }
+    }
+
+    /// Returns the stored value equal to `data`, storing it if needed.
+    fn intern(&self, data: T) -> &'static T {
+        let mut values = self.values.get_or_init(Default::default).lock().unwrap();
+        if let Some(value) = values.get(&data) {
+            return value;
+        }
+        let value: &'static T = Box::leak(Box::new(data));
+        values.insert(value);
+        value
+    }
+
+    /// Number of distinct values that have been interned.
+    pub fn len(&self) -> usize {
+        self.values
+            .get()
+            .map(|values| values.lock().unwrap().len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A hash-consed `T`; see the [module documentation](`self`).
+pub struct Interned<T: 'static> {
+    data: &'static T,
+}
+
+impl<T: Internable> Interned<T> {
+    pub fn new(data: T) -> Self {
+        Interned {
+            data: T::interner().intern(data),
+        }
+    }
+}
+
+impl<T> Interned<T> {
+    /// True if `a` and `b` are the same interned value (equivalently, if they are equal).
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        std::ptr::eq(a.data, b.data)
+    }
+}
+
+impl<T> Deref for Interned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.data
+    }
+}
+
+impl<T> Clone for Interned<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Interned<T> {}
+
+impl<T> PartialEq for Interned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Interned::ptr_eq(self, other)
+    }
+}
+
+impl<T> Eq for Interned<T> {}
+
+impl<T> Hash for Interned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self.data, state)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Interned<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.serialize(serializer)
+    }
+}
+
+/// Deserialized values are interned, so they are shared with equal values already in use.
+#[cfg(feature = "serde")]
+impl<'de, T: Internable + serde::Deserialize<'de>> serde::Deserialize<'de> for Interned<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Interned::new)
+    }
+}
+
+impl<T: Ord> PartialOrd for Interned<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Interned<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if Interned::ptr_eq(self, other) {
+            Ordering::Equal
+        } else {
+            self.data.cmp(other.data)
+        }
+    }
+}
+
+impl<T: Debug> Debug for Interned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.data, f)
+    }
+}
@@ -2,7 +2,12 @@ use std::{cell::RefCell, collections::BTreeSet};
 
 use crate::fixed_point::FixedPointStack;
 
+pub mod failure;
+pub mod proof_tree;
+mod test_cache;
+mod test_failure;
 mod test_filtered;
+mod test_proof_tree;
 mod test_reachable;
 
 pub type JudgmentStack<J, O> = RefCell<FixedPointStack<J, BTreeSet<O>>>;
@@ -43,15 +48,30 @@ macro_rules! judgment_fn {
                 assert!($assert_expr);
             )*
 
+            // Inputs as recorded in proof trees and failures.
+            let __judgment_inputs = || vec![
+                $((stringify!($debug_input_name), format!("{:?}", $debug_input_name))),*
+            ];
+
             $(
                 // Trivial cases are an (important) optimization that lets
                 // you cut out all the normal rules.
                 if $trivial_expr {
-                    return std::iter::once($trivial_result).collect();
+                    $crate::judgment::proof_tree::enter_judgment(&__judgment_inputs);
+                    let result: $output = $trivial_result;
+                    $crate::judgment::proof_tree::conclude(stringify!($name), "trivial", &result);
+                    $crate::judgment::proof_tree::exit_judgment();
+                    return std::iter::once(result).collect();
                 }
             )*
 
-            $crate::fixed_point::fixed_point::<
+            $crate::judgment::proof_tree::enter_judgment(&__judgment_inputs);
+            $crate::judgment::failure::enter_judgment(&[
+                $((stringify!($debug_input_name), &$debug_input_name as &dyn $crate::judgment::failure::InputValue)),*
+            ]);
+            let __evaluated = std::cell::Cell::new(false);
+
+            let __result = $crate::fixed_point::fixed_point::<
                 __JudgmentStruct,
                 $crate::Set<$output>,
             >(
@@ -80,6 +100,10 @@ macro_rules! judgment_fn {
 
                 // Next value:
                 |input: __JudgmentStruct| {
+                    $crate::judgment::proof_tree::restart_judgment();
+                    $crate::judgment::failure::restart_judgment();
+                    __evaluated.set(true);
+
                     let mut output = $crate::Set::new();
 
                     $crate::push_rules!(
@@ -92,7 +116,12 @@ macro_rules! judgment_fn {
 
                     output
                 },
-            )
+            );
+
+            $crate::judgment::proof_tree::exit_judgment();
+            $crate::judgment::failure::exit_judgment(stringify!($name), __result.is_empty(), __evaluated.get());
+
+            __result
         }
     }
 }
@@ -174,7 +203,7 @@ macro_rules! push_rules {
 
     (@match inputs() patterns() args($judgment_name:ident; $n:literal; $v:expr; $output:expr; $($m:tt)*)) => {
         tracing::trace_span!("matched rule", rule = $n, judgment = stringify!($judgment_name)).in_scope(|| {
-            $crate::push_rules!(@body ($judgment_name, $n, $v, $output) $($m)*);
+            $crate::push_rules!(@first ($judgment_name, $n, $v, $output) $($m)*);
         });
     };
 
@@ -238,16 +267,40 @@ macro_rules! push_rules {
     //     }
     // };
 
+    // `@first` phase: a rule whose first condition is an `if` only applies when it is true,
+    // so when it is false, the rule is not recorded as a failure (unless the condition
+    // invoked judgments that failed). Otherwise, the first condition is processed like the rest.
+
+    (@first $args:tt (if $c:expr) $($m:tt)*) => {
+        let __failure_mark = $crate::judgment::failure::reported_mark();
+        if $c {
+            $crate::push_rules!(@body $args $($m)*);
+        } else {
+            tracing::trace!("failed to match if condition {:?}", stringify!($c));
+            $crate::push_rules!(@fail_guard $args __failure_mark, $crate::judgment::failure::FailedPremise::IfFalse {
+                condition: stringify!($c),
+            });
+        }
+    };
+
+    (@first $args:tt $($m:tt)*) => {
+        $crate::push_rules!(@body $args $($m)*);
+    };
+
     // `@body (v)` phase: processes the conditions, generating the code
     // to evaluate the rule. This is effectively an iterator chain. The
     // expression `v` is carried in from the conclusion and forms the final
     // output of this rule, once all the conditions are evaluated.
 
     (@body $args:tt (if $c:expr) $($m:tt)*) => {
+        let __failure_mark = $crate::judgment::failure::reported_mark();
         if $c {
             $crate::push_rules!(@body $args $($m)*);
         } else {
-            tracing::trace!("failed to match if condition {:?}", stringify!($c))
+            tracing::trace!("failed to match if condition {:?}", stringify!($c));
+            $crate::push_rules!(@fail $args __failure_mark, $crate::judgment::failure::FailedPremise::IfFalse {
+                condition: stringify!($c),
+            });
         }
     };
 
@@ -257,16 +310,35 @@ macro_rules! push_rules {
     };
 
     (@body $args:tt (if let $p:pat = $e:expr) $($m:tt)*) => {
+        let __failure_mark = $crate::judgment::failure::reported_mark();
         if let $p = $e {
             $crate::push_rules!(@body $args $($m)*);
         } else {
-            tracing::trace!("failed to match pattern {:?}", stringify!($p))
+            tracing::trace!("failed to match pattern {:?}", stringify!($p));
+            $crate::push_rules!(@fail $args __failure_mark, $crate::judgment::failure::FailedPremise::IfLetNoMatch {
+                pattern: stringify!($p),
+                expr: stringify!($e),
+            });
         }
     };
 
     (@body $args:tt ($i:expr => $p:pat) $($m:tt)*) => {
-        for $p in $i {
+        let __reported_mark = $crate::judgment::proof_tree::reported_mark();
+        let __failure_mark = $crate::judgment::failure::reported_mark();
+        let __items = $i;
+        let __reported = $crate::judgment::proof_tree::take_reported(__reported_mark);
+        let mut __any_items = false;
+        for __item in __items {
+            __any_items = true;
+            let __premises_mark = $crate::judgment::proof_tree::push_premises(&__reported, &__item);
+            let $p = __item;
             $crate::push_rules!(@body $args $($m)*);
+            $crate::judgment::proof_tree::pop_premises(__premises_mark);
+        }
+        if !__any_items {
+            $crate::push_rules!(@fail $args __failure_mark, $crate::judgment::failure::FailedPremise::NoResults {
+                expr: stringify!($i),
+            });
         }
     };
 
@@ -277,10 +349,30 @@ macro_rules! push_rules {
         }
     };
 
+    // `@fail (args) mark, premise` records that `premise` failed, along with the
+    // failures of the judgments it invoked (those reported since `mark`).
+
+    (@fail ($judgment_name:ident, $rule_name:literal, $v:expr, $output:expr) $mark:ident, $premise:expr) => {
+        $crate::judgment::failure::rule_failed(
+            $rule_name,
+            $premise,
+            $crate::judgment::failure::take_reported($mark),
+        )
+    };
+
+    (@fail_guard ($judgment_name:ident, $rule_name:literal, $v:expr, $output:expr) $mark:ident, $premise:expr) => {
+        $crate::judgment::failure::guard_failed(
+            $rule_name,
+            $premise,
+            $crate::judgment::failure::take_reported($mark),
+        )
+    };
+
     (@body ($judgment_name:ident, $rule_name:literal, $v:expr, $output:expr)) => {
         {
             let result = $crate::Upcast::upcast($v);
             tracing::debug!("produced {:?} from rule {:?} in judgment {:?}", result, $rule_name, stringify!($judgment_name));
+            $crate::judgment::proof_tree::conclude(stringify!($judgment_name), $rule_name, &result);
             $output.insert(result)
         }
     };
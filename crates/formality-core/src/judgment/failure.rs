@@ -0,0 +1,391 @@
+//! Opt-in recording of why judgments failed.
+//!
+//! When recording is enabled (see [`record`]), every judgment defined with
+//! [`judgment_fn!`](`crate::judgment_fn`) that produces no outputs remembers,
+//! for each rule whose conclusion matched its inputs, which premise of the rule failed.
+//! Rules whose first premise is an `if` that was false are left out, as they did not apply.
+//! If the failing premise invoked other judgments that themselves failed, their
+//! failures are recorded too, forming a tree. Successful judgments are pruned.
+//! Recording is per-thread and has no effect on the results of judgments.
+//!
+//! Inputs are recorded as values and only formatted when a failure is displayed,
+//! since most of the recorded failures are pruned or never shown.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fmt::{self, Debug, Display};
+use std::rc::Rc;
+
+use crate::fixed_point;
+
+/// Nested failures beyond this depth are elided when printing.
+const MAX_DISPLAY_DEPTH: usize = 12;
+
+/// A judgment that produced no outputs.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FailedJudgment {
+    /// Name of the judgment, e.g. `prove_wc`.
+    pub judgment: &'static str,
+
+    /// The inputs to the judgment (those listed in its `debug(...)` clause), with their names.
+    /// Inputs passed to [`record_eliding`] are left out.
+    pub inputs: Vec<(&'static str, Input)>,
+
+    /// The rules whose conclusion matched the inputs, with the reason they failed.
+    /// Empty if no rule applied.
+    pub failed_rules: Vec<FailedRule>,
+}
+
+/// A rule that matched but whose premises could not be satisfied.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FailedRule {
+    /// Name of the rule.
+    pub rule: &'static str,
+
+    /// The premise that failed.
+    pub premise: FailedPremise,
+
+    /// Judgments invoked by the premise that failed.
+    pub failed_judgments: Vec<FailedJudgment>,
+}
+
+/// Why a premise failed.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum FailedPremise {
+    /// `(if cond)` where `cond` was false.
+    IfFalse { condition: &'static str },
+
+    /// `(if let pat = expr)` where `expr` did not match `pat`.
+    IfLetNoMatch {
+        pattern: &'static str,
+        expr: &'static str,
+    },
+
+    /// `(expr => pat)` where `expr` yielded no items.
+    NoResults { expr: &'static str },
+}
+
+impl Display for FailedPremise {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailedPremise::IfFalse { condition } => {
+                write!(f, "condition `{}` was false", one_line(condition))
+            }
+            FailedPremise::IfLetNoMatch { pattern, expr } => {
+                write!(
+                    f,
+                    "`{}` did not match `{}`",
+                    one_line(expr),
+                    one_line(pattern)
+                )
+            }
+            FailedPremise::NoResults { expr } => {
+                write!(f, "`{}` had no results", one_line(expr))
+            }
+        }
+    }
+}
+
+/// The value of a judgment input, kept as is until it is displayed.
+#[derive(Clone)]
+pub struct Input(Rc<dyn InputValue>);
+
+/// Implemented for every type that can be a judgment input.
+pub trait InputValue: Debug {
+    fn as_any(&self) -> &dyn Any;
+
+    fn eq_input(&self, other: &dyn InputValue) -> bool;
+
+    fn to_input(&self) -> Input;
+}
+
+impl<T: Debug + Eq + Clone + 'static> InputValue for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_input(&self, other: &dyn InputValue) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+
+    fn to_input(&self) -> Input {
+        Input(Rc::new(self.clone()))
+    }
+}
+
+impl Input {
+    pub fn new(value: impl InputValue + 'static) -> Self {
+        Input(Rc::new(value))
+    }
+}
+
+impl PartialEq for Input {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_input(&*other.0)
+    }
+}
+
+impl Eq for Input {}
+
+impl Debug for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Premises are captured with `stringify!`, which may span several lines.
+fn one_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl FailedJudgment {
+    fn fmt_indented(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        depth: usize,
+        parent_inputs: &[(&'static str, String)],
+        shown: &mut BTreeSet<(&'static str, Vec<(&'static str, String)>)>,
+    ) -> fmt::Result {
+        let all_inputs: Vec<(&'static str, String)> = self
+            .inputs
+            .iter()
+            .map(|(name, value)| (*name, format!("{value:?}")))
+            .collect();
+        let inputs: Vec<String> = all_inputs
+            .iter()
+            .filter(|input| !parent_inputs.contains(input))
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect();
+        let indent = depth * 4;
+        write!(
+            f,
+            "{:indent$}{}({}) failed",
+            "",
+            self.judgment,
+            inputs.join(", ")
+        )?;
+
+        // The same failure often shows up along many paths; only explain it once.
+        if !shown.insert((self.judgment, all_inputs.clone())) {
+            return writeln!(f, " (see above)");
+        }
+
+        if depth >= MAX_DISPLAY_DEPTH {
+            return writeln!(f, " (...)");
+        }
+
+        if self.failed_rules.is_empty() {
+            return writeln!(f, ": no applicable rules");
+        }
+
+        writeln!(f)?;
+        for failed_rule in &self.failed_rules {
+            writeln!(
+                f,
+                "{:indent$}rule {:?}: {}",
+                "",
+                failed_rule.rule,
+                failed_rule.premise,
+                indent = indent + 2,
+            )?;
+            for child in &failed_rule.failed_judgments {
+                child.fmt_indented(f, depth + 1, &all_inputs, shown)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for FailedJudgment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0, &[], &mut BTreeSet::new())
+    }
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+struct Recorder {
+    /// Inputs that are not recorded, see [`record_eliding`].
+    elided: Vec<(&'static str, Input)>,
+
+    frames: Vec<Frame>,
+}
+
+/// One frame per active judgment (or [`capture`]).
+#[derive(Default)]
+struct Frame {
+    /// Inputs of the judgment being evaluated.
+    inputs: Vec<(&'static str, Input)>,
+
+    /// Failures reported by judgments called from this frame that
+    /// have not yet been consumed by a premise.
+    reported: Vec<FailedJudgment>,
+
+    /// Rules that have failed so far.
+    failed_rules: Vec<FailedRule>,
+}
+
+/// Runs `op` with failure recording enabled.
+pub fn record<R>(op: impl FnOnce() -> R) -> R {
+    record_eliding(vec![], op)
+}
+
+/// Like [`record`], but judgment inputs equal to one of `elided` (matching name and value)
+/// are not recorded. Used for inputs that are the same throughout, like the program
+/// declarations, which would only clutter the output.
+pub fn record_eliding<R>(elided: Vec<(&'static str, Input)>, op: impl FnOnce() -> R) -> R {
+    let recorder = Recorder {
+        elided,
+        frames: vec![],
+    };
+    let previous = RECORDER.with(|r| r.borrow_mut().replace(recorder));
+    // Cached outputs would have no record, so evaluate everything.
+    let result = fixed_point::without_cache(op);
+    RECORDER.with(|r| *r.borrow_mut() = previous);
+    result
+}
+
+/// True if failures are currently being recorded.
+pub fn is_recording() -> bool {
+    RECORDER.with(|r| r.borrow().is_some())
+}
+
+/// Runs `op` and returns the failures of the judgments that it calls
+/// (directly, not through other judgments). Returns no failures if recording is not enabled.
+pub fn capture<R>(op: impl FnOnce() -> R) -> (R, Vec<FailedJudgment>) {
+    if !is_recording() {
+        return (op(), vec![]);
+    }
+    with_frames(|frames| frames.push(Frame::default()));
+    let result = op();
+    let frame = with_frames(|frames| frames.pop().unwrap());
+    (result, frame.reported)
+}
+
+fn with_frames<R>(op: impl FnOnce(&mut Vec<Frame>) -> R) -> R {
+    RECORDER.with(|r| {
+        let mut recorder = r.borrow_mut();
+        op(&mut recorder.as_mut().expect("not recording failures").frames)
+    })
+}
+
+// The functions below are used by the code generated by `judgment_fn!`
+// and are no-ops unless recording.
+
+#[doc(hidden)]
+pub fn enter_judgment(inputs: &[(&'static str, &dyn InputValue)]) {
+    RECORDER.with(|r| {
+        let mut recorder = r.borrow_mut();
+        let Some(recorder) = recorder.as_mut() else {
+            return;
+        };
+        let inputs = inputs
+            .iter()
+            .filter(|(name, value)| {
+                !recorder
+                    .elided
+                    .iter()
+                    .any(|(n, v)| n == name && value.eq_input(&*v.0))
+            })
+            .map(|(name, value)| (*name, value.to_input()))
+            .collect();
+        recorder.frames.push(Frame {
+            inputs,
+            ..Frame::default()
+        })
+    })
+}
+
+/// Pops the frame for the current judgment; if it `failed`, reports the failure to the caller.
+/// A judgment whose rules were never `evaluated` (because it was a recursive
+/// call that yielded the current fixed-point value) is not reported.
+#[doc(hidden)]
+pub fn exit_judgment(judgment: &'static str, failed: bool, evaluated: bool) {
+    if is_recording() {
+        let frame = with_frames(|frames| frames.pop().unwrap());
+        if failed && evaluated {
+            let failure = FailedJudgment {
+                judgment,
+                inputs: frame.inputs,
+                failed_rules: frame.failed_rules,
+            };
+            with_frames(|frames| {
+                if let Some(parent) = frames.last_mut() {
+                    parent.reported.push(failure);
+                }
+            })
+        }
+    }
+}
+
+/// Called at the start of each round of fixed-point iteration;
+/// only the failures from the final round are kept.
+#[doc(hidden)]
+pub fn restart_judgment() {
+    if is_recording() {
+        with_frames(|frames| {
+            let frame = frames.last_mut().unwrap();
+            frame.reported.clear();
+            frame.failed_rules.clear();
+        });
+    }
+}
+
+/// Returns the number of reported failures, to be passed to [`take_reported`].
+#[doc(hidden)]
+pub fn reported_mark() -> usize {
+    if is_recording() {
+        with_frames(|frames| frames.last().unwrap().reported.len())
+    } else {
+        0
+    }
+}
+
+/// Takes the failures reported since `mark`.
+#[doc(hidden)]
+pub fn take_reported(mark: usize) -> Vec<FailedJudgment> {
+    if is_recording() {
+        with_frames(|frames| frames.last_mut().unwrap().reported.split_off(mark))
+    } else {
+        vec![]
+    }
+}
+
+/// Records that `premise` of `rule` failed, along with the judgments it invoked that failed.
+#[doc(hidden)]
+pub fn rule_failed(
+    rule: &'static str,
+    premise: FailedPremise,
+    failed_judgments: Vec<FailedJudgment>,
+) {
+    if is_recording() {
+        let failed_rule = FailedRule {
+            rule,
+            premise,
+            failed_judgments,
+        };
+        with_frames(|frames| {
+            let failed_rules = &mut frames.last_mut().unwrap().failed_rules;
+            if !failed_rules.contains(&failed_rule) {
+                failed_rules.push(failed_rule);
+            }
+        })
+    }
+}
+
+/// Like [`rule_failed`], for a rule whose first premise is an `if` that was false.
+/// Such a rule did not apply, which is only worth recording if the condition
+/// invoked judgments that failed.
+#[doc(hidden)]
+pub fn guard_failed(
+    rule: &'static str,
+    premise: FailedPremise,
+    failed_judgments: Vec<FailedJudgment>,
+) {
+    if !failed_judgments.is_empty() {
+        rule_failed(rule, premise, failed_judgments);
+    }
+}
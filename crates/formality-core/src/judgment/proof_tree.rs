@@ -0,0 +1,237 @@
+//! Opt-in recording of derivations ("proof trees") for judgments.
+//!
+//! When recording is enabled (see [`record`]), every judgment defined with
+//! [`judgment_fn!`](`crate::judgment_fn`) remembers, for each output it produces,
+//! which rule produced it and the derivations of the judgments used in the premises.
+//! Recording is per-thread and has no effect on the results of judgments.
+
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Display};
+
+use crate::fixed_point;
+
+/// The derivation of a single output of a judgment.
+///
+/// With the `serde` feature, proof trees can be serialized (but not deserialized,
+/// since judgment and rule names are `&'static str`).
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ProofTree {
+    /// Name of the judgment, e.g. `prove_wc`.
+    pub judgment: &'static str,
+
+    /// The inputs to the judgment (those listed in its `debug(...)` clause), with their names.
+    pub inputs: Vec<(&'static str, String)>,
+
+    /// Name of the rule that produced the output.
+    pub rule: &'static str,
+
+    /// The output that was produced.
+    pub output: String,
+
+    /// Derivations for the judgments in the premises of the rule.
+    pub children: Vec<ProofTree>,
+}
+
+impl ProofTree {
+    fn fmt_indented(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        depth: usize,
+        parent_inputs: &[(&'static str, String)],
+    ) -> fmt::Result {
+        // Inputs that are the same as in the parent (e.g., the decls) are elided
+        // to keep the output readable.
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .filter(|input| !parent_inputs.contains(input))
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect();
+        writeln!(
+            f,
+            "{:indent$}({:?}) {}({}) => {}",
+            "",
+            self.rule,
+            self.judgment,
+            inputs.join(", "),
+            self.output,
+            indent = depth * 4,
+        )?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1, &self.inputs)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ProofTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0, &[])
+    }
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<Vec<Frame>>> = const { RefCell::new(None) };
+}
+
+/// One frame per active judgment (or [`capture`]).
+#[derive(Default)]
+struct Frame {
+    /// Inputs of the judgment being evaluated.
+    inputs: Vec<(&'static str, String)>,
+
+    /// Proof trees reported by judgments called from this frame that
+    /// have not yet been consumed by a premise.
+    reported: Vec<ProofTree>,
+
+    /// Proof trees of the premises along the current path through the rule.
+    premises: Vec<ProofTree>,
+
+    /// Proof trees for the outputs produced so far.
+    outputs: Vec<ProofTree>,
+}
+
+/// Runs `op` with proof tree recording enabled.
+pub fn record<R>(op: impl FnOnce() -> R) -> R {
+    let previous = RECORDER.with(|r| r.borrow_mut().replace(vec![]));
+    // Cached outputs would have no record, so evaluate everything.
+    let result = fixed_point::without_cache(op);
+    RECORDER.with(|r| *r.borrow_mut() = previous);
+    result
+}
+
+/// True if proof trees are currently being recorded.
+pub fn is_recording() -> bool {
+    RECORDER.with(|r| r.borrow().is_some())
+}
+
+/// Runs `op` and returns the proof trees for the outputs of the judgments that it calls
+/// (directly, not through other judgments). Returns no trees if recording is not enabled.
+pub fn capture<R>(op: impl FnOnce() -> R) -> (R, Vec<ProofTree>) {
+    if !is_recording() {
+        return (op(), vec![]);
+    }
+    with_frames(|frames| frames.push(Frame::default()));
+    let result = op();
+    let frame = with_frames(|frames| frames.pop().unwrap());
+    (result, frame.reported)
+}
+
+/// Reports `trees` to the enclosing judgment as if they had been produced by a judgment call.
+/// Used by helper functions that wrap judgments and transform their outputs.
+pub fn report(trees: Vec<ProofTree>) {
+    with_frames(|frames| {
+        if let Some(frame) = frames.last_mut() {
+            frame.reported.extend(trees);
+        }
+    })
+}
+
+fn with_frames<R>(op: impl FnOnce(&mut Vec<Frame>) -> R) -> R {
+    RECORDER.with(|r| op(r.borrow_mut().as_mut().expect("not recording proof trees")))
+}
+
+// The functions below are used by the code generated by `judgment_fn!`
+// and are no-ops unless recording.
+
+#[doc(hidden)]
+pub fn enter_judgment(inputs: impl FnOnce() -> Vec<(&'static str, String)>) {
+    if is_recording() {
+        let inputs = inputs();
+        with_frames(|frames| {
+            frames.push(Frame {
+                inputs,
+                ..Frame::default()
+            })
+        })
+    }
+}
+
+#[doc(hidden)]
+pub fn exit_judgment() {
+    if is_recording() {
+        let frame = with_frames(|frames| frames.pop().unwrap());
+        report(frame.outputs);
+    }
+}
+
+/// Called at the start of each round of fixed-point iteration;
+/// only the derivations from the final round are kept.
+#[doc(hidden)]
+pub fn restart_judgment() {
+    if is_recording() {
+        with_frames(|frames| frames.last_mut().unwrap().outputs.clear());
+    }
+}
+
+/// Returns the number of reported trees, to be passed to [`take_reported`].
+#[doc(hidden)]
+pub fn reported_mark() -> usize {
+    if is_recording() {
+        with_frames(|frames| frames.last().unwrap().reported.len())
+    } else {
+        0
+    }
+}
+
+/// Takes the trees reported since `mark`.
+#[doc(hidden)]
+pub fn take_reported(mark: usize) -> Vec<ProofTree> {
+    if is_recording() {
+        with_frames(|frames| frames.last_mut().unwrap().reported.split_off(mark))
+    } else {
+        vec![]
+    }
+}
+
+/// Adds the derivation(s) of `item` from `trees` to the premises of the current rule,
+/// returning the previous number of premises (to be passed to [`pop_premises`]).
+/// If no tree has `item` as its output (e.g., because `item` was computed from the
+/// outputs of several judgments), all of `trees` are used.
+#[doc(hidden)]
+pub fn push_premises(trees: &[ProofTree], item: &impl Debug) -> usize {
+    if !is_recording() {
+        return 0;
+    }
+    let item = format!("{item:?}");
+    let matching: Vec<ProofTree> = match trees.iter().find(|t| t.output == item) {
+        Some(tree) => vec![tree.clone()],
+        None => trees.to_vec(),
+    };
+    with_frames(|frames| {
+        let premises = &mut frames.last_mut().unwrap().premises;
+        let mark = premises.len();
+        premises.extend(matching);
+        mark
+    })
+}
+
+#[doc(hidden)]
+pub fn pop_premises(mark: usize) {
+    if is_recording() {
+        with_frames(|frames| frames.last_mut().unwrap().premises.truncate(mark))
+    }
+}
+
+/// Records that `rule` produced `output`, with the current premises as children.
+#[doc(hidden)]
+pub fn conclude(judgment: &'static str, rule: &'static str, output: &impl Debug) {
+    if is_recording() {
+        let output = format!("{output:?}");
+        with_frames(|frames| {
+            let frame = frames.last_mut().unwrap();
+            if frame.outputs.iter().any(|t| t.output == output) {
+                return;
+            }
+            let tree = ProofTree {
+                judgment,
+                inputs: frame.inputs.clone(),
+                rule,
+                output,
+                children: frame.premises.clone(),
+            };
+            frame.outputs.push(tree);
+        })
+    }
+}
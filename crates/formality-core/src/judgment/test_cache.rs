@@ -0,0 +1,177 @@
+#![cfg(test)]
+
+use std::cell::Cell;
+use std::sync::Arc;
+
+use crate::fixed_point::{cache_stats, clear_cache, uncacheable, CacheStats};
+use crate::{cast_impl, judgment_fn};
+
+#[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Debug, Hash)]
+struct Graph {
+    edges: Vec<(u32, u32)>,
+}
+
+cast_impl!(Graph);
+
+thread_local! {
+    static EVALUATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+impl Graph {
+    fn successors(&self, n: u32) -> Vec<u32> {
+        EVALUATIONS.with(|e| e.set(e.get() + 1));
+        self.edges
+            .iter()
+            .flat_map(|(a, b)| if *a == n { Some(*b) } else { None })
+            .collect()
+    }
+
+    /// Like `successors`, but the result may not be cached.
+    fn uncacheable_successors(&self, n: u32) -> Vec<u32> {
+        uncacheable();
+        self.successors(n)
+    }
+}
+
+judgment_fn! {
+    fn reachable(
+        graph: Arc<Graph>,
+        from: u32,
+    ) => u32 {
+        debug(from, graph)
+
+        (
+            (graph.successors(start) => s)
+            --------------------------------------- ("edge")
+            (reachable(graph, start) => s)
+        )
+
+        (
+            (graph.successors(a) => b)
+            (reachable(&graph, b) => c)
+            --------------------------------------- ("step")
+            (reachable(graph, a) => c)
+        )
+    }
+}
+
+judgment_fn! {
+    fn successor(
+        graph: Arc<Graph>,
+        from: u32,
+    ) => u32 {
+        debug(from, graph)
+
+        (
+            (graph.uncacheable_successors(a) => b)
+            --------------------------------------- ("edge")
+            (successor(graph, a) => b)
+        )
+    }
+}
+
+fn evaluations() -> usize {
+    EVALUATIONS.with(|e| e.get())
+}
+
+#[test]
+fn acyclic_results_are_cached() {
+    clear_cache();
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1), (1, 2), (2, 3)],
+    });
+
+    let first = reachable(&graph, 0);
+    let before = evaluations();
+    let second = reachable(&graph, 0);
+    assert_eq!(first, second);
+    assert_eq!(evaluations(), before);
+
+    // `reachable(1)` was cached while computing `reachable(0)`.
+    assert_eq!(reachable(&graph, 1).len(), 2);
+    assert_eq!(evaluations(), before);
+
+    expect_test::expect![[r#"
+        CacheStats {
+            hits: 2,
+            misses: 4,
+            provisional: 0,
+        }
+    "#]]
+    .assert_debug_eq(&cache_stats());
+}
+
+#[test]
+fn cycle_members_are_not_cached_until_head_completes() {
+    clear_cache();
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1), (1, 2), (2, 0), (2, 3)],
+    });
+
+    // `reachable(1)` and `reachable(2)` observe the in-progress result of `reachable(0)`
+    // and so are only provisional; `reachable(0)` itself is final.
+    expect_test::expect![[r#"
+        {
+            0,
+            1,
+            2,
+            3,
+        }
+    "#]]
+    .assert_debug_eq(&reachable(&graph, 0));
+    let stats = cache_stats();
+    assert!(stats.provisional > 0);
+
+    // Computing `reachable(1)` from scratch gives the complete answer,
+    // not the partial one observed during the cycle.
+    expect_test::expect![[r#"
+        {
+            0,
+            1,
+            2,
+            3,
+        }
+    "#]]
+    .assert_debug_eq(&reachable(&graph, 1));
+
+    let before = evaluations();
+    reachable(&graph, 0);
+    assert_eq!(evaluations(), before);
+    assert!(cache_stats().hits > stats.hits);
+}
+
+#[test]
+fn clear() {
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1)],
+    });
+    reachable(&graph, 0);
+    clear_cache();
+    assert_eq!(cache_stats(), CacheStats::default());
+
+    let before = evaluations();
+    reachable(&graph, 0);
+    assert!(evaluations() > before);
+}
+
+#[test]
+fn uncacheable_results_are_not_cached() {
+    clear_cache();
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1)],
+    });
+
+    successor(&graph, 0);
+    let before = evaluations();
+    successor(&graph, 0);
+    assert!(evaluations() > before);
+
+    expect_test::expect![[r#"
+        CacheStats {
+            hits: 0,
+            misses: 0,
+            provisional: 2,
+        }
+    "#]]
+    .assert_debug_eq(&cache_stats());
+}
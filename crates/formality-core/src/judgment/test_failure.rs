@@ -0,0 +1,94 @@
+#![cfg(test)]
+
+use std::sync::Arc;
+
+use crate::judgment::failure;
+use crate::{cast_impl, judgment_fn};
+
+#[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Debug, Hash)]
+struct Graph {
+    edges: Vec<(u32, u32)>,
+}
+
+cast_impl!(Graph);
+
+impl Graph {
+    fn successors(&self, n: u32) -> Vec<u32> {
+        self.edges
+            .iter()
+            .flat_map(|(a, b)| if *a == n { Some(*b) } else { None })
+            .collect()
+    }
+}
+
+judgment_fn! {
+    fn path(
+        graph: Arc<Graph>,
+        from: u32,
+        to: u32,
+    ) => () {
+        debug(from, to, graph)
+
+        (
+            (if from == to)
+            --------------------------------------- ("same")
+            (path(_graph, from, to) => ())
+        )
+
+        (
+            (graph.successors(from) => s)
+            (path(&graph, s, to) => ())
+            --------------------------------------- ("step")
+            (path(graph, from, to) => ())
+        )
+    }
+}
+
+#[test]
+fn not_recording() {
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1)],
+    });
+
+    let (result, failures) = failure::capture(|| path(graph, 0, 2));
+    assert!(result.is_empty());
+    assert!(failures.is_empty());
+}
+
+#[test]
+fn success_is_pruned() {
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1), (1, 2)],
+    });
+
+    let (result, failures) = failure::record(|| failure::capture(|| path(graph, 0, 2)));
+    assert_eq!(result.len(), 1);
+    assert!(failures.is_empty());
+}
+
+#[test]
+fn failure_tree() {
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1), (0, 2), (1, 3), (2, 3), (3, 0)],
+    });
+
+    let elided = vec![("graph", failure::Input::new(graph.clone()))];
+    let (result, failures) =
+        failure::record_eliding(elided, || failure::capture(|| path(graph, 0, 4)));
+    assert!(result.is_empty());
+
+    let text: String = failures.iter().map(|f| f.to_string()).collect();
+    expect_test::expect![[r#"
+        path(from: 0, to: 4) failed
+          rule "step": `path(&graph, s, to)` had no results
+            path(from: 1) failed
+              rule "step": `path(&graph, s, to)` had no results
+                path(from: 3) failed
+                  rule "step": `path(&graph, s, to)` had no results
+          rule "step": `path(&graph, s, to)` had no results
+            path(from: 2) failed
+              rule "step": `path(&graph, s, to)` had no results
+                path(from: 3) failed (see above)
+    "#]]
+    .assert_eq(&text);
+}
@@ -0,0 +1,84 @@
+#![cfg(test)]
+
+use std::sync::Arc;
+
+use crate::judgment::proof_tree;
+use crate::{cast_impl, judgment_fn};
+
+#[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Debug, Hash)]
+struct Graph {
+    edges: Vec<(u32, u32)>,
+}
+
+cast_impl!(Graph);
+
+impl Graph {
+    fn successors(&self, n: u32) -> Vec<u32> {
+        self.edges
+            .iter()
+            .flat_map(|(a, b)| if *a == n { Some(*b) } else { None })
+            .collect()
+    }
+}
+
+judgment_fn! {
+    fn reachable(
+        graph: Arc<Graph>,
+        from: u32,
+    ) => u32 {
+        debug(from, graph)
+
+        (
+            (graph.successors(start) => s)
+            --------------------------------------- ("edge")
+            (reachable(graph, start) => s)
+        )
+
+        (
+            (graph.successors(a) => b)
+            (reachable(&graph, b) => c)
+            --------------------------------------- ("step")
+            (reachable(graph, a) => c)
+        )
+    }
+}
+
+#[test]
+fn not_recording() {
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1), (1, 2)],
+    });
+
+    let (result, trees) = proof_tree::capture(|| reachable(graph, 0));
+    assert_eq!(result.len(), 2);
+    assert!(trees.is_empty());
+}
+
+#[test]
+fn derivations() {
+    let graph = Arc::new(Graph {
+        edges: vec![(0, 1), (1, 2), (2, 3)],
+    });
+
+    let (result, trees) = proof_tree::record(|| proof_tree::capture(|| reachable(graph, 0)));
+
+    expect_test::expect![[r#"
+        {
+            1,
+            2,
+            3,
+        }
+    "#]]
+    .assert_debug_eq(&result);
+
+    let text: String = trees.iter().map(|t| t.to_string()).collect();
+    expect_test::expect![[r#"
+        ("edge") reachable(from: 0, graph: Graph { edges: [(0, 1), (1, 2), (2, 3)] }) => 1
+        ("step") reachable(from: 0, graph: Graph { edges: [(0, 1), (1, 2), (2, 3)] }) => 2
+            ("edge") reachable(from: 1) => 2
+        ("step") reachable(from: 0, graph: Graph { edges: [(0, 1), (1, 2), (2, 3)] }) => 3
+            ("step") reachable(from: 1) => 3
+                ("edge") reachable(from: 2) => 3
+    "#]]
+    .assert_eq(&text);
+}
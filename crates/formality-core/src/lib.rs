@@ -14,6 +14,9 @@ pub use tracing::debug;
 pub use tracing::instrument;
 pub use tracing::trace;
 
+#[cfg(feature = "serde")]
+pub use serde;
+
 // Re-export things from formality-macros.
 pub use formality_macros::{fixed_point, term, test, Visit};
 
@@ -27,9 +30,12 @@ mod cast;
 mod collections;
 pub mod fixed_point;
 pub mod fold;
+pub mod generate;
+pub mod interner;
 pub mod judgment;
 pub mod language;
 pub mod parse;
+pub mod print;
 pub mod substitution;
 pub mod term;
 pub mod util;
@@ -41,6 +47,7 @@ pub use collections::Deduplicate;
 pub use collections::Map;
 pub use collections::Set;
 pub use collections::SetExt;
+pub use interner::Interned;
 
 /// Run an action with a tracing log subscriber. The logging level is loaded
 /// from `RUST_LOG`. The `formality_macro::test` expansion uses this to enable logs.
@@ -131,6 +138,14 @@ macro_rules! declare_language {
                 pub trait Parse = $crate::parse::CoreParse<FormalityLang>
             }
 
+            $crate::trait_alias! {
+                pub trait Print = $crate::print::CorePrint<FormalityLang>
+            }
+
+            $crate::trait_alias! {
+                pub trait Generate = $crate::generate::CoreGenerate<FormalityLang>
+            }
+
             $crate::trait_alias! {
                 pub trait Term = $crate::term::CoreTerm<FormalityLang>
             }
@@ -144,6 +159,7 @@ macro_rules! declare_language {
                 pub type BoundVar = $crate::variable::CoreBoundVar<FormalityLang>;
                 pub type DebruijnIndex = $crate::variable::DebruijnIndex;
                 pub type VarIndex = $crate::variable::VarIndex;
+                pub type VarName = $crate::variable::VarName;
                 pub type Binder<T> = $crate::binder::CoreBinder<FormalityLang, T>;
                 pub type Substitution = $crate::substitution::CoreSubstitution<FormalityLang>;
                 pub type VarSubstitution = $crate::substitution::CoreVarSubstitution<FormalityLang>;
@@ -167,6 +183,16 @@ macro_rules! declare_language {
                 term_with(None::<(String, $param)>, text)
             }
 
+            /// Parses `text`, the contents of the file at `path`, as a term with no bindings in scope.
+            /// Returns the term along with the spans of the terms parsed.
+            #[track_caller]
+            pub fn try_term_from_file<T>(path: &str, text: &str) -> $crate::Fallible<(T, $crate::parse::SourceMap)>
+            where
+                T: Parse,
+            {
+                $crate::parse::core_term_from_file::<FormalityLang, T>(path, text)
+            }
+
             /// Parses `text` as a term with the given bindings in scope.
             ///
             /// References to the given string will be replaced with the given parameter
@@ -179,6 +205,51 @@ macro_rules! declare_language {
             {
                 $crate::parse::core_term_with::<FormalityLang, T, B>(bindings, text)
             }
+
+            /// Generates a random `T` using `seed`, with at most `budget` compound terms.
+            /// Returns `None` if there is no such term.
+            pub fn generate<T>(seed: u64, budget: usize) -> Option<T>
+            where
+                T: Generate,
+            {
+                $crate::generate::Generator::new(seed, budget).generate()
+            }
+
+            /// Panics unless the `Fold` and `Visit` impls of `term` agree on its free variables
+            /// and its bound variables are all bound by binders within `term`.
+            #[track_caller]
+            pub fn assert_fold_visit_agree<T>(term: &T)
+            where
+                T: Term,
+            {
+                $crate::generate::core_assert_fold_visit_agree(term)
+            }
+
+            /// The syntax accepted when parsing a `T`, as EBNF.
+            pub fn ebnf<T>() -> String
+            where
+                T: Parse,
+            {
+                $crate::parse::core_ebnf::<FormalityLang, T>()
+            }
+
+            /// Prints `term` as text that parses back to it. Free variables are given
+            /// invented names, which are returned alongside the text.
+            pub fn print<T>(term: &T) -> $crate::print::Printed<FormalityLang>
+            where
+                T: Print,
+            {
+                $crate::print::core_print(term)
+            }
+
+            /// Panics unless printing `term` and parsing the result yields `term` again.
+            #[track_caller]
+            pub fn assert_round_trip<T>(term: &T)
+            where
+                T: Term,
+            {
+                $crate::print::core_assert_round_trip(term)
+            }
         }
     }
 }
@@ -196,6 +267,7 @@ macro_rules! id {
         const _: () = {
             use $crate::fold::{self, CoreFold};
             use $crate::parse::{self, CoreParse};
+            use $crate::print::{CorePrint, Printer};
             use $crate::variable::CoreVariable;
             use $crate::visit::CoreVisit;
 
@@ -248,6 +320,30 @@ macro_rules! id {
                         Ok($n::new(&string))
                     })
                 }
+
+                fn grammar(g: &mut parse::Grammar) -> parse::Symbol {
+                    g.nonterminal::<Self>(stringify!($n), |_| {
+                        vec![parse::Production::new(
+                            stringify!($n),
+                            parse::Precedence::default(),
+                            vec![parse::Symbol::Identifier],
+                        )]
+                    })
+                }
+            }
+
+            impl $crate::generate::CoreGenerate<crate::FormalityLang> for $n {
+                fn generate(
+                    g: &mut $crate::generate::Generator<crate::FormalityLang>,
+                ) -> Option<Self> {
+                    Some($n::new(&g.identifier()))
+                }
+            }
+
+            impl CorePrint<crate::FormalityLang> for $n {
+                fn print(&self, p: &mut Printer<crate::FormalityLang>) {
+                    p.token(&self.data)
+                }
             }
 
             impl std::fmt::Debug for $n {
@@ -255,6 +351,42 @@ macro_rules! id {
                     write!(f, "{}", &self.data)
                 }
             }
+
+            $crate::id_serde!($n);
         };
     };
 }
+
+/// With the `serde` feature, identifiers declared with [`id!`] are serialized as strings.
+#[cfg(feature = "serde")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! id_serde {
+    ($n:ident) => {
+        impl $crate::serde::Serialize for $n {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: $crate::serde::Serializer,
+            {
+                serializer.serialize_str(&self.data)
+            }
+        }
+
+        impl<'de> $crate::serde::Deserialize<'de> for $n {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: $crate::serde::Deserializer<'de>,
+            {
+                let string: String = $crate::serde::Deserialize::deserialize(deserializer)?;
+                Ok($n::new(&string))
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! id_serde {
+    ($n:ident) => {};
+}
@@ -3,10 +3,11 @@ use std::sync::Arc;
 use crate::{
     binder::CoreBinder,
     collections::Set,
+    interner::{Internable, Interned},
     language::{CoreKind, CoreParameter, Language},
     set,
     term::CoreTerm,
-    variable::CoreBoundVar,
+    variable::{CoreBoundVar, VarName},
     Fallible, Upcast, UpcastFrom, Upcasted,
 };
 use std::fmt::Debug;
@@ -21,10 +22,23 @@ pub trait CoreParse<L: Language>: Sized + Debug + Clone + Eq + 'static {
     /// This is intended to be implemented by constructing
     /// a [`Parser`][], typically with `Parser::single_variant_nonterminal`.
     fn parse<'t>(scope: &Scope<L>, text: &'t str) -> ParseResult<'t, Self>;
+
+    /// Describes the syntax accepted by `parse`, adding any nonterminals it
+    /// refers to to `g`. Handwritten parsers must keep this in sync by hand;
+    /// by default, the type is an opaque nonterminal whose syntax is not described.
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.opaque::<Self>()
+    }
 }
 
 mod parser;
-pub use parser::{skip_whitespace, ActiveVariant, Parser, Precedence};
+pub use parser::{skip_whitespace, ActiveVariant, Associativity, Parser, Precedence};
+
+mod grammar;
+pub use grammar::{core_ebnf, Grammar, Nonterminal, Production, Symbol};
+
+mod span;
+pub use span::{record_span, SourceFile, SourceMap, Span};
 
 /// Parses `text` as a term with the given bindings in scope.
 ///
@@ -40,23 +54,41 @@ where
     let scope = Scope::new(bindings.into_iter().map(|b| b.upcast()));
     let parse = match T::parse(&scope, text) {
         Ok(v) => v,
-        Err(errors) => {
-            let mut err = crate::anyhow!("failed to parse {text}");
-            for error in errors {
-                err = err.context(error.text.to_owned()).context(error.message);
-            }
-            return Err(err);
-        }
+        Err(errors) => return Err(ParseFailure::new(text, errors).into()),
     };
     let (value, remainder) = parse.finish();
 
-    if !skip_whitespace(remainder).is_empty() {
-        crate::bail!("extra tokens after parsing {text:?} to {value:?}: {remainder:?}");
+    let remainder = skip_whitespace(remainder);
+    if !remainder.is_empty() {
+        let errors = ParseError::at(remainder, "expected end of input".to_string());
+        return Err(ParseFailure::new(text, errors).into());
     }
 
     Ok(value)
 }
 
+/// Parses `text`, the contents of the file at `path`, as a term with no
+/// bindings in scope. Parse failures are reported relative to `path`.
+/// Returns the term along with the spans of the terms parsed.
+#[track_caller]
+pub fn core_term_from_file<L, T>(path: &str, text: &str) -> Fallible<(T, SourceMap)>
+where
+    T: CoreParse<L>,
+    L: Language,
+{
+    let file = SourceFile {
+        path: path.to_string(),
+        text: text.to_string(),
+    };
+    span::recording(file, |text| {
+        core_term_with::<L, T, (String, CoreParameter<L>)>(None, text)
+    })
+    .map_err(|error| match error.downcast::<ParseFailure>() {
+        Ok(failure) => failure.with_path(path).into(),
+        Err(error) => error,
+    })
+}
+
 /// Record from a successful parse.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SuccessfulParse<'t, T> {
@@ -126,7 +158,11 @@ where
 /// When parse errors are generated, there is just one (e.g., "expected identifier"),
 /// but when there are choice points in the grammar (e.g., when parsing an enum),
 /// those errors can be combined by [`require_unambiguous`].
-#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+///
+/// Errors are compared by their position in the input (i.e., the length of `text`)
+/// and their message, as comparing `text` itself would take time proportional to
+/// the rest of the input.
+#[derive(Clone, Debug)]
 pub struct ParseError<'t> {
     /// Input that triggered the parse error. Some suffix
     /// of the original input.
@@ -136,6 +172,32 @@ pub struct ParseError<'t> {
     pub message: String,
 }
 
+impl ParseError<'_> {
+    fn key(&self) -> (std::cmp::Reverse<usize>, &str) {
+        (std::cmp::Reverse(self.text.len()), &self.message)
+    }
+}
+
+impl PartialEq for ParseError<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for ParseError<'_> {}
+
+impl PartialOrd for ParseError<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ParseError<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
 impl<'t> ParseError<'t> {
     /// Creates a single parse error at the given point. Returns
     /// a set so that it can be wrapped as a [`ParseResult`].
@@ -146,7 +208,14 @@ impl<'t> ParseError<'t> {
 
     /// Offset of this error relative to the starting point `text`
     pub fn offset(&self, text: &str) -> usize {
-        assert!(text.ends_with(self.text));
+        // The error text is normally a suffix of the same input as `text`, which we can
+        // check in constant time (comparing the contents takes time proportional to the
+        // rest of the input, for every error).
+        let is_same_suffix = std::ptr::eq(
+            self.text.as_ptr().wrapping_add(self.text.len()),
+            text.as_ptr().wrapping_add(text.len()),
+        ) && self.text.len() <= text.len();
+        assert!(is_same_suffix || text.ends_with(self.text));
         text.len() - self.text.len()
     }
 
@@ -164,11 +233,105 @@ impl<'t> ParseError<'t> {
     }
 }
 
+/// A failed parse of a complete input, reported at the furthest point
+/// in the input that any variant of the grammar reached (which is
+/// almost always where the mistake is).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseFailure {
+    /// Path of the file being parsed, if known.
+    pub path: Option<String>,
+
+    /// Byte offset of the failure in the input.
+    pub offset: usize,
+
+    /// Line of the failure (1-based).
+    pub line: usize,
+
+    /// Column of the failure, in characters (1-based).
+    pub column: usize,
+
+    /// Text of the line containing the failure.
+    pub source_line: String,
+
+    /// Messages describing what was expected at the failure point,
+    /// e.g., "expected `:`".
+    pub expected: Set<String>,
+}
+
+impl ParseFailure {
+    /// Creates a failure from the `errors` encountered while parsing `input`,
+    /// keeping only those at the furthest point reached.
+    pub fn new(input: &str, errors: Set<ParseError<'_>>) -> Self {
+        let offset = errors.iter().map(|e| e.offset(input)).max().unwrap_or(0);
+        let expected = errors
+            .into_iter()
+            .filter(|e| e.offset(input) == offset)
+            .map(|e| e.message)
+            .collect();
+
+        let (line, column) = line_column(input, offset);
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        ParseFailure {
+            path: None,
+            offset,
+            line,
+            column,
+            source_line: input[line_start..line_end].trim_end().to_string(),
+            expected,
+        }
+    }
+
+    /// Records that the input was read from the file at `path`.
+    pub fn with_path(self, path: &str) -> Self {
+        ParseFailure {
+            path: Some(path.to_string()),
+            ..self
+        }
+    }
+}
+
+impl std::fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.expected.len() {
+            // Handwritten parsers may fail without saying what they expected.
+            0 => writeln!(f, "parse failed")?,
+            1 => writeln!(f, "{}", self.expected.first().unwrap())?,
+            _ => {
+                writeln!(f, "parse failed, one of the following was expected:")?;
+                for message in &self.expected {
+                    writeln!(f, "  - {message}")?;
+                }
+            }
+        }
+
+        let path = self.path.as_deref().unwrap_or("<input>");
+        let line = self.line.to_string();
+        let gutter = " ".repeat(line.len());
+        writeln!(f, "{gutter}--> {path}:{}:{}", self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line} | {}", self.source_line)?;
+        write!(f, "{gutter} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseFailure {}
+
+/// Line and column (both 1-based, column in characters) of the byte `offset` in `text`.
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = text[..offset].matches('\n').count() + 1;
+    let column = text[line_start..offset].chars().count() + 1;
+    (line, column)
+}
+
 pub type ParseResult<'t, T> = Result<SuccessfulParse<'t, T>, Set<ParseError<'t>>>;
 pub type TokenResult<'t, T> = Result<(T, &'t str), Set<ParseError<'t>>>;
 
 /// Tracks the variables in scope at this point in parsing.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Scope<L: Language> {
     bindings: Vec<(String, CoreParameter<L>)>,
 }
@@ -221,6 +384,10 @@ where
             p.delimited_nonterminal('[', false, ']')
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        Symbol::delimited('[', T::grammar(g), ']')
+    }
 }
 
 impl<L, T> CoreParse<L> for Set<T>
@@ -237,6 +404,10 @@ where
             Ok(s)
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        Symbol::delimited('{', T::grammar(g), '}')
+    }
 }
 
 impl<L, T> CoreParse<L> for Option<T>
@@ -247,6 +418,10 @@ where
     fn parse<'t>(scope: &Scope<L>, text: &'t str) -> ParseResult<'t, Self> {
         Parser::single_variant(scope, text, "Option", |p| p.opt_nonterminal())
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        Symbol::optional(T::grammar(g))
+    }
 }
 
 /// Binding grammar is `$kind $name`, e.g., `ty Foo`.
@@ -255,10 +430,14 @@ impl<L: Language> CoreParse<L> for Binding<L> {
         Parser::single_variant(scope, text, "Binding", |p| {
             let kind: CoreKind<L> = p.nonterminal()?;
             let name = p.identifier()?;
-            let bound_var = CoreBoundVar::fresh(kind);
+            let bound_var = CoreBoundVar::fresh_named(kind, VarName::new(&name));
             Ok(Binding { name, bound_var })
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        Symbol::Sequence(vec![CoreKind::<L>::grammar(g), Symbol::Identifier])
+    }
 }
 
 /// Parse a binder: find the names in scope, parse the contents, and then
@@ -289,6 +468,12 @@ where
             Ok(CoreBinder::new(kvis, data))
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        let bindings =
+            Symbol::delimited(L::BINDING_OPEN, Binding::<L>::grammar(g), L::BINDING_CLOSE);
+        Symbol::Sequence(vec![Symbol::optional(bindings), T::grammar(g)])
+    }
 }
 
 impl<L, T> CoreParse<L> for Arc<T>
@@ -299,6 +484,24 @@ where
     fn parse<'t>(scope: &Scope<L>, text: &'t str) -> ParseResult<'t, Self> {
         T::parse(scope, text).map(|success| success.map(Arc::new))
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        T::grammar(g)
+    }
+}
+
+impl<L, T> CoreParse<L> for Interned<T>
+where
+    L: Language,
+    T: CoreParse<L> + Internable,
+{
+    fn parse<'t>(scope: &Scope<L>, text: &'t str) -> ParseResult<'t, Self> {
+        T::parse(scope, text).map(|success| success.map(Interned::new))
+    }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        T::grammar(g)
+    }
 }
 
 impl<L> CoreParse<L> for usize
@@ -308,6 +511,10 @@ where
     fn parse<'t>(scope: &Scope<L>, text: &'t str) -> ParseResult<'t, Self> {
         Parser::single_variant(scope, text, "usize", |p| p.number())
     }
+
+    fn grammar(_g: &mut Grammar) -> Symbol {
+        Symbol::Number
+    }
 }
 
 impl<L> CoreParse<L> for u32
@@ -317,6 +524,10 @@ where
     fn parse<'t>(scope: &Scope<L>, text: &'t str) -> ParseResult<'t, Self> {
         Parser::single_variant(scope, text, "u32", |p| p.number())
     }
+
+    fn grammar(_g: &mut Grammar) -> Symbol {
+        Symbol::Number
+    }
 }
 
 impl<L> CoreParse<L> for u64
@@ -326,6 +537,10 @@ where
     fn parse<'t>(scope: &Scope<L>, text: &'t str) -> ParseResult<'t, Self> {
         Parser::single_variant(scope, text, "u64", |p| p.number())
     }
+
+    fn grammar(_g: &mut Grammar) -> Symbol {
+        Symbol::Number
+    }
 }
 
 impl<L: Language> CoreParse<L> for () {
@@ -336,6 +551,10 @@ impl<L: Language> CoreParse<L> for () {
             Ok(())
         })
     }
+
+    fn grammar(_g: &mut Grammar) -> Symbol {
+        Symbol::Sequence(vec![Symbol::Char('('), Symbol::Char(')')])
+    }
 }
 
 impl<L: Language, A: CoreParse<L>, B: CoreParse<L>> CoreParse<L> for (A, B) {
@@ -350,6 +569,17 @@ impl<L: Language, A: CoreParse<L>, B: CoreParse<L>> CoreParse<L> for (A, B) {
             Ok((a, b))
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        Symbol::Sequence(vec![
+            Symbol::Char('('),
+            A::grammar(g),
+            Symbol::Char(','),
+            B::grammar(g),
+            Symbol::optional(Symbol::Char(',')),
+            Symbol::Char(')'),
+        ])
+    }
 }
 
 impl<L: Language, A: CoreParse<L>, B: CoreParse<L>, C: CoreParse<L>> CoreParse<L> for (A, B, C) {
@@ -366,4 +596,17 @@ impl<L: Language, A: CoreParse<L>, B: CoreParse<L>, C: CoreParse<L>> CoreParse<L
             Ok((a, b, c))
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        Symbol::Sequence(vec![
+            Symbol::Char('('),
+            A::grammar(g),
+            Symbol::Char(','),
+            B::grammar(g),
+            Symbol::Char(','),
+            C::grammar(g),
+            Symbol::optional(Symbol::Char(',')),
+            Symbol::Char(')'),
+        ])
+    }
 }
@@ -0,0 +1,367 @@
+//! A machine-readable description of the syntax accepted by [`CoreParse`][] impls,
+//! which can be rendered as EBNF.
+//!
+//! Each type describes its own syntax via [`CoreParse::grammar`][]. Types with
+//! a parser generated by `#[term]` (and identifiers declared with `id!`) become
+//! named nonterminals of the [`Grammar`]; generic "plumbing" types like `Vec<T>`,
+//! `Option<T>` and binders are described inline wherever they are used.
+//!
+//! The grammar describes which token sequences are accepted, but not the
+//! restrictions the parser places on top of that (e.g., that a type name
+//! cannot be a variable in scope); precedence is recorded but only reported
+//! as a comment.
+
+use std::any::type_name;
+use std::fmt::Write;
+use std::marker::PhantomData;
+
+use crate::{collections::Map, language::Language};
+
+use super::{Associativity, CoreParse, Precedence};
+
+/// The named nonterminals reachable from some starting type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Grammar {
+    /// Nonterminals in the order they were first encountered.
+    nonterminals: Vec<Nonterminal>,
+
+    /// Index in `nonterminals` for each type name.
+    indices: Map<&'static str, usize>,
+}
+
+/// A nonterminal, i.e., a type with a named set of productions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Nonterminal {
+    /// The full type name (see [`std::any::type_name`]), which identifies the nonterminal.
+    pub type_name: &'static str,
+
+    /// Name used when rendering the grammar, e.g. `Ty`.
+    pub name: &'static str,
+
+    /// The alternatives, in the order the parser tries them.
+    /// Empty if the syntax is not described (see [`Grammar::opaque`]).
+    pub productions: Vec<Production>,
+}
+
+/// One alternative for a nonterminal, typically an enum variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Production {
+    pub name: &'static str,
+    pub precedence: Precedence,
+    pub symbols: Vec<Symbol>,
+}
+
+/// A (possibly compound) symbol on the right-hand side of a production.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Symbol {
+    /// A keyword such as `for`.
+    Keyword(&'static str),
+
+    /// A single punctuation character such as `<`.
+    Char(char),
+
+    /// A named nonterminal, identified by its type name.
+    Nonterminal(&'static str),
+
+    /// An identifier that is not a keyword.
+    Identifier,
+
+    /// A sequence of decimal digits.
+    Number,
+
+    /// The name of a variable in scope.
+    Variable,
+
+    /// The symbols one after the other.
+    Sequence(Vec<Symbol>),
+
+    /// The symbol or nothing.
+    Optional(Box<Symbol>),
+
+    /// Zero or more repetitions of the symbol.
+    Many(Box<Symbol>),
+
+    /// Zero or more repetitions of the symbol, separated by commas,
+    /// with an optional trailing comma.
+    Comma(Box<Symbol>),
+}
+
+impl Symbol {
+    /// The symbol or nothing; a symbol that is already optional is returned as is.
+    pub fn optional(symbol: Symbol) -> Self {
+        match symbol {
+            Symbol::Optional(_) => symbol,
+            _ => Symbol::Optional(Box::new(symbol)),
+        }
+    }
+
+    pub fn many(symbol: Symbol) -> Self {
+        Symbol::Many(Box::new(symbol))
+    }
+
+    pub fn comma(symbol: Symbol) -> Self {
+        Symbol::Comma(Box::new(symbol))
+    }
+
+    /// `open`, a comma-separated list of `symbol`, then `close`.
+    pub fn delimited(open: char, symbol: Symbol, close: char) -> Self {
+        Symbol::Sequence(vec![
+            Symbol::Char(open),
+            Symbol::comma(symbol),
+            Symbol::Char(close),
+        ])
+    }
+}
+
+impl Production {
+    pub fn new(name: &'static str, precedence: Precedence, symbols: Vec<Symbol>) -> Self {
+        Production {
+            name,
+            precedence,
+            symbols,
+        }
+    }
+}
+
+impl Grammar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The nonterminals encountered so far, in the order they were first encountered.
+    pub fn nonterminals(&self) -> &[Nonterminal] {
+        &self.nonterminals
+    }
+
+    /// Returns the symbol for the nonterminal `T`, named `name`, adding it to the
+    /// grammar with the productions returned by `op` if it is not already present.
+    /// `T` is registered before `op` runs, so that productions may refer back to `T`.
+    pub fn nonterminal<T>(
+        &mut self,
+        name: &'static str,
+        op: impl FnOnce(&mut Grammar) -> Vec<Production>,
+    ) -> Symbol {
+        let type_name = type_name::<T>();
+        if !self.indices.contains_key(type_name) {
+            let index = self.nonterminals.len();
+            self.indices.insert(type_name, index);
+            self.nonterminals.push(Nonterminal {
+                type_name,
+                name,
+                productions: vec![],
+            });
+            let productions = op(self);
+            self.nonterminals[index].productions = productions;
+        }
+        Symbol::Nonterminal(type_name)
+    }
+
+    /// Returns the symbol for the nonterminal `T`, named after the type, without
+    /// any productions. Used for types whose syntax is not described.
+    pub fn opaque<T>(&mut self) -> Symbol {
+        let type_name = type_name::<T>();
+        let path = type_name.split('<').next().unwrap();
+        let name = path.rsplit("::").next().unwrap();
+        self.nonterminal::<T>(name, |_| vec![])
+    }
+
+    /// Returns the symbol for an element `T` of a list of type `Vec<T>`.
+    /// Used for the fields of `#[term]` types parsed with `$*`, `$,` and friends.
+    pub fn items<L, T>(&mut self, _list: PhantomData<Vec<T>>) -> Symbol
+    where
+        L: Language,
+        T: CoreParse<L>,
+    {
+        T::grammar(self)
+    }
+
+    /// Renders the grammar as EBNF, with `start` as the start symbol and
+    /// `keywords` listed as the words that are not identifiers.
+    pub fn to_ebnf(&self, start: &Symbol, keywords: &[&str]) -> String {
+        let names = self.display_names();
+        let mut out = String::new();
+        let mut lexical = Lexical::default();
+
+        if !matches!(start, Symbol::Nonterminal(_)) {
+            let rhs = render(&names, &mut lexical, start);
+            writeln!(out, "start = {rhs} ;").unwrap();
+            writeln!(out).unwrap();
+        }
+
+        for nonterminal in &self.nonterminals {
+            let name = &names[nonterminal.type_name];
+            let alternatives: Vec<(String, Option<String>)> = nonterminal
+                .productions
+                .iter()
+                .map(|production| {
+                    let rhs = render_sequence(&names, &mut lexical, &production.symbols);
+                    let comment = production_comment(nonterminal, production, &rhs);
+                    (rhs, comment)
+                })
+                .collect();
+
+            match &alternatives[..] {
+                [] => writeln!(out, "{name} = ? not described ? ;").unwrap(),
+                [(rhs, None)] => writeln!(out, "{name} = {rhs} ;").unwrap(),
+                _ => {
+                    writeln!(out, "{name} =").unwrap();
+                    for (index, (rhs, comment)) in alternatives.iter().enumerate() {
+                        let bar = if index == 0 { " " } else { "|" };
+                        match comment {
+                            Some(comment) => writeln!(out, "  {bar} {rhs}  (* {comment} *)"),
+                            None => writeln!(out, "  {bar} {rhs}"),
+                        }
+                        .unwrap();
+                    }
+                    writeln!(out, "  ;").unwrap();
+                }
+            }
+        }
+
+        lexical.write(&mut out, keywords);
+        out
+    }
+
+    /// Short names for the nonterminals, falling back to the full type name
+    /// for nonterminals whose short names collide.
+    fn display_names(&self) -> Map<&'static str, String> {
+        let mut counts: Map<&'static str, usize> = Map::new();
+        for nonterminal in &self.nonterminals {
+            *counts.entry(nonterminal.name).or_default() += 1;
+        }
+        self.nonterminals
+            .iter()
+            .map(|nonterminal| {
+                let name = if counts[nonterminal.name] == 1 {
+                    nonterminal.name.to_string()
+                } else {
+                    nonterminal.type_name.replace("::", "_")
+                };
+                (nonterminal.type_name, name)
+            })
+            .collect()
+    }
+}
+
+/// Parses `T` into a [`Grammar`] and renders it as EBNF.
+pub fn core_ebnf<L, T>() -> String
+where
+    L: Language,
+    T: CoreParse<L>,
+{
+    let mut grammar = Grammar::new();
+    let start = T::grammar(&mut grammar);
+    grammar.to_ebnf(&start, L::KEYWORDS)
+}
+
+/// The comment describing `production` (rendered as `rhs`), if it is one of several
+/// or has a precedence. The name is omitted when it is just `rhs` (e.g., for `#[cast]` variants).
+fn production_comment(
+    nonterminal: &Nonterminal,
+    production: &Production,
+    rhs: &str,
+) -> Option<String> {
+    let precedence = production.precedence;
+    let mut parts = vec![];
+    if nonterminal.productions.len() > 1 && production.name != rhs {
+        parts.push(production.name.to_string());
+    }
+    if precedence != Precedence::default() {
+        let associativity = match precedence.associativity() {
+            Associativity::Left => "left",
+            Associativity::Right => "right",
+            Associativity::None => "non",
+            Associativity::Both => "both",
+        };
+        parts.push(format!(
+            "precedence {}, {associativity}-associative",
+            precedence.level()
+        ));
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+/// Which lexical symbols were used, so that only their definitions are emitted.
+#[derive(Default)]
+struct Lexical {
+    identifier: bool,
+    number: bool,
+    variable: bool,
+}
+
+impl Lexical {
+    fn write(&self, out: &mut String, keywords: &[&str]) {
+        if !(self.identifier || self.number || self.variable) {
+            return;
+        }
+        writeln!(out).unwrap();
+        if self.identifier || self.variable {
+            writeln!(
+                out,
+                "identifier = ? a letter or `_`, then letters, digits and `_`, other than a keyword ? ;"
+            )
+            .unwrap();
+        }
+        if self.number {
+            writeln!(out, "number = ? one or more decimal digits ? ;").unwrap();
+        }
+        if self.variable {
+            writeln!(
+                out,
+                "variable = ? an identifier bound by an enclosing binder, of the expected kind ? ;"
+            )
+            .unwrap();
+        }
+        if (self.identifier || self.variable) && !keywords.is_empty() {
+            let keywords: Vec<String> = keywords.iter().map(|k| format!("\"{k}\"")).collect();
+            writeln!(out, "(* keywords: {} *)", keywords.join(" ")).unwrap();
+        }
+    }
+}
+
+fn render_sequence(
+    names: &Map<&'static str, String>,
+    lexical: &mut Lexical,
+    symbols: &[Symbol],
+) -> String {
+    if symbols.is_empty() {
+        return "\"\"".to_string();
+    }
+    symbols
+        .iter()
+        .map(|symbol| render(names, lexical, symbol))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn render(names: &Map<&'static str, String>, lexical: &mut Lexical, symbol: &Symbol) -> String {
+    match symbol {
+        Symbol::Keyword(keyword) => format!("\"{keyword}\""),
+        Symbol::Char('"') => "'\"'".to_string(),
+        Symbol::Char(ch) => format!("\"{ch}\""),
+        Symbol::Nonterminal(type_name) => names[type_name].clone(),
+        Symbol::Identifier => {
+            lexical.identifier = true;
+            "identifier".to_string()
+        }
+        Symbol::Number => {
+            lexical.number = true;
+            "number".to_string()
+        }
+        Symbol::Variable => {
+            lexical.variable = true;
+            "variable".to_string()
+        }
+        Symbol::Sequence(symbols) => render_sequence(names, lexical, symbols),
+        Symbol::Optional(symbol) => format!("[ {} ]", render(names, lexical, symbol)),
+        Symbol::Many(symbol) => format!("{{ {} }}", render(names, lexical, symbol)),
+        Symbol::Comma(symbol) => {
+            let item = render(names, lexical, symbol);
+            format!("[ {item} {{ \",\" {item} }} [ \",\" ] ]")
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Write};
 use std::str::FromStr;
 
 use crate::{
@@ -12,6 +12,7 @@ use crate::{
 use super::{CoreParse, ParseError, ParseResult, Scope, SuccessfulParse, TokenResult};
 
 mod left_recursion;
+mod memo;
 
 /// Create this struct when implementing the [`CoreParse`][] trait.
 /// Each `Parser` corresponds to some symbol in the grammar.
@@ -31,7 +32,7 @@ where
     scope: &'s Scope<L>,
     start_text: &'t str,
     nonterminal_name: &'static str,
-    successes: Vec<SuccessfulParse<'t, T>>,
+    successes: Vec<(&'static str, SuccessfulParse<'t, T>)>,
     failures: Set<ParseError<'t>>,
     min_precedence_level: usize,
 }
@@ -98,6 +99,27 @@ impl Precedence {
         Self::new(level, Associativity::None)
     }
 
+    /// The precedence level.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// How recursive occurrences with equal precedence are treated.
+    pub fn associativity(&self) -> Associativity {
+        self.associativity
+    }
+
+    /// The minimum precedence level that the parser accepts for a recursive
+    /// occurrence of the same nonterminal within a variant with this precedence,
+    /// either at the start of the variant (`left`) or after some tokens.
+    pub fn min_recursive_level(&self, left: bool) -> usize {
+        match (self.associativity, left) {
+            (Associativity::Both, _) => 0,
+            (Associativity::Left, true) | (Associativity::Right, false) => self.level,
+            (Associativity::Left | Associativity::Right | Associativity::None, _) => self.level + 1,
+        }
+    }
+
     /// Construct a new precedence.
     fn new(level: usize, associativity: Associativity) -> Self {
         // We require level to be STRICTLY LESS than usize::MAX
@@ -273,13 +295,14 @@ where
                     active_variant.reductions.push(variant_name);
                 }
 
-                self.successes.push(SuccessfulParse {
+                let success = SuccessfulParse {
                     text: active_variant.current_text,
                     reductions: active_variant.reductions,
                     precedence: variant_precedence,
                     value,
-                });
-                tracing::trace!("success: {:?}", self.successes.last().unwrap());
+                };
+                tracing::trace!("success: {:?}", success);
+                self.successes.push((variant_name, success));
             }
 
             Err(errs) => {
@@ -325,23 +348,40 @@ where
             };
         }
 
-        if self.successes.len() > 1 {
-            tracing::trace!("successful parses: `{:?}`", self.successes);
+        if self.successes.len() == 1 {
+            let (_, s) = self.successes.into_iter().next().unwrap();
+            drop(guard);
+            tracing::trace!("best parse = `{:?}`", s);
+            return Ok(s);
         }
 
+        // Multiple variants succeeded: the decisions made here are logged at `debug` level,
+        // so that `RUST_LOG=formality_core::parse=debug` shows how ambiguities are resolved.
+        tracing::debug!(
+            "{} parses of `{}` as `{}`:\n{}",
+            self.successes.len(),
+            self.longest_consumed_text(),
+            self.nonterminal_name,
+            self.describe_successes(),
+        );
+
         // Otherwise, check if we had an unambiguous parse.
         // This results if there exists some success S that is 'better' than every other success.
-        // We say S1 better than S2 if:
-        // * S1 has higher precedence OR
-        // * S1 parsed more tokens and in the same way (i.e., S2.reductions is a prefix of S1.reductions)
-        for (s_i, i) in self.successes.iter().zip(0..) {
+        // We say S1 better than S2 if S1 parsed more tokens and in the same way
+        // (i.e., S2.reductions is a prefix of S1.reductions), see `is_preferable`.
+        // Precedence plays no role here: it is enforced while parsing, when a variant
+        // recurses (see `left_recursion`), so lower-precedence parses never get this far.
+        for ((variant_i, s_i), i) in self.successes.iter().zip(0..) {
             if self
                 .successes
                 .iter()
                 .zip(0..)
-                .all(|(s_j, j)| i == j || Self::is_preferable(s_i, s_j))
+                .all(|((_, s_j), j)| i == j || Self::is_preferable(s_i, s_j))
             {
-                let s_i = self.successes.into_iter().skip(i).next().unwrap();
+                tracing::debug!(
+                    "chose `{variant_i}` (parse {i}), as the reductions of every other parse are a prefix of its reductions",
+                );
+                let (_, s_i) = self.successes.into_iter().nth(i).unwrap();
                 // It's better to print this result alongside the main parsing section.
                 drop(guard);
                 tracing::trace!("best parse = `{:?}`", s_i);
@@ -350,12 +390,49 @@ where
         }
 
         panic!(
-            "ambiguous parse of `{text:?}`, possibilities are {possibilities:#?}",
-            text = self.start_text,
-            possibilities = self.successes,
+            "ambiguous parse of `{text}` as `{nonterminal}`, no parse is preferable to all others. \
+             The competing parses are:\n{possibilities}",
+            text = self.longest_consumed_text(),
+            nonterminal = self.nonterminal_name,
+            possibilities = self.describe_successes(),
         );
     }
 
+    /// The text from the start of this nonterminal up to `remainder`.
+    fn consumed_text(&self, remainder: &'t str) -> &'t str {
+        self.start_text[..self.start_text.len() - remainder.len()].trim_end()
+    }
+
+    /// The text consumed by the successful parse that consumed the most.
+    fn longest_consumed_text(&self) -> &'t str {
+        let remainder = self
+            .successes
+            .iter()
+            .map(|(_, s)| s.text)
+            .min_by_key(|text| text.len())
+            .unwrap_or(self.start_text);
+        self.consumed_text(remainder)
+    }
+
+    /// Describes each successful parse, with the variant that produced it,
+    /// the text it consumed, and its reductions, for use in error messages and logs.
+    fn describe_successes(&self) -> String {
+        self.successes
+            .iter()
+            .zip(0..)
+            .fold(String::new(), |mut out, ((variant, s), i)| {
+                writeln!(
+                    out,
+                    "  {i}: `{variant}` consumed `{text}` with reductions {reductions:?}, producing {value:?}",
+                    text = self.consumed_text(s.text),
+                    reductions = s.reductions,
+                    value = s.value,
+                )
+                .unwrap();
+                out
+            })
+    }
+
     fn is_preferable(s_i: &SuccessfulParse<T>, s_j: &SuccessfulParse<T>) -> bool {
         fn has_prefix<T: Eq>(l1: &[T], l2: &[T]) -> bool {
             l1.len() > l2.len() && (0..l2.len()).all(|i| l1[i] == l2[i])
@@ -387,8 +464,10 @@ where
         //
         // Subtle-ish: this comparison assumes there is no whitespace,
         // but we establish that invariant in `Self::new`.
+        // We compare positions rather than contents, which would take time
+        // proportional to the rest of the input.
         debug_assert_eq!(self.start_text, skip_whitespace(self.start_text));
-        let left_right = if self.start_text == self.current_text {
+        let left_right = if std::ptr::eq(self.start_text, self.current_text) {
             LeftRight::Left
         } else {
             LeftRight::Right
@@ -497,12 +576,26 @@ where
     #[tracing::instrument(level = "trace", ret)]
     pub fn expect_keyword(&mut self, expected: &str) -> Result<(), Set<ParseError<'t>>> {
         let text0 = self.current_text;
-        match self.identifier_like_string() {
-            Ok(ident) if &*ident == expected => Ok(()),
-            _ => Err(ParseError::at(
-                skip_whitespace(text0),
-                format!("expected `{}`", expected),
-            )),
+        // Compare in place rather than with `identifier_like_string`, which allocates:
+        // most of the time the keyword isn't there.
+        let text = skip_whitespace(text0);
+        let len = text
+            .find(|ch: char| !matches!(ch, 'a'..='z' | 'A'..='Z' | '_' | '0'..='9'))
+            .unwrap_or(text.len());
+        match &text[..len] {
+            ident if ident == expected => {
+                self.current_text = &text[len..];
+                Ok(())
+            }
+            _ => {
+                // Don't consume the identifier on failure, so that callers
+                // (e.g., `$:guard` fields) can try something else.
+                self.current_text = text0;
+                Err(ParseError::at(
+                    skip_whitespace(text0),
+                    format!("expected `{}`", expected),
+                ))
+            }
         }
     }
 
@@ -549,7 +642,12 @@ where
     /// you can invoke `reject_custom_keywords` to reject anything that you don't want to permit in this position.
     pub fn reject_custom_keywords(&self, keywords: &[&str]) -> Result<(), Set<ParseError<'t>>> {
         self.reject(
-            |p| p.expect_keyword_in(keywords),
+            // Not `expect_keyword_in`: its error message (which would be discarded)
+            // is costly to build, and most identifiers are not keywords.
+            |p| match p.identifier_like_string() {
+                Ok(ident) if keywords.contains(&&*ident) => Ok(ident),
+                _ => Err(set![]),
+            },
             |ident| {
                 ParseError::at(
                     self.current_text,
@@ -14,7 +14,7 @@ use crate::{
     parse::{parser::Associativity, ParseError, ParseResult, Scope, SuccessfulParse},
 };
 
-use super::Precedence;
+use super::{memo, Precedence};
 
 thread_local! {
     static STACK: RefCell<Vec<StackEntry>> = Default::default()
@@ -39,6 +39,16 @@ struct StackEntry {
 
     ///
     observed: bool,
+
+    /// True if the result depends on some enclosing parse that is still in progress
+    /// (e.g., because it reused the intermediate value of a left-recursive parse).
+    /// Such results cannot be memoized.
+    context_dependent: bool,
+
+    /// The types of the nonterminals parsed at `start_text` so far, including `T`.
+    /// A memoized result is only valid if none of these could have recursed into
+    /// a parse that is currently in progress, see [`enter`].
+    left_types: Vec<TypeId>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -48,6 +58,15 @@ pub(super) struct CurrentState {
     pub current_text: *const str,
 }
 
+impl CurrentState {
+    /// True if recursing from this state has no effect on the recursive parse
+    /// (see the right-recursion case in [`enter`]).
+    fn is_right_both(&self) -> bool {
+        matches!(self.left_right, LeftRight::Right)
+            && self.precedence.associativity == Associativity::Both
+    }
+}
+
 /// Determines the kind of recursion the current variant
 /// would have if it recursed. For example, given a grammar
 /// with a variant
@@ -86,6 +105,8 @@ impl StackEntry {
             type_id: TypeId::of::<T>(),
             value: None,
             observed: false,
+            context_dependent: false,
+            left_types: vec![TypeId::of::<T>()],
         }
     }
 
@@ -199,7 +220,10 @@ where
     // First check whether we are already parsing this same text in this same scope as this same type.
     let mut min_precedence_level = 0;
     let return_value = STACK.with_borrow_mut(|stack| {
-        for entry in stack.iter_mut().rev() {
+        record_left_types(stack, text, &[TypeId::of::<T>()]);
+
+        for index in (0..stack.len()).rev() {
+            let entry = &mut stack[index];
             match entry.matches_current_state::<L, T>(scope, text) {
                 // Keep searching.
                 None => (),
@@ -236,6 +260,11 @@ where
                         // UNSAFE: See [1] below for justification.
                         entry.observe::<T>(text)
                     };
+
+                    // Whatever we return depends on the state of `entry`, and so do the
+                    // results of the parses in between.
+                    mark_context_dependent(&mut stack[index + 1..]);
+
                     tracing::trace!(
                         "found left-recursive stack entry with precedence {:?}, previous_result = {:?}",
                         current_precedence,
@@ -326,11 +355,33 @@ where
                         }
                         Associativity::Both => {}
                     };
+                    if current_precedence.associativity != Associativity::Both {
+                        mark_context_dependent(&mut stack[index + 1..]);
+                    }
                     break;
                 }
             }
         }
 
+        // Reuse a memoized result, if there is one that is still valid. The memoized
+        // result was produced without reference to the parses now in progress. This is
+        // still what we would get now unless, when producing it, we parsed something at
+        // `text` that would now recurse into one of the parses in progress.
+        if let Some((result, left_types)) =
+            memo::lookup::<L, T>(scope, text, min_precedence_level)
+        {
+            let in_progress = stack.iter().any(|entry| {
+                entry.current_state.is_some_and(|state| {
+                    std::ptr::eq(state.current_text, text) && !state.is_right_both()
+                }) && left_types.contains(&entry.type_id)
+            });
+            if !in_progress {
+                tracing::trace!("memoized result = {:?}", result);
+                record_left_types(stack, text, &left_types);
+                return ControlFlow::Break(result);
+            }
+        }
+
         stack.push(StackEntry::new::<L, T>(scope, text));
         ControlFlow::Continue(())
     });
@@ -352,91 +403,108 @@ where
         };
     }
 
-    // Pop the stack before we return
+    // Pop the stack before we return. Once the outermost parse is done,
+    // the memoized results refer to input that may no longer be valid.
     final_fn::final_fn!(STACK.with_borrow_mut(|stack| {
         let top = stack.pop().unwrap();
         assert!(top.matches_start_state::<L, T>(scope, text));
+        if stack.is_empty() {
+            memo::clear();
+        }
     }));
 
-    // EXAMPLE: Consider this grammar
-    //
-    // ```
-    // Expr = Expr '+' Expr
-    //      | Integer
-    // ```
-    //
-    // and this input `2 + 3`. We process this in rounds.
-    //
-    // Round 0: Previous value `value` is `None`. When we go to parse expr, it will recurse,
-    // which will yield an error that consumes zero tokens. We will then attempt integer,
-    // which succeeds, yielding a parsed result of `2` with remainder `+ 3`.
-    //
-    // Round 1: We store `(2, "+ 3")` as the previous result and try again. When we go to parse `Expr`,
-    // there are two options. First, we successfully parse as an integer just like before.
-    // But also we are able to parse as `Expr + Expr`, because the left recursive reference to `Expr` yields `2`
-    // and we can continue and parse `2 + 3`. The `Parser` prefers this longer result and so we get
-    // `2 + 3` as the final result.
-    //
-    // Round 2: We store `(2+3, "")` as the previous result and try again. *This time* when we recurse,
-    // we get `2` again! The reason why is a bit surprising. The parse of `2` succeeds with remainder
-    // `"+ 3"`. But when we go parse `Expr + Expr`, the first `Expr` result yields `2 + 3` and there are no more
-    // tokens, so that arm fails. In our loop below, we search back through the result and find that `2` has already
-    // occurred, so we take `2 + 3` as the best overall parse.
-    //
-    // It's a bit subtle why this is ok. It's relying on some properties of grammars and parsing.
-    // To be more obviously correct we would want to return sets of successful results.
-    // In particular, the assumption is that `op` is always returning a best result (if any) and panicking on
-    // ambiguity.
-
-    // First round parse is a bit special, because if we get an error here, we can just return immediately,
-    // as there is no base case to build from.
-    let mut values = vec![];
-    match op(min_precedence_level) {
-        Ok(v) => values.push(v),
-        Err(errs) => return Err(errs),
-    };
-
-    // Check whether there was recursion to begin with.
-    let observed = with_top!(|top| top.observed);
-    if !observed {
-        return Ok(values.pop().unwrap()); // If not, we are done.
-    }
-
-    // OK, this is the interesting case. We may be able to get a better parse.
-    loop {
-        tracing::trace!(
-            "reparsing of left-recursive grammar: values = {:#?}",
-            values
-        );
+    // Parse the value, growing it as long as possible if this is left-recursive.
+    let mut grow_seed = || -> ParseResult<'t, T> {
+        // EXAMPLE: Consider this grammar
+        //
+        // ```
+        // Expr = Expr '+' Expr
+        //      | Integer
+        // ```
+        //
+        // and this input `2 + 3`. We process this in rounds.
+        //
+        // Round 0: Previous value `value` is `None`. When we go to parse expr, it will recurse,
+        // which will yield an error that consumes zero tokens. We will then attempt integer,
+        // which succeeds, yielding a parsed result of `2` with remainder `+ 3`.
+        //
+        // Round 1: We store `(2, "+ 3")` as the previous result and try again. When we go to parse `Expr`,
+        // there are two options. First, we successfully parse as an integer just like before.
+        // But also we are able to parse as `Expr + Expr`, because the left recursive reference to `Expr` yields `2`
+        // and we can continue and parse `2 + 3`. The `Parser` prefers this longer result and so we get
+        // `2 + 3` as the final result.
+        //
+        // Round 2: We store `(2+3, "")` as the previous result and try again. *This time* when we recurse,
+        // we get `2` again! The reason why is a bit surprising. The parse of `2` succeeds with remainder
+        // `"+ 3"`. But when we go parse `Expr + Expr`, the first `Expr` result yields `2 + 3` and there are no more
+        // tokens, so that arm fails. In our loop below, we search back through the result and find that `2` has already
+        // occurred, so we take `2 + 3` as the best overall parse.
+        //
+        // It's a bit subtle why this is ok. It's relying on some properties of grammars and parsing.
+        // To be more obviously correct we would want to return sets of successful results.
+        // In particular, the assumption is that `op` is always returning a best result (if any) and panicking on
+        // ambiguity.
+
+        // First round parse is a bit special, because if we get an error here, we can just return immediately,
+        // as there is no base case to build from.
+        let mut values = vec![];
+        match op(min_precedence_level) {
+            Ok(v) => values.push(v),
+            Err(errs) => return Err(errs),
+        };
 
-        // If we have an intermediate value, update the stack entry to point at.
-        // This takes a borrow of `value` but converts it into a raw pointer.
-        // This borrow lasts until after `op` is complete.
-        let best_value = values.last().unwrap();
-        with_top!(|top| {
-            top.value = Some(erase_type(best_value));
-        });
-
-        // Invoke the operation. As noted above, if we get a failed parse NOW,
-        // we know we already found the best result, so we can just use it.
-        let Ok(value1) = op(min_precedence_level) else {
+        // Check whether there was recursion to begin with.
+        let observed = with_top!(|top| top.observed);
+        if !observed {
             return Ok(values.pop().unwrap()); // If not, we are done.
-        };
+        }
 
-        tracing::trace!("left-recursive grammar yielded: value1 = {:?}", value1);
+        // OK, this is the interesting case. We may be able to get a better parse.
+        loop {
+            tracing::trace!(
+                "reparsing of left-recursive grammar: values = {:#?}",
+                values
+            );
+
+            // If we have an intermediate value, update the stack entry to point at.
+            // This takes a borrow of `value` but converts it into a raw pointer.
+            // This borrow lasts until after `op` is complete.
+            let best_value = values.last().unwrap();
+            with_top!(|top| {
+                top.value = Some(erase_type(best_value));
+            });
+
+            // Invoke the operation. As noted above, if we get a failed parse NOW,
+            // we know we already found the best result, so we can just use it.
+            let Ok(value1) = op(min_precedence_level) else {
+                return Ok(values.pop().unwrap()); // If not, we are done.
+            };
+
+            tracing::trace!("left-recursive grammar yielded: value1 = {:?}", value1);
+
+            // If we got back on the previous results we saw, then we're entering
+            // a loop and we can stop and take the best one (which should also be the longest).
+            // In our example, this occurs when we parse `6` -- the first result
+            // succeeds, but we have to try again to see if there's a more complex
+            // expression that can be produced (there isn't).
+            if values.iter().any(|v| *v == value1) {
+                return Ok(values.pop().unwrap()); // If not, we are done.
+            }
 
-        // If we got back on the previous results we saw, then we're entering
-        // a loop and we can stop and take the best one (which should also be the longest).
-        // In our example, this occurs when we parse `6` -- the first result
-        // succeeds, but we have to try again to see if there's a more complex
-        // expression that can be produced (there isn't).
-        if values.iter().any(|v| *v == value1) {
-            return Ok(values.pop().unwrap()); // If not, we are done.
+            // Otherwise, we have to try again.
+            values.push(value1);
         }
+    };
 
-        // Otherwise, we have to try again.
-        values.push(value1);
+    let result = grow_seed();
+
+    let (context_dependent, left_types) =
+        with_top!(|top| (top.context_dependent, top.left_types.clone()));
+    if !context_dependent {
+        memo::insert::<L, T>(scope, text, min_precedence_level, &result, left_types);
     }
+
+    result
 }
 
 pub(super) fn recurse<'s, 't, R>(current_state: CurrentState, op: impl FnOnce() -> R) -> R {
@@ -461,6 +529,26 @@ pub(super) fn recurse<'s, 't, R>(current_state: CurrentState, op: impl FnOnce()
     op()
 }
 
+/// Records that nonterminals of the given types are being parsed at `text`,
+/// on behalf of each enclosing parse that started at `text`.
+fn record_left_types(stack: &mut [StackEntry], text: &str, types: &[TypeId]) {
+    let text: *const str = text;
+    for entry in stack.iter_mut().filter(|entry| entry.start_text == text) {
+        for type_id in types {
+            if !entry.left_types.contains(type_id) {
+                entry.left_types.push(*type_id);
+            }
+        }
+    }
+}
+
+/// Marks the results of the given parses as depending on an enclosing parse in progress.
+fn mark_context_dependent(entries: &mut [StackEntry]) {
+    for entry in entries {
+        entry.context_dependent = true;
+    }
+}
+
 fn erase_type<T>(s: &T) -> *const () {
     s as *const T as *const ()
 }
@@ -0,0 +1,125 @@
+//! Memoization of parse results ("packrat parsing"). Without it, the same
+//! nonterminal gets re-parsed at the same point in the input once for every
+//! variant that tries it (and once per round of left-recursion), which is
+//! exponential in the nesting depth of the input.
+//!
+//! Results are keyed on the type being parsed, the scope, the input position, and
+//! the minimum precedence level. Results that depend on a parse that is still in
+//! progress are not memoized, see [`super::left_recursion::enter`][] for the
+//! interaction with left-recursion.
+//!
+//! Like the left-recursion stack, the table is a `thread_local!`, so it has to erase
+//! the types `L` and `T`. It also erases the lifetime `'t` of the input: this is why
+//! the table is cleared when the outermost parse completes.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+};
+
+use crate::{
+    language::Language,
+    parse::{ParseResult, Scope},
+};
+
+thread_local! {
+    static MEMO: RefCell<HashMap<MemoKey, Vec<MemoEntry>>> = Default::default()
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct MemoKey {
+    /// The TypeId of the type `T` that was parsed.
+    type_id: TypeId,
+
+    /// The input position: we use `*const` instead of `&'t str`.
+    text: *const str,
+
+    min_precedence_level: usize,
+}
+
+/// A memoized result. Results for different scopes share a key, since
+/// scopes are compared by value rather than by address.
+struct MemoEntry {
+    /// The `Scope<L>` the result was parsed in.
+    scope: Box<dyn Any>,
+
+    /// The `ParseResult<'static, T>` (really a `ParseResult<'t, T>`).
+    result: Box<dyn Any>,
+
+    /// The types of the nonterminals that were parsed at `text` while producing `result`.
+    left_types: Vec<TypeId>,
+}
+
+/// Looks for a memoized result of parsing a `T` from `text`.
+/// Returns the result and the types of the nonterminals that were parsed at `text` while producing it.
+pub(super) fn lookup<'t, L, T>(
+    scope: &Scope<L>,
+    text: &'t str,
+    min_precedence_level: usize,
+) -> Option<(ParseResult<'t, T>, Vec<TypeId>)>
+where
+    L: Language,
+    T: Clone + 'static,
+{
+    let key = MemoKey::new::<T>(text, min_precedence_level);
+    MEMO.with_borrow(|memo| {
+        let entry = memo
+            .get(&key)?
+            .iter()
+            .find(|entry| entry.scope.downcast_ref::<Scope<L>>() == Some(scope))?;
+        let result: &ParseResult<'static, T> = entry.result.downcast_ref().unwrap();
+
+        // UNSAFE: the result was parsed from the same input as `text` (as it contains `text`),
+        // see [1] below.
+        let result: &ParseResult<'t, T> =
+            unsafe { &*(result as *const ParseResult<'static, T>).cast::<ParseResult<'t, T>>() };
+
+        Some((result.clone(), entry.left_types.clone()))
+    })
+}
+
+/// Memoizes `result`, the result of parsing a `T` from `text`.
+pub(super) fn insert<'t, L, T>(
+    scope: &Scope<L>,
+    text: &'t str,
+    min_precedence_level: usize,
+    result: &ParseResult<'t, T>,
+    left_types: Vec<TypeId>,
+) where
+    L: Language,
+    T: Clone + 'static,
+{
+    // [1] UNSAFE: We erase the lifetime `'t`, so we need to justify that the references
+    // to the input in `result` remain valid for as long as they are in the table.
+    //
+    // The table is cleared when the outermost parse on this thread completes (see `clear`).
+    // The input text is borrowed by that outermost parse, so it outlives the table entries.
+    // Results are only returned for the same input position they were parsed from.
+    let result: &ParseResult<'static, T> =
+        unsafe { &*(result as *const ParseResult<'t, T>).cast::<ParseResult<'static, T>>() };
+
+    let key = MemoKey::new::<T>(text, min_precedence_level);
+    MEMO.with_borrow_mut(|memo| {
+        memo.entry(key).or_default().push(MemoEntry {
+            scope: Box::new(scope.clone()),
+            result: Box::new(result.clone()),
+            left_types,
+        })
+    });
+}
+
+/// Forgets all memoized results. Invoked when the outermost parse completes.
+pub(super) fn clear() {
+    MEMO.with_borrow_mut(|memo| memo.clear());
+}
+
+impl MemoKey {
+    fn new<T: 'static>(text: &str, min_precedence_level: usize) -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            text,
+            min_precedence_level,
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Side-table recording where in a source file parsed terms came from.
+//!
+//! Recording is only active while parsing a file with [`core_term_from_file`][],
+//! and only for terms whose parser was generated by `#[term]`. The result is a
+//! [`SourceMap`] for that file, returned alongside the parsed term. Terms are keyed
+//! by value, so [`SourceMap::span_of`] finds the location of any term *equal* to one
+//! that was parsed from the file (the first one, if it appeared more than once).
+//! Terms produced later, e.g. by instantiating a binder, have no span.
+//!
+//! [`core_term_from_file`]: super::core_term_from_file
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use super::{skip_whitespace, ParseResult};
+
+/// A file that terms were parsed from.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SourceFile {
+    pub path: String,
+    pub text: String,
+}
+
+/// The range of bytes `start..end` in `file` that a term was parsed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub file: Arc<SourceFile>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// Line and column (both 1-based) of the start of the span.
+    pub fn line_column(&self) -> (usize, usize) {
+        super::line_column(&self.file.text, self.start)
+    }
+
+    /// The source text covered by the span.
+    pub fn text(&self) -> &str {
+        &self.file.text[self.start..self.end]
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, column) = self.line_column();
+        write!(f, "{}:{}:{}", self.file.path, line, column)
+    }
+}
+
+/// Spans of the terms parsed from `file`, per term type.
+pub struct SourceMap {
+    file: Arc<SourceFile>,
+
+    /// Each entry is a `HashMap<T, (usize, usize)>` for the type `T`.
+    spans: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl SourceMap {
+    /// The file that the terms were parsed from.
+    pub fn file(&self) -> &SourceFile {
+        &self.file
+    }
+
+    /// Returns the span that `term` was parsed from, if any.
+    pub fn span_of<T>(&self, term: &T) -> Option<Span>
+    where
+        T: Hash + Eq + 'static,
+    {
+        let &(start, end) = self
+            .spans
+            .get(&TypeId::of::<T>())?
+            .downcast_ref::<HashMap<T, (usize, usize)>>()?
+            .get(term)?;
+        Some(Span {
+            file: self.file.clone(),
+            start,
+            end,
+        })
+    }
+}
+
+impl fmt::Debug for SourceMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SourceMap")
+            .field("path", &self.file.path)
+            .finish_non_exhaustive()
+    }
+}
+
+thread_local! {
+    /// Table being filled in by the parse in progress on this thread, if any.
+    static RECORDING: RefCell<Option<SourceMap>> = const { RefCell::new(None) };
+}
+
+/// Runs `op` (which parses `file.text`) while recording the spans of the terms it parses.
+/// If the parse succeeds, returns its result along with those spans.
+pub(super) fn recording<R, E>(
+    file: SourceFile,
+    op: impl FnOnce(&str) -> Result<R, E>,
+) -> Result<(R, SourceMap), E> {
+    let file = Arc::new(file);
+    let previous = RECORDING.with(|r| {
+        r.replace(Some(SourceMap {
+            file: file.clone(),
+            spans: HashMap::new(),
+        }))
+    });
+    let result = op(&file.text);
+    let source_map = RECORDING.with(|r| r.replace(previous)).unwrap();
+    result.map(|r| (r, source_map))
+}
+
+/// Records the span of a successful parse of `text`, if recording is active
+/// and `text` is part of the file being parsed. Invoked by `#[term]`-generated parsers.
+pub fn record_span<'t, T>(text: &'t str, result: ParseResult<'t, T>) -> ParseResult<'t, T>
+where
+    T: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    let Ok(success) = &result else {
+        return result;
+    };
+
+    RECORDING.with(|r| {
+        let mut r = r.borrow_mut();
+        let Some(table) = &mut *r else {
+            return;
+        };
+
+        let base = table.file.text.as_ptr() as usize;
+        let start = skip_whitespace(text).as_ptr() as usize;
+        let end = success.text.as_ptr() as usize;
+        if start < base || end > base + table.file.text.len() {
+            return;
+        }
+
+        table
+            .spans
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::<HashMap<T, (usize, usize)>>::default())
+            .downcast_mut::<HashMap<T, (usize, usize)>>()
+            .unwrap()
+            .entry(success.value.clone())
+            .or_insert((start - base, end - base));
+    });
+
+    result
+}
@@ -0,0 +1,548 @@
+//! Printing terms as text that parses back to the same term.
+//!
+//! The `Debug` output of a term is meant for humans: bound variables show up
+//! as `^ty0_0`, universal variables as `!ty_2`, and so forth, none of which the
+//! parser accepts. [`CorePrint`] is the counterpart of
+//! [`CoreParse`](`crate::parse::CoreParse`) instead: it invents names for bound
+//! variables (and lists them in the binders that introduce them), gives
+//! free variables names that are reported alongside the text (see [`Printed`]),
+//! adds parentheses where `#[precedence]` requires them, and breaks lists
+//! that do not fit on a line.
+//!
+//! `#[term]` generates `CorePrint` impls from the same grammar it uses for
+//! parsing; types with custom parsers implement it by hand, usually on top of
+//! [`Printer::token`] and the list helpers.
+//!
+//! Invented names are of the form `T0`, `L1`, ... (the first letter of the kind
+//! followed by a counter), skipping any name that the term itself uses as an
+//! identifier. Since those identifiers are only known once the term has been
+//! printed, [`core_print`] prints the term twice.
+
+use std::sync::Arc;
+
+use crate::{
+    binder::CoreBinder,
+    cast::Upcast,
+    collections::Set,
+    interner::{Internable, Interned},
+    language::{CoreKind, CoreParameter, Language},
+    parse::{core_term_with, Precedence},
+    term::CoreTerm,
+    variable::{CoreBoundVar, CoreExistentialVar, CoreUniversalVar, CoreVariable},
+};
+
+/// Lines longer than this are broken up, where possible.
+const WIDTH: usize = 100;
+
+/// Indentation of the items of a list that was broken across lines.
+const INDENT: usize = 4;
+
+/// Trait for printing a term in a form that [`CoreParse`](`crate::parse::CoreParse`) accepts.
+pub trait CorePrint<L: Language> {
+    /// Prints `self` into `p`.
+    fn print(&self, p: &mut Printer<L>);
+}
+
+/// The result of printing a term.
+pub struct Printed<L: Language> {
+    /// The printed text.
+    pub text: String,
+
+    /// The names given to the free variables that appear in the term.
+    /// Parsing `text` with these bindings in scope yields the term again.
+    pub free_variables: Vec<(String, CoreVariable<L>)>,
+}
+
+/// Prints `term` as parseable text.
+pub fn core_print<L, T>(term: &T) -> Printed<L>
+where
+    L: Language,
+    T: CorePrint<L> + ?Sized,
+{
+    let mut first = Printer::new();
+    first.print(term);
+
+    let mut p = Printer::new();
+    p.reserved = first.identifiers;
+    p.print(term);
+    p.finish()
+}
+
+/// Prints `term`, parses the text back, and panics unless that yields `term` again.
+#[track_caller]
+pub fn core_assert_round_trip<L, T>(term: &T)
+where
+    L: Language,
+    T: CoreTerm<L>,
+{
+    let Printed {
+        text,
+        free_variables,
+    } = core_print(term);
+
+    let bindings = free_variables
+        .into_iter()
+        .map(|(name, var)| -> (String, CoreParameter<L>) { (name, var.upcast()) });
+    match core_term_with::<L, T, _>(bindings, &text) {
+        Ok(parsed) => {
+            if parsed != *term {
+                panic!(
+                    "printed term parses to a different term\n\
+                     text:\n{text}\n\
+                     expected: {term:?}\n\
+                     parsed:   {parsed:?}"
+                );
+            }
+        }
+        Err(err) => panic!("printed term does not parse\ntext:\n{text}\nerror: {err}"),
+    }
+}
+
+/// Accumulates the text of a term being printed.
+///
+/// Text is produced as a sequence of tokens. Tokens are separated by a space
+/// unless [`Printer::no_space`] is called in between. Lists (see
+/// [`Printer::comma`] and friends) form groups that are printed on one line if
+/// they fit and otherwise with one item per line.
+pub struct Printer<L: Language> {
+    /// The contents of each open group; the innermost group is last.
+    groups: Vec<Vec<Doc>>,
+
+    /// Whether the next token is separated from the previous one by a space.
+    space: bool,
+
+    /// The names of the variables bound by each enclosing binder; innermost last.
+    binders: Vec<Vec<String>>,
+
+    /// The names given to the free variables printed so far.
+    free_variables: Vec<(String, CoreVariable<L>)>,
+
+    /// Number of names invented so far, used to keep them distinct.
+    names: usize,
+
+    /// The tokens printed so far, other than the names of variables.
+    identifiers: Set<String>,
+
+    /// Names that must not be invented, as the term uses them (see [`core_print`]).
+    reserved: Set<String>,
+
+    /// Set by [`Printer::print_at`]: the type whose next variant must have
+    /// at least the given precedence level to be printed without parentheses.
+    min_precedence: Option<(&'static str, usize)>,
+}
+
+/// The layout of printed text, before deciding where lines are broken.
+enum Doc {
+    Text(String),
+
+    /// A space, omitted at the start of a line.
+    Space,
+
+    /// A line break indented one level deeper than the enclosing group, if the group is broken.
+    Break,
+
+    /// A line break back to the indentation of the enclosing group, if the group is broken.
+    BreakOut,
+
+    /// Text printed only if the enclosing group is broken.
+    Trailing(&'static str),
+
+    /// A group printed either entirely on one line or with all of its breaks.
+    Group(Vec<Doc>),
+}
+
+impl<L: Language> Default for Printer<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Language> Printer<L> {
+    pub fn new() -> Self {
+        Self {
+            groups: vec![vec![]],
+            space: false,
+            binders: vec![],
+            free_variables: vec![],
+            names: 0,
+            identifiers: Set::new(),
+            reserved: Set::new(),
+            min_precedence: None,
+        }
+    }
+
+    /// Lays out the text printed so far.
+    pub fn finish(mut self) -> Printed<L> {
+        assert_eq!(self.groups.len(), 1, "unclosed group");
+        let docs = self.groups.pop().unwrap();
+        let mut renderer = Renderer::default();
+        renderer.group(&docs);
+        Printed {
+            text: renderer.out,
+            free_variables: self.free_variables,
+        }
+    }
+
+    /// Prints `term`.
+    pub fn print<T: CorePrint<L> + ?Sized>(&mut self, term: &T) {
+        self.min_precedence = None;
+        term.print(self);
+    }
+
+    /// Prints `term`, which appears in a variant of the type `P` at a position
+    /// where the parser only accepts variants of `P` with precedence `level` or higher.
+    /// Variants with lower precedence are wrapped in parentheses.
+    pub fn print_at<P: ?Sized, T: CorePrint<L> + ?Sized>(&mut self, term: &T, level: usize) {
+        self.min_precedence = Some((std::any::type_name::<P>(), level));
+        term.print(self);
+        self.min_precedence = None;
+    }
+
+    /// Prints a variant of the type `T` with the given precedence, using `op`
+    /// to print its contents. Adds parentheses if required by [`Printer::print_at`].
+    pub fn variant<T: ?Sized>(&mut self, precedence: Precedence, op: impl FnOnce(&mut Self)) {
+        let parens = match self.min_precedence.take() {
+            Some((name, level)) => name == std::any::type_name::<T>() && precedence.level() < level,
+            None => false,
+        };
+
+        if parens {
+            self.token("(");
+            self.no_space();
+        }
+        op(self);
+        if parens {
+            self.no_space();
+            self.token(")");
+        }
+    }
+
+    /// Prints `text`, preceded by a space unless [`Printer::no_space`] was called since the last token.
+    pub fn token(&mut self, text: &str) {
+        if !self.identifiers.contains(text) {
+            self.identifiers.insert(text.to_string());
+        }
+        self.name(text);
+    }
+
+    /// Like [`Printer::token`], for the name of a variable.
+    fn name(&mut self, text: &str) {
+        if std::mem::replace(&mut self.space, true) {
+            self.push(Doc::Space);
+        }
+        self.push(Doc::Text(text.to_string()));
+    }
+
+    /// Suppresses the space before the next token.
+    pub fn no_space(&mut self) {
+        self.space = false;
+    }
+
+    /// Prints `items` separated by commas.
+    pub fn comma<'i, T: CorePrint<L> + 'i>(&mut self, items: impl IntoIterator<Item = &'i T>) {
+        self.sequence(items, Some(","), |p, item| p.print(item));
+    }
+
+    /// Prints `items` separated by commas and enclosed in `open` and `close`.
+    pub fn delimited<'i, T: CorePrint<L> + 'i>(
+        &mut self,
+        open: &str,
+        items: impl IntoIterator<Item = &'i T>,
+        close: &str,
+    ) {
+        self.token(open);
+        self.no_space();
+        self.comma(items);
+        self.no_space();
+        self.token(close);
+    }
+
+    /// Prints `items` separated by whitespace.
+    pub fn many<'i, T: CorePrint<L> + 'i>(&mut self, items: impl IntoIterator<Item = &'i T>) {
+        self.sequence(items, None, |p, item| p.print(item));
+    }
+
+    /// Prints `items` with `op`, as a group broken one item per line if it does not fit.
+    /// If `separator` is given, it is printed between items (and after the last one,
+    /// if the group is broken).
+    pub fn sequence<I>(
+        &mut self,
+        items: impl IntoIterator<Item = I>,
+        separator: Option<&'static str>,
+        mut op: impl FnMut(&mut Self, I),
+    ) {
+        self.groups.push(vec![]);
+        let mut any = false;
+        for item in items {
+            if let (true, Some(separator)) = (any, separator) {
+                self.no_space();
+                self.token(separator);
+            }
+            self.push(Doc::Break);
+            op(self, item);
+            any = true;
+        }
+        if let (true, Some(separator)) = (any, separator) {
+            self.push(Doc::Trailing(separator));
+        }
+        self.push(Doc::BreakOut);
+        let group = self.groups.pop().unwrap();
+        self.push(Doc::Group(group));
+    }
+
+    /// Invents a name for a variable of the given kind.
+    pub fn fresh_name(&mut self, kind: &CoreKind<L>) -> String {
+        let kind = format!("{kind:?}");
+        let initial: String = kind.chars().take(1).flat_map(char::to_uppercase).collect();
+        loop {
+            let name = format!("{initial}{}", self.names);
+            self.names += 1;
+            if !self.reserved.contains(&name) {
+                return name;
+            }
+        }
+    }
+
+    /// Prints the list of bindings `<kind name, ...>` that the parser expects at the start of a binder.
+    pub fn bindings(&mut self, kinds: &[CoreKind<L>], names: &[String]) {
+        self.token(&L::BINDING_OPEN.to_string());
+        self.no_space();
+        self.sequence(kinds.iter().zip(names), Some(","), |p, (kind, name)| {
+            p.print(kind);
+            p.name(name);
+        });
+        self.no_space();
+        self.token(&L::BINDING_CLOSE.to_string());
+    }
+
+    /// Runs `op` with `names` as the names of the variables bound by an innermost binder.
+    pub fn with_bound_names<R>(
+        &mut self,
+        names: Vec<String>,
+        op: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        self.binders.push(names);
+        let result = op(self);
+        self.binders.pop();
+        result
+    }
+
+    /// Prints a binder with the given kinds, using `op` to print its contents.
+    pub fn binder(&mut self, kinds: &[CoreKind<L>], op: impl FnOnce(&mut Self)) {
+        let names: Vec<String> = kinds.iter().map(|kind| self.fresh_name(kind)).collect();
+        self.bindings(kinds, &names);
+        self.with_bound_names(names, op);
+    }
+
+    /// Prints the name of a free variable, inventing one if it has not been printed before.
+    fn free_variable(&mut self, var: CoreVariable<L>) {
+        let name = match self.free_variables.iter().find(|(_, v)| *v == var) {
+            Some((name, _)) => name.clone(),
+            None => {
+                let name = self.fresh_name(&var.kind());
+                self.free_variables.push((name.clone(), var));
+                name
+            }
+        };
+        self.name(&name);
+    }
+
+    fn push(&mut self, doc: Doc) {
+        self.groups.last_mut().unwrap().push(doc);
+    }
+}
+
+#[derive(Default)]
+struct Renderer {
+    out: String,
+    column: usize,
+    indent: usize,
+    at_line_start: bool,
+}
+
+impl Renderer {
+    /// Renders `docs` on one line if they fit in the rest of the current line, else with their breaks.
+    fn group(&mut self, docs: &[Doc]) {
+        let flat = self.column + width(docs) <= WIDTH;
+        self.render(docs, flat);
+    }
+
+    fn render(&mut self, docs: &[Doc], flat: bool) {
+        let base = self.indent;
+        for doc in docs {
+            match doc {
+                Doc::Text(text) => self.text(text),
+                Doc::Space => {
+                    if !self.at_line_start {
+                        self.text(" ");
+                    }
+                }
+                Doc::Break if !flat => self.newline(base + INDENT),
+                Doc::BreakOut if !flat => self.newline(base),
+                Doc::Trailing(text) if !flat => self.text(text),
+                Doc::Break | Doc::BreakOut | Doc::Trailing(_) => {}
+                Doc::Group(docs) if flat => self.render(docs, true),
+                Doc::Group(docs) => self.group(docs),
+            }
+        }
+        self.indent = base;
+    }
+
+    fn text(&mut self, text: &str) {
+        self.out.push_str(text);
+        self.column += text.chars().count();
+        self.at_line_start = false;
+    }
+
+    fn newline(&mut self, indent: usize) {
+        self.out.push('\n');
+        self.out.extend(std::iter::repeat(' ').take(indent));
+        self.column = indent;
+        self.indent = indent;
+        self.at_line_start = true;
+    }
+}
+
+/// Width of `docs` when printed on one line.
+fn width(docs: &[Doc]) -> usize {
+    docs.iter()
+        .map(|doc| match doc {
+            Doc::Text(text) => text.chars().count(),
+            Doc::Space => 1,
+            Doc::Break | Doc::BreakOut | Doc::Trailing(_) => 0,
+            Doc::Group(docs) => width(docs),
+        })
+        .sum()
+}
+
+impl<L: Language, T: CorePrint<L>> CorePrint<L> for Vec<T> {
+    fn print(&self, p: &mut Printer<L>) {
+        p.delimited("[", self, "]")
+    }
+}
+
+impl<L: Language, T: CorePrint<L>> CorePrint<L> for Set<T> {
+    fn print(&self, p: &mut Printer<L>) {
+        p.delimited("{", self, "}")
+    }
+}
+
+impl<L: Language, T: CorePrint<L>> CorePrint<L> for Option<T> {
+    fn print(&self, p: &mut Printer<L>) {
+        if let Some(t) = self {
+            p.print(t)
+        }
+    }
+}
+
+/// Passes any precedence requirement through to the contents.
+impl<L: Language, T: CorePrint<L> + ?Sized> CorePrint<L> for Arc<T> {
+    fn print(&self, p: &mut Printer<L>) {
+        T::print(self, p)
+    }
+}
+
+impl<L: Language, T: CorePrint<L> + Internable> CorePrint<L> for Interned<T> {
+    fn print(&self, p: &mut Printer<L>) {
+        T::print(self, p)
+    }
+}
+
+/// Binders are always printed with their bindings (`<>` if there are none),
+/// since otherwise contents starting with `<` would be mistaken for bindings.
+impl<L: Language, T: CoreTerm<L>> CorePrint<L> for CoreBinder<L, T> {
+    fn print(&self, p: &mut Printer<L>) {
+        p.binder(self.kinds(), |p| p.print(self.peek()))
+    }
+}
+
+impl<L: Language> CorePrint<L> for CoreVariable<L> {
+    fn print(&self, p: &mut Printer<L>) {
+        match self {
+            CoreVariable::UniversalVar(v) => p.print(v),
+            CoreVariable::ExistentialVar(v) => p.print(v),
+            CoreVariable::BoundVar(v) => p.print(v),
+        }
+    }
+}
+
+impl<L: Language> CorePrint<L> for CoreUniversalVar<L> {
+    fn print(&self, p: &mut Printer<L>) {
+        p.free_variable(self.upcast())
+    }
+}
+
+impl<L: Language> CorePrint<L> for CoreExistentialVar<L> {
+    fn print(&self, p: &mut Printer<L>) {
+        p.free_variable(self.upcast())
+    }
+}
+
+impl<L: Language> CorePrint<L> for CoreBoundVar<L> {
+    fn print(&self, p: &mut Printer<L>) {
+        let Some(debruijn) = self.debruijn else {
+            return p.free_variable(self.upcast());
+        };
+
+        let name = p
+            .binders
+            .iter()
+            .rev()
+            .nth(debruijn.index)
+            .and_then(|names| names.get(self.var_index.index))
+            .unwrap_or_else(|| panic!("bound variable {self:?} is not bound by any binder"))
+            .clone();
+        p.name(&name);
+    }
+}
+
+macro_rules! print_number {
+    ($($t:ty),*) => {
+        $(
+            impl<L: Language> CorePrint<L> for $t {
+                fn print(&self, p: &mut Printer<L>) {
+                    p.token(&self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+print_number!(usize, u32, u64);
+
+impl<L: Language> CorePrint<L> for () {
+    fn print(&self, p: &mut Printer<L>) {
+        p.token("(");
+        p.no_space();
+        p.token(")");
+    }
+}
+
+impl<L: Language, A: CorePrint<L>, B: CorePrint<L>> CorePrint<L> for (A, B) {
+    fn print(&self, p: &mut Printer<L>) {
+        p.token("(");
+        p.no_space();
+        p.print(&self.0);
+        p.no_space();
+        p.token(",");
+        p.print(&self.1);
+        p.no_space();
+        p.token(")");
+    }
+}
+
+impl<L: Language, A: CorePrint<L>, B: CorePrint<L>, C: CorePrint<L>> CorePrint<L> for (A, B, C) {
+    fn print(&self, p: &mut Printer<L>) {
+        p.token("(");
+        p.no_space();
+        p.print(&self.0);
+        p.no_space();
+        p.token(",");
+        p.print(&self.1);
+        p.no_space();
+        p.token(",");
+        p.print(&self.2);
+        p.no_space();
+        p.token(")");
+    }
+}
@@ -114,6 +114,31 @@ where
     }
 }
 
+/// Serialized as a list of pairs, since formats like JSON only allow strings as map keys.
+#[cfg(feature = "serde")]
+impl<L: Language> serde::Serialize for CoreSubstitution<L>
+where
+    CoreVariable<L>: serde::Serialize,
+    CoreParameter<L>: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.map)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, L: Language> serde::Deserialize<'de> for CoreSubstitution<L>
+where
+    CoreVariable<L>: serde::Deserialize<'de>,
+    CoreParameter<L>: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pairs: Vec<(CoreVariable<L>, CoreParameter<L>)> =
+            serde::Deserialize::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
 impl<L: Language> CoreSubstitution<L> {
     pub fn apply<T: CoreFold<L>>(&self, t: &T) -> T {
         t.substitute(&mut |v| self.map.get(&v).cloned())
@@ -5,14 +5,19 @@ use crate::{
     cast::{DowncastFrom, Upcast},
     collections::Set,
     fold::CoreFold,
+    generate::CoreGenerate,
+    interner::{Internable, Interned},
     language::Language,
     parse::CoreParse,
+    print::CorePrint,
 };
 
 pub trait CoreTerm<L: Language>:
     Clone
     + CoreFold<L>
     + CoreParse<L>
+    + CorePrint<L>
+    + CoreGenerate<L>
     + Ord
     + Eq
     + Hash
@@ -32,6 +37,8 @@ impl<L: Language, T: CoreTerm<L>> CoreTerm<L> for Option<T> {}
 
 impl<L: Language, T: CoreTerm<L>> CoreTerm<L> for Arc<T> {}
 
+impl<L: Language, T: CoreTerm<L> + Internable> CoreTerm<L> for Interned<T> {}
+
 impl<L: Language> CoreTerm<L> for usize {}
 
 impl<L: Language> CoreTerm<L> for u32 {}
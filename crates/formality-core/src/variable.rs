@@ -1,10 +1,19 @@
 use crate::cast::Upcast;
+use crate::interner::Interned;
 use crate::language::CoreKind;
 use crate::language::Language;
 use crate::visit::CoreVisit;
 
 /// A term representing a variable.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "CoreKind<L>: serde::Serialize",
+        deserialize = "CoreKind<L>: serde::Deserialize<'de>"
+    ))
+)]
 pub enum CoreVariable<L: Language> {
     /// A "universal free variable" is a variable that appears
     /// free in all terms because it is bound in the environment.
@@ -36,6 +45,15 @@ impl<L: Language> CoreVariable<L> {
         }
     }
 
+    /// The name the variable was given in the source, if any.
+    pub fn name(&self) -> VarName {
+        match self {
+            CoreVariable::UniversalVar(v) => v.name,
+            CoreVariable::ExistentialVar(v) => v.name,
+            CoreVariable::BoundVar(v) => v.name,
+        }
+    }
+
     /// Shift a variable in through `binders` binding levels.
     /// Only affects bound variables.
     pub fn shift_in(&self) -> Self {
@@ -43,12 +61,14 @@ impl<L: Language> CoreVariable<L> {
             debruijn: Some(db),
             var_index,
             kind,
+            name,
         }) = self
         {
             CoreBoundVar {
                 debruijn: Some(db.shift_in()),
                 var_index: *var_index,
                 kind: *kind,
+                name: *name,
             }
             .upcast()
         } else {
@@ -64,6 +84,7 @@ impl<L: Language> CoreVariable<L> {
             debruijn: Some(db),
             var_index,
             kind,
+            name,
         }) = self
         {
             db.shift_out().map(|db1| {
@@ -71,6 +92,7 @@ impl<L: Language> CoreVariable<L> {
                     debruijn: Some(db1),
                     var_index: *var_index,
                     kind: *kind,
+                    name: *name,
                 }
                 .upcast()
             })
@@ -87,16 +109,10 @@ impl<L: Language> CoreVariable<L> {
         match self {
             CoreVariable::UniversalVar(_)
             | CoreVariable::ExistentialVar(_)
-            | CoreVariable::BoundVar(CoreBoundVar {
-                debruijn: None,
-                var_index: _,
-                kind: _,
-            }) => true,
+            | CoreVariable::BoundVar(CoreBoundVar { debruijn: None, .. }) => true,
 
             CoreVariable::BoundVar(CoreBoundVar {
-                debruijn: Some(_),
-                var_index: _,
-                kind: _,
+                debruijn: Some(_), ..
             }) => false,
         }
     }
@@ -127,9 +143,22 @@ impl<L: Language> CoreVisit<L> for CoreVariable<L> {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "CoreKind<L>: serde::Serialize",
+        deserialize = "CoreKind<L>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct CoreExistentialVar<L: Language> {
     pub kind: CoreKind<L>,
     pub var_index: VarIndex,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "VarName::is_none")
+    )]
+    pub name: VarName,
 }
 
 impl<L: Language> CoreVisit<L> for CoreExistentialVar<L> {
@@ -148,13 +177,34 @@ impl<L: Language> CoreVisit<L> for CoreExistentialVar<L> {
 /// that which we see in the environment. When we want to prove something
 /// is true for all `T` (`∀T`), we replace `T` with a universal variable.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "CoreKind<L>: serde::Serialize",
+        deserialize = "CoreKind<L>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct CoreUniversalVar<L: Language> {
     pub kind: CoreKind<L>,
     pub var_index: VarIndex,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "VarName::is_none")
+    )]
+    pub name: VarName,
 }
 
 /// Identifies a bound variable.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "CoreKind<L>: serde::Serialize",
+        deserialize = "CoreKind<L>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct CoreBoundVar<L: Language> {
     /// Identifies the binder that contained this variable, counting "outwards".
     /// When you create a binder with `Binder::new`,
@@ -162,9 +212,15 @@ pub struct CoreBoundVar<L: Language> {
     pub debruijn: Option<DebruijnIndex>,
     pub var_index: VarIndex,
     pub kind: CoreKind<L>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "VarName::is_none")
+    )]
+    pub name: VarName,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DebruijnIndex {
     pub index: usize,
 }
@@ -192,6 +248,7 @@ impl DebruijnIndex {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VarIndex {
     pub index: usize,
 }
@@ -210,5 +267,74 @@ impl std::ops::Add<usize> for VarIndex {
     }
 }
 
+/// The name a variable was given in the source, e.g. `T` in `impl<ty T>`,
+/// which is used when debug-printing the variable.
+///
+/// Binders record the names of their variables, but ignore them when compared, so that
+/// terms differing only in the names of their bound variables are equal. The variables
+/// inside a binder (those with a debruijn index) have no name of their own; they are
+/// printed with the names recorded by the enclosing binder.
+///
+/// Other variables do compare their names, so that results cached for one environment
+/// are not reused with the names of another. The names of the variables in an
+/// environment are distinct (see [`VarName::disambiguate`]).
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VarName {
+    name: Option<Interned<String>>,
+}
+
+crate::internable!(String);
+
+impl VarName {
+    /// A variable without a name.
+    pub const NONE: VarName = VarName { name: None };
+
+    pub fn new(name: &str) -> Self {
+        VarName {
+            name: Some(Interned::new(name.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        self.name.as_deref().map(|name| &name[..])
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.name.is_none()
+    }
+
+    /// The first of `name`, `name_1`, `name_2`, ... that is not `taken`
+    /// (or no name, if this variable has none).
+    pub fn disambiguate(self, taken: impl Fn(VarName) -> bool) -> VarName {
+        let Some(name) = self.name else {
+            return self;
+        };
+        std::iter::once(self)
+            .chain((1..).map(|i| VarName::new(&format!("{}_{i}", name.as_str()))))
+            .find(|candidate| !taken(*candidate))
+            .unwrap()
+    }
+
+    /// True if both variables have a name, and it is the same.
+    pub fn same_as(&self, other: VarName) -> bool {
+        self.name.is_some() && self.as_str() == other.as_str()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for VarName {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_str().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VarName {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name: Option<String> = serde::Deserialize::deserialize(deserializer)?;
+        Ok(name.map_or(VarName::NONE, |name| VarName::new(&name)))
+    }
+}
+
 mod cast_impls;
 mod debug_impls;
@@ -12,15 +12,29 @@ impl<L: Language> std::fmt::Debug for CoreVariable<L> {
 
 impl<L: Language> std::fmt::Debug for CoreUniversalVar<L> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let CoreUniversalVar { var_index, kind } = self;
-        write!(f, "!{:?}_{:?}", kind, var_index)
+        let CoreUniversalVar {
+            var_index,
+            kind,
+            name,
+        } = self;
+        match name.as_str() {
+            Some(name) => write!(f, "!{name}"),
+            None => write!(f, "!{:?}_{:?}", kind, var_index),
+        }
     }
 }
 
 impl<L: Language> std::fmt::Debug for CoreExistentialVar<L> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let CoreExistentialVar { var_index, kind } = self;
-        write!(f, "?{:?}_{:?}", kind, var_index)
+        let CoreExistentialVar {
+            var_index,
+            kind,
+            name,
+        } = self;
+        match name.as_str() {
+            Some(name) => write!(f, "?{name}"),
+            None => write!(f, "?{:?}_{:?}", kind, var_index),
+        }
     }
 }
 
@@ -32,16 +46,25 @@ impl std::fmt::Debug for VarIndex {
 
 impl<L: Language> std::fmt::Debug for CoreBoundVar<L> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self.debruijn {
+            None => self.name,
+            Some(db) => crate::binder::bound_var_name(db, self.var_index),
+        };
+        if let Some(name) = name.as_str() {
+            return write!(f, "^{name}");
+        }
         match self {
             CoreBoundVar {
                 debruijn: None,
                 var_index,
                 kind,
+                name: _,
             } => write!(f, "^{:?}_{:?}", kind, var_index),
             CoreBoundVar {
                 debruijn: Some(db),
                 var_index,
                 kind,
+                name: _,
             } => write!(f, "^{:?}{:?}_{:?}", kind, db.index, var_index),
         }
     }
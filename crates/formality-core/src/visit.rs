@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::{collections::Set, language::Language, variable::CoreVariable};
+use crate::{collections::Set, interner::Interned, language::Language, variable::CoreVariable};
 
 pub trait CoreVisit<L: Language> {
     /// Extract the list of free variables (for the purposes of this function, defined by `Variable::is_free`).
@@ -87,6 +87,20 @@ impl<L: Language, T: CoreVisit<L> + ?Sized> CoreVisit<L> for Arc<T> {
     }
 }
 
+impl<L: Language, T: CoreVisit<L>> CoreVisit<L> for Interned<T> {
+    fn free_variables(&self) -> Vec<CoreVariable<L>> {
+        T::free_variables(self)
+    }
+
+    fn size(&self) -> usize {
+        T::size(self)
+    }
+
+    fn assert_valid(&self) {
+        T::assert_valid(self)
+    }
+}
+
 impl<L: Language> CoreVisit<L> for usize {
     fn free_variables(&self) -> Vec<CoreVariable<L>> {
         vec![]
@@ -123,6 +137,18 @@ impl<L: Language> CoreVisit<L> for u128 {
     fn assert_valid(&self) {}
 }
 
+impl<L: Language> CoreVisit<L> for bool {
+    fn free_variables(&self) -> Vec<CoreVariable<L>> {
+        vec![]
+    }
+
+    fn size(&self) -> usize {
+        1
+    }
+
+    fn assert_valid(&self) {}
+}
+
 impl<L: Language> CoreVisit<L> for () {
     fn free_variables(&self) -> Vec<CoreVariable<L>> {
         vec![]
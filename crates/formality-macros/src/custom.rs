@@ -4,6 +4,8 @@ use proc_macro2::TokenStream;
 pub(crate) struct Customize {
     pub parse: bool,
     pub debug: bool,
+    pub print: bool,
+    pub generate: bool,
 }
 
 impl syn::parse::Parse for Customize {
@@ -28,6 +30,20 @@ impl syn::parse::Parse for Customize {
                     result.debug = true;
                 }
 
+                proc_macro2::TokenTree::Ident(ident) if ident == "print" => {
+                    if result.print {
+                        return Err(syn::Error::new(ident.span(), "already customizing print"));
+                    }
+                    result.print = true;
+                }
+
+                proc_macro2::TokenTree::Ident(ident) if ident == "generate" => {
+                    if result.generate {
+                        return Err(syn::Error::new(ident.span(), "already customizing generate"));
+                    }
+                    result.generate = true;
+                }
+
                 _ => {
                     return Err(syn::Error::new(
                         token.span(),
@@ -0,0 +1,60 @@
+extern crate proc_macro;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+use crate::attrs::{has_cast_attr, has_variable_attr};
+
+/// Derive the `CoreGenerate` impl, which picks a random variant and generates its fields.
+///
+/// Variants with fields spend one unit of the generator's budget, except for
+/// `#[cast]` variants, which are just another way to write their field.
+/// `#[variable]` variants pick a variable of the right kind that is in scope.
+pub(crate) fn derive_generate(mut s: synstructure::Structure) -> syn::Result<TokenStream> {
+    if let syn::Data::Union(v) = &s.ast().data {
+        return Err(syn::Error::new(
+            v.union_token.span,
+            "unions are not supported",
+        ));
+    }
+
+    s.bind_with(|_| synstructure::BindStyle::Move);
+
+    let mut alternatives = TokenStream::new();
+    for variant in s.variants() {
+        let ast = variant.ast();
+        let body = if has_variable_attr(ast.attrs) {
+            quote!(__g.variable())
+        } else {
+            let spend = if variant.bindings().is_empty() || has_cast_attr(ast.attrs) {
+                quote!()
+            } else {
+                quote!(if !__g.spend() {
+                    return None;
+                })
+            };
+            let construct = variant.construct(|field, _| {
+                let ty = &field.ty;
+                quote_spanned!(field.span() =>
+                    <#ty as generate::CoreGenerate<crate::FormalityLang>>::generate(__g)?
+                )
+            });
+            quote!(#spend Some(#construct))
+        };
+        alternatives.extend(quote_spanned!(
+            ast.ident.span() =>
+            &|__g: &mut generate::Generator<crate::FormalityLang>| { #body },
+        ));
+    }
+
+    Ok(s.gen_impl(quote! {
+        use formality_core::generate;
+
+        gen impl generate::CoreGenerate<crate::FormalityLang> for @Self {
+            fn generate(__g: &mut generate::Generator<crate::FormalityLang>) -> Option<Self> {
+                __g.one_of(&[#alternatives])
+            }
+        }
+    }))
+}
@@ -12,8 +12,10 @@ mod custom;
 mod debug;
 mod fixed_point;
 mod fold;
+mod generate;
 mod parse;
 mod precedence;
+mod print;
 mod spec;
 mod term;
 mod test;
@@ -45,6 +45,7 @@ pub(crate) fn derive_parse_with_spec(
         .any(|v| has_variable_attr(v.ast().attrs));
 
     let mut parse_variants = TokenStream::new();
+    let mut productions = TokenStream::new();
     for variant in s.variants() {
         let variant_name = Literal::string(&format!("{}::{}", s.ast().ident, variant.ast().ident));
         let v = parse_variant(variant, external_spec, any_variable_variant)?;
@@ -53,6 +54,13 @@ pub(crate) fn derive_parse_with_spec(
             variant.ast().ident.span() =>
             __parser.parse_variant(#variant_name, #precedence, |__p| { #v });
         ));
+
+        let production_name = as_literal(variant.ast().ident);
+        let symbols = grammar_variant(variant, external_spec)?;
+        productions.extend(quote_spanned!(
+            variant.ast().ident.span() =>
+            parse::Production::new(#production_name, #precedence, vec![#(#symbols),*]),
+        ));
     }
 
     let type_name: Literal = as_literal(&s.ast().ident);
@@ -62,9 +70,13 @@ pub(crate) fn derive_parse_with_spec(
         gen impl parse::CoreParse<crate::FormalityLang> for @Self {
             fn parse<'t>(scope: &parse::Scope<crate::FormalityLang>, text: &'t str) -> parse::ParseResult<'t, Self>
             {
-                parse::Parser::multi_variant(scope, text, #type_name, |__parser| {
+                parse::record_span(text, parse::Parser::multi_variant(scope, text, #type_name, |__parser| {
                     #parse_variants;
-                })
+                }))
+            }
+
+            fn grammar(__g: &mut parse::Grammar) -> parse::Symbol {
+                __g.nonterminal::<Self>(#type_name, |__g| vec![#productions])
             }
         }
     }))
@@ -264,6 +276,137 @@ fn parse_field_mode(span: Span, mode: &FieldMode) -> TokenStream {
     }
 }
 
+/// Creates the expressions for the grammar symbols of `variant`,
+/// mirroring the parser created by `parse_variant`.
+fn grammar_variant(
+    variant: &synstructure::VariantInfo,
+    external_spec: Option<&FormalitySpec>,
+) -> syn::Result<Vec<TokenStream>> {
+    let ast = variant.ast();
+
+    if let Some(spec) = external_spec {
+        return Ok(grammar_variant_with_attr(variant, spec));
+    }
+
+    if let Some(attr) = get_grammar_attr(ast.attrs) {
+        return Ok(grammar_variant_with_attr(variant, &attr?));
+    }
+
+    if variant.bindings().is_empty() {
+        let literal = Literal::string(&to_parse_ident(ast.ident));
+        Ok(vec![quote!(parse::Symbol::Keyword(#literal))])
+    } else if has_variable_attr(ast.attrs) {
+        Ok(vec![quote!(parse::Symbol::Variable)])
+    } else if has_cast_attr(ast.attrs) {
+        Ok(grammar_bindings(variant.bindings()))
+    } else {
+        let literal = Literal::string(&to_parse_ident(ast.ident));
+        let mut symbols = vec![
+            quote!(parse::Symbol::Keyword(#literal)),
+            quote!(parse::Symbol::Char('(')),
+        ];
+        symbols.extend(grammar_bindings(variant.bindings()));
+        symbols.push(quote!(parse::Symbol::optional(parse::Symbol::Char(','))));
+        symbols.push(quote!(parse::Symbol::Char(')')));
+        Ok(symbols)
+    }
+}
+
+fn grammar_variant_with_attr(
+    variant: &synstructure::VariantInfo,
+    spec: &FormalitySpec,
+) -> Vec<TokenStream> {
+    spec.symbols
+        .iter()
+        .map(|symbol| match symbol {
+            spec::FormalitySpecSymbol::Field { name, mode } => {
+                let ty = variant
+                    .bindings()
+                    .iter()
+                    .zip(0..)
+                    .find(|(b, index)| field_ident(b.ast(), *index) == *name)
+                    .map(|(b, _)| &b.ast().ty);
+                match ty {
+                    Some(ty) => grammar_field_mode(ty, mode),
+                    // The parser reports the unknown field.
+                    None => quote!(parse::Symbol::Sequence(vec![])),
+                }
+            }
+
+            spec::FormalitySpecSymbol::Keyword { ident } => {
+                let literal = as_literal(ident);
+                quote_spanned!(ident.span() => parse::Symbol::Keyword(#literal))
+            }
+
+            spec::FormalitySpecSymbol::Char { punct } => {
+                let literal = Literal::character(punct.as_char());
+                quote_spanned!(punct.span() => parse::Symbol::Char(#literal))
+            }
+
+            spec::FormalitySpecSymbol::Delimeter { text } => {
+                let literal = Literal::character(*text);
+                quote!(parse::Symbol::Char(#literal))
+            }
+        })
+        .collect()
+}
+
+fn grammar_field_mode(ty: &syn::Type, mode: &FieldMode) -> TokenStream {
+    let items = quote_spanned!(ty.span() =>
+        __g.items::<crate::FormalityLang, _>(std::marker::PhantomData::<#ty>)
+    );
+    match mode {
+        FieldMode::Single => {
+            quote_spanned!(ty.span() => <#ty as parse::CoreParse<crate::FormalityLang>>::grammar(__g))
+        }
+
+        FieldMode::Optional => {
+            let single = grammar_field_mode(ty, &FieldMode::Single);
+            quote!(parse::Symbol::optional(#single))
+        }
+
+        FieldMode::Many => quote!(parse::Symbol::many(#items)),
+
+        FieldMode::Comma => quote!(parse::Symbol::comma(#items)),
+
+        FieldMode::DelimitedVec {
+            open,
+            optional,
+            close,
+        } => {
+            let open = Literal::character(*open);
+            let close = Literal::character(*close);
+            let delimited = quote!(parse::Symbol::delimited(#open, #items, #close));
+            if *optional {
+                quote!(parse::Symbol::optional(#delimited))
+            } else {
+                delimited
+            }
+        }
+
+        FieldMode::Guarded { guard, mode } => {
+            let guard_keyword = as_literal(guard);
+            let base = grammar_field_mode(ty, mode);
+            quote!(parse::Symbol::optional(parse::Symbol::Sequence(vec![
+                parse::Symbol::Keyword(#guard_keyword),
+                #base,
+            ])))
+        }
+    }
+}
+
+/// Creates the grammar symbols for `b0, b1, ..., bN` where `bi` is a binding
+fn grammar_bindings(bindings: &[BindingInfo]) -> Vec<TokenStream> {
+    let mut symbols = vec![];
+    for (b, index) in bindings.iter().zip(0..) {
+        if index > 0 {
+            symbols.push(quote!(parse::Symbol::Char(',')));
+        }
+        symbols.push(grammar_field_mode(&b.ast().ty, &FieldMode::Single));
+    }
+    symbols
+}
+
 fn get_grammar_attr(attrs: &[Attribute]) -> Option<syn::Result<FormalitySpec>> {
     let attr = attrs.iter().find(|a| a.path().is_ident("grammar"))?;
     Some(attr.parse_args())
@@ -0,0 +1,293 @@
+extern crate proc_macro;
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Attribute};
+
+use crate::{
+    attrs::{self, precedence},
+    precedence::Precedence,
+    spec::{self, FieldMode, FormalitySpec, FormalitySpecSymbol},
+};
+
+/// Derive the `CorePrint` impl, using an optional grammar supplied "from the outside".
+/// This is used by the `#[term(G)]` macro, which supplies the grammar `G`.
+///
+/// The printed text follows the grammar that `derive_parse_with_spec` parses,
+/// with the same spacing as the `Debug` impl.
+pub(crate) fn derive_print_with_spec(
+    s: synstructure::Structure,
+    external_spec: Option<&FormalitySpec>,
+) -> syn::Result<TokenStream> {
+    if let syn::Data::Union(v) = &s.ast().data {
+        return Err(syn::Error::new(
+            v.union_token.span,
+            "unions are not supported",
+        ));
+    }
+
+    let mut print_arms = TokenStream::new();
+    for variant in s.variants() {
+        let pat = variant.pat();
+        let precedence = precedence(variant.ast().attrs)?;
+        let precedence_expr = precedence.expr();
+        let v = print_variant(variant, external_spec, &precedence)?;
+        print_arms.extend(quote_spanned!(
+            variant.ast().ident.span() =>
+            #pat => __p.variant::<Self>(#precedence_expr, |__p| { #v }),
+        ));
+    }
+
+    Ok(s.gen_impl(quote! {
+        use formality_core::print;
+
+        gen impl print::CorePrint<crate::FormalityLang> for @Self {
+            fn print(&self, __p: &mut print::Printer<crate::FormalityLang>)
+            {
+                match self {
+                    #print_arms
+                }
+            }
+        }
+    }))
+}
+
+fn print_variant(
+    variant: &synstructure::VariantInfo,
+    external_spec: Option<&FormalitySpec>,
+    precedence: &Precedence,
+) -> syn::Result<TokenStream> {
+    let ast = variant.ast();
+
+    // When invoked like `#[term(foo)]`, use the spec from `foo`
+    if let Some(spec) = external_spec {
+        return Ok(print_variant_with_attr(variant, spec, precedence));
+    }
+
+    // Else, look for a `#[grammar]` attribute on the variant
+    if let Some(attr) = get_grammar_attr(ast.attrs) {
+        return Ok(print_variant_with_attr(variant, &attr?, precedence));
+    }
+
+    // If no `#[grammar(...)]` attribute is provided, then we mirror the default parse.
+
+    if variant.bindings().is_empty() {
+        // No bindings (e.g., `Foo`) -- just print a keyword `foo`
+        let literal = Literal::string(&to_parse_ident(ast.ident));
+        Ok(quote! {
+            __p.token(#literal);
+        })
+    } else if attrs::has_isa_attr(variant.ast().attrs) {
+        // `#[cast]` or `#[variable]` -- just print the bindings (comma separated, if needed)
+        let streams: Vec<_> = variant
+            .bindings()
+            .iter()
+            .zip(0..)
+            .map(|(bi, i)| {
+                let binding = &bi.binding;
+                if i == 0 {
+                    quote! { __p.print(#binding); }
+                } else {
+                    quote! { __p.no_space(); __p.token(","); __p.print(#binding); }
+                }
+            })
+            .collect();
+        Ok(quote!(#(#streams)*))
+    } else {
+        // Otherwise -- print `variant(binding0, ..., bindingN)`
+        let literal = Literal::string(&to_parse_ident(ast.ident));
+        let streams: Vec<_> = variant
+            .bindings()
+            .iter()
+            .zip(0..)
+            .map(|(bi, i)| {
+                let binding = &bi.binding;
+                if i == 0 {
+                    quote! { __p.print(#binding); }
+                } else {
+                    quote! { __p.no_space(); __p.token(","); __p.print(#binding); }
+                }
+            })
+            .collect();
+        Ok(quote! {
+            __p.token(#literal);
+            __p.no_space();
+            __p.token("(");
+            __p.no_space();
+            #(#streams)*
+            __p.no_space();
+            __p.token(")");
+        })
+    }
+}
+
+/// Print according to the grammar, inserting the same whitespace as the `Debug` impl.
+fn print_variant_with_attr(
+    variant: &synstructure::VariantInfo,
+    spec: &FormalitySpec,
+    precedence: &Precedence,
+) -> TokenStream {
+    let mut stream = TokenStream::new();
+
+    for (b, index) in variant.bindings().iter().zip(0..) {
+        let binding_field = &b.binding;
+        let spec_field = field_ident(b.ast(), index);
+        stream.extend(quote!(let #spec_field = #binding_field;));
+    }
+
+    let precedence_expr = precedence.expr();
+    let mut prev_op: Option<&FormalitySpecSymbol> = None;
+    for (op, op_index) in spec.symbols.iter().zip(0..) {
+        // insert whitespace if needed
+        let suppress_space = matches!(
+            (prev_op, op),
+            // consecutive characters don't need spaces
+            (
+                Some(spec::FormalitySpecSymbol::Char { .. }),
+                spec::FormalitySpecSymbol::Char { .. },
+            )
+            // `foo(` looks better than `foo (`, but `{ foo }` is better than `{ foo}`
+            | (
+                Some(spec::FormalitySpecSymbol::Keyword { .. }),
+                spec::FormalitySpecSymbol::Delimeter { text: '(' | '[' | '{' },
+            )
+            // consecutive delimeters don't need spaces
+            | (
+                Some(spec::FormalitySpecSymbol::Delimeter { .. }),
+                spec::FormalitySpecSymbol::Delimeter { .. },
+            )
+        );
+        prev_op = Some(op);
+
+        if suppress_space {
+            stream.extend(quote!(__p.no_space();));
+        }
+
+        stream.extend(match op {
+            spec::FormalitySpecSymbol::Field {
+                name,
+                mode: FieldMode::Single,
+            } if matches!(precedence, Precedence::Parsed { .. }) => {
+                // A recursive occurrence of this type may need parentheses,
+                // depending on whether the parser sees it at the start of the variant or not.
+                let left = op_index == 0;
+                quote_spanned! { name.span() =>
+                    __p.print_at::<Self, _>(#name, #precedence_expr.min_recursive_level(#left));
+                }
+            }
+
+            spec::FormalitySpecSymbol::Field { name, mode } => print_field_with_mode(name, mode),
+
+            spec::FormalitySpecSymbol::Keyword { ident } => {
+                let literal = as_literal(ident);
+                quote_spanned!(ident.span() =>
+                    __p.token(#literal);
+                )
+            }
+
+            spec::FormalitySpecSymbol::Char { punct } => {
+                let literal = Literal::string(&punct.as_char().to_string());
+                quote_spanned!(punct.span() =>
+                    __p.token(#literal);
+                )
+            }
+
+            spec::FormalitySpecSymbol::Delimeter { text } => {
+                let literal = Literal::string(&text.to_string());
+                match text {
+                    '(' | '[' => quote!(
+                        __p.token(#literal);
+                        __p.no_space();
+                    ),
+                    ')' | ']' => quote!(
+                        __p.no_space();
+                        __p.token(#literal);
+                    ),
+                    _ => quote!(
+                        __p.token(#literal);
+                    ),
+                }
+            }
+        });
+    }
+
+    stream
+}
+
+fn print_field_with_mode(name: &Ident, mode: &FieldMode) -> TokenStream {
+    match mode {
+        FieldMode::Single => {
+            quote_spanned! { name.span() =>
+                __p.print(#name);
+            }
+        }
+
+        FieldMode::Optional => {
+            quote_spanned! { name.span() =>
+                if !::formality_core::util::is_default(#name) {
+                    __p.print(#name);
+                }
+            }
+        }
+
+        FieldMode::Many => {
+            quote_spanned! { name.span() =>
+                __p.many(#name);
+            }
+        }
+
+        FieldMode::Comma => {
+            quote_spanned! { name.span() =>
+                __p.comma(#name);
+            }
+        }
+
+        FieldMode::DelimitedVec {
+            open,
+            optional,
+            close,
+        } => {
+            let open = Literal::string(&open.to_string());
+            let close = Literal::string(&close.to_string());
+            quote_spanned! { name.span() =>
+                if !#optional || !#name.is_empty() {
+                    __p.delimited(#open, #name, #close);
+                }
+            }
+        }
+
+        FieldMode::Guarded { guard, mode } => {
+            let guard = as_literal(guard);
+            let base = print_field_with_mode(name, mode);
+
+            quote_spanned! { name.span() =>
+                if !::formality_core::util::is_default(#name) {
+                    __p.token(#guard);
+                    #base
+                }
+            }
+        }
+    }
+}
+
+fn get_grammar_attr(attrs: &[Attribute]) -> Option<syn::Result<FormalitySpec>> {
+    let attr = attrs.iter().find(|a| a.path().is_ident("grammar"))?;
+    Some(attr.parse_args())
+}
+
+fn as_literal(ident: &Ident) -> Literal {
+    Literal::string(&ident.to_string())
+}
+
+/// Convert a name like `Foo` into the name we expect to parse (`foo`).
+fn to_parse_ident(ident: &Ident) -> String {
+    ident.to_string().to_case(Case::Snake)
+}
+
+fn field_ident(field: &syn::Field, index: usize) -> syn::Ident {
+    match &field.ident {
+        Some(field_name) => field_name.clone(),
+        None => syn::Ident::new(&format!("v{}", index), field.span()),
+    }
+}
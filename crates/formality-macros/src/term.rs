@@ -7,7 +7,9 @@ use crate::{
     cast::{downcast_impls, upcast_impls},
     debug::derive_debug_with_spec,
     fold::derive_fold,
+    generate::derive_generate,
     parse::derive_parse_with_spec,
+    print::derive_print_with_spec,
     spec::FormalitySpec,
     visit::derive_visit,
 };
@@ -32,25 +34,56 @@ pub fn term(spec: Option<FormalitySpec>, mut input: DeriveInput) -> syn::Result<
             spec.as_ref(),
         ))
     };
+    let print_impl = if customize.print {
+        None
+    } else {
+        Some(derive_print_with_spec(
+            synstructure::Structure::new(&input),
+            spec.as_ref(),
+        )?)
+    };
+    let generate_impl = if customize.generate {
+        None
+    } else {
+        Some(derive_generate(synstructure::Structure::new(&input))?)
+    };
     let term_impl = derive_term(synstructure::Structure::new(&input));
     let downcast_impls = downcast_impls(synstructure::Structure::new(&input));
     let upcast_impls = upcast_impls(synstructure::Structure::new(&input));
     remove_formality_attributes(&mut input);
+    let serde_derive = serde_derive();
 
     Ok(quote! {
         #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #serde_derive
         #input
 
         #fold_impl
         #visit_impl
         #parse_impl
         #debug_impl
+        #print_impl
+        #generate_impl
         #term_impl
         #(#downcast_impls)*
         #(#upcast_impls)*
     })
 }
 
+/// With the `serde` feature, terms also derive `Serialize` and `Deserialize`
+/// (through the serde re-exported by `formality_core`, so that crates
+/// defining terms do not need to depend on serde themselves).
+fn serde_derive() -> Option<TokenStream> {
+    if cfg!(feature = "serde") {
+        Some(quote! {
+            #[derive(formality_core::serde::Serialize, formality_core::serde::Deserialize)]
+            #[serde(crate = "formality_core::serde")]
+        })
+    } else {
+        None
+    }
+}
+
 fn derive_term(mut s: synstructure::Structure) -> TokenStream {
     s.underscore_const(true);
     s.bind_with(|_| synstructure::BindStyle::Move);
@@ -1,14 +1,44 @@
-use formality_core::{set, Set, Upcast};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use formality_core::{cast_impl, set, Map, Set, Upcast};
 use formality_macros::term;
 use formality_types::grammar::{
-    AdtId, AliasName, AliasTy, Binder, Parameter, Predicate, Relation, TraitId, TraitRef, Ty, Wc,
-    Wcs, PR,
+    AdtId, AliasName, AliasTy, Binder, FieldName, Parameter, Predicate, Relation, TraitId,
+    TraitRef, Ty, VariantId, Wc, Wcs, PR,
 };
 
-#[term]
+/// The declarations from a program that are relevant to the solver.
+///
+/// Constructed from a [`DeclsData`] via [`Decls::new`], which indexes the declarations
+/// (e.g., impls by trait) so that the lookups done by the solver do not need to scan
+/// every declaration. Cloning is cheap. Equality, hashing, and debug output are based
+/// only on the [`DeclsData`].
+#[derive(Clone)]
 pub struct Decls {
+    data: Arc<DeclsData>,
+    index: Arc<DeclsIndex>,
+
+    /// Hash of `data`, computed once since decls are part of the key for every judgment.
+    hash: u64,
+}
+
+/// The declarations that make up [`Decls`], in program order.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
+pub struct DeclsData {
     pub max_size: usize,
 
+    /// How deeply [`prove`](`crate::prove`) may recurse before giving up with an overflow.
+    pub max_depth: usize,
+
     /// Each trait in the program
     pub trait_decls: Vec<TraitDecl>,
     pub impl_decls: Vec<ImplDecl>,
@@ -16,88 +46,264 @@ pub struct Decls {
     pub alias_eq_decls: Vec<AliasEqDecl>,
     pub alias_bound_decls: Vec<AliasBoundDecl>,
     pub adt_decls: Vec<AdtDecl>,
+    pub drop_decls: Vec<DropDecl>,
     pub local_trait_ids: Set<TraitId>,
     pub local_adt_ids: Set<AdtId>,
 }
 
+impl DeclsData {
+    pub fn empty() -> Self {
+        Self {
+            max_size: Decls::DEFAULT_MAX_SIZE,
+            max_depth: Decls::DEFAULT_MAX_DEPTH,
+            trait_decls: vec![],
+            impl_decls: vec![],
+            neg_impl_decls: vec![],
+            alias_eq_decls: vec![],
+            alias_bound_decls: vec![],
+            adt_decls: vec![],
+            drop_decls: vec![],
+            local_trait_ids: set![],
+            local_adt_ids: set![],
+        }
+    }
+}
+
+/// Declarations from a [`DeclsData`] grouped by the name they are looked up by.
+/// Duplicate names are kept (and reported on lookup) since the program may not
+/// have been checked for them yet.
+#[derive(Default)]
+struct DeclsIndex {
+    trait_decls: Map<TraitId, Vec<TraitDecl>>,
+    impl_decls: Map<TraitId, Vec<ImplDecl>>,
+    neg_impl_decls: Map<TraitId, Vec<NegImplDecl>>,
+    trait_invariants: Set<TraitInvariant>,
+    alias_eq_decls: Map<AliasName, Vec<AliasEqDecl>>,
+    alias_bound_decls: Map<AliasName, Vec<AliasBoundDecl>>,
+    adt_decls: Map<AdtId, Vec<AdtDecl>>,
+    drop_decls: Map<AdtId, Vec<DropDecl>>,
+}
+
+impl DeclsIndex {
+    fn new(data: &DeclsData) -> Self {
+        fn group<K: Ord, V: Clone>(values: &[V], key: impl Fn(&V) -> K) -> Map<K, Vec<V>> {
+            let mut map: Map<K, Vec<V>> = Map::new();
+            for value in values {
+                map.entry(key(value)).or_default().push(value.clone());
+            }
+            map
+        }
+
+        DeclsIndex {
+            trait_decls: group(&data.trait_decls, |t| t.id.clone()),
+            impl_decls: group(&data.impl_decls, |i| {
+                i.binder.peek().trait_ref.trait_id.clone()
+            }),
+            neg_impl_decls: group(&data.neg_impl_decls, |i| {
+                i.binder.peek().trait_ref.trait_id.clone()
+            }),
+            trait_invariants: data
+                .trait_decls
+                .iter()
+                .flat_map(|td| td.trait_invariants())
+                .collect(),
+            alias_eq_decls: group(&data.alias_eq_decls, |a| a.alias_name()),
+            alias_bound_decls: group(&data.alias_bound_decls, |a| a.alias_name()),
+            adt_decls: group(&data.adt_decls, |a| a.id.clone()),
+            drop_decls: group(&data.drop_decls, |d| d.adt_id.clone()),
+        }
+    }
+}
+
 impl Decls {
     /// Max size used in unit tests that are not stress testing maximum size.
     pub const DEFAULT_MAX_SIZE: usize = 222;
 
+    /// Default for [`DeclsData::max_depth`].
+    pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+    pub fn new(data: DeclsData) -> Self {
+        let index = DeclsIndex::new(&data);
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        Decls {
+            data: Arc::new(data),
+            index: Arc::new(index),
+            hash: hasher.finish(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Decls::new(DeclsData::empty())
+    }
+
+    /// The declarations, in program order.
+    pub fn data(&self) -> &DeclsData {
+        &self.data
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.data.max_size
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.data.max_depth
+    }
+
     pub fn is_local_trait_id(&self, trait_id: &TraitId) -> bool {
-        self.local_trait_ids.contains(trait_id)
+        self.data.local_trait_ids.contains(trait_id)
     }
 
     pub fn is_local_adt_id(&self, adt_id: &AdtId) -> bool {
-        self.local_adt_ids.contains(adt_id)
+        self.data.local_adt_ids.contains(adt_id)
     }
 
-    pub fn impl_decls<'s>(&'s self, trait_id: &'s TraitId) -> impl Iterator<Item = &'s ImplDecl> {
-        self.impl_decls
-            .iter()
-            .filter(move |i| i.binder.peek().trait_ref.trait_id == *trait_id)
+    pub fn impl_decls(&self, trait_id: &TraitId) -> impl Iterator<Item = &ImplDecl> {
+        lookup(&self.index.impl_decls, trait_id).iter()
     }
 
-    pub fn neg_impl_decls<'s>(
-        &'s self,
-        trait_id: &'s TraitId,
-    ) -> impl Iterator<Item = &'s NegImplDecl> {
-        self.neg_impl_decls
-            .iter()
-            .filter(move |i| i.binder.peek().trait_ref.trait_id == *trait_id)
+    pub fn neg_impl_decls(&self, trait_id: &TraitId) -> impl Iterator<Item = &NegImplDecl> {
+        lookup(&self.index.neg_impl_decls, trait_id).iter()
     }
 
     pub fn trait_decl(&self, trait_id: &TraitId) -> &TraitDecl {
-        let mut v: Vec<_> = self
-            .trait_decls
-            .iter()
-            .filter(|t| t.id == *trait_id)
-            .collect();
+        let v = lookup(&self.index.trait_decls, trait_id);
         assert!(!v.is_empty(), "no traits named `{trait_id:?}`");
         assert!(v.len() <= 1, "multiple traits named `{trait_id:?}`");
-        v.pop().unwrap()
+        &v[0]
     }
 
-    pub fn alias_eq_decls<'s>(
-        &'s self,
-        name: &'s AliasName,
-    ) -> impl Iterator<Item = &'s AliasEqDecl> {
-        self.alias_eq_decls
-            .iter()
-            .filter(move |a| a.alias_name() == *name)
+    pub fn alias_eq_decls(&self, name: &AliasName) -> impl Iterator<Item = &AliasEqDecl> {
+        lookup(&self.index.alias_eq_decls, name).iter()
     }
 
-    pub fn alias_bound_decls(&self) -> &[AliasBoundDecl] {
-        &self.alias_bound_decls
+    pub fn alias_bound_decls(&self, name: &AliasName) -> impl Iterator<Item = &AliasBoundDecl> {
+        lookup(&self.index.alias_bound_decls, name).iter()
     }
 
     pub fn adt_decl(&self, adt_id: &AdtId) -> &AdtDecl {
-        let mut v: Vec<_> = self.adt_decls.iter().filter(|t| t.id == *adt_id).collect();
+        let v = lookup(&self.index.adt_decls, adt_id);
         assert!(!v.is_empty(), "no ADT named `{adt_id:?}`");
         assert!(v.len() <= 1, "multiple ADTs named `{adt_id:?}`");
-        v.pop().unwrap()
+        &v[0]
+    }
+
+    /// Returns the destructors declared for the given ADT. There is normally at most one;
+    /// duplicates are reported by the coherence check, and the solver requires all of them.
+    pub fn drop_decls(&self, adt_id: &AdtId) -> impl Iterator<Item = &DropDecl> {
+        lookup(&self.index.drop_decls, adt_id).iter()
     }
 
     /// Return the set of "trait invariants" for all traits.
     /// See [`TraitDecl::trait_invariants`].
-    pub fn trait_invariants(&self) -> Set<TraitInvariant> {
-        self.trait_decls
-            .iter()
-            .flat_map(|td| td.trait_invariants())
-            .collect()
+    pub fn trait_invariants(&self) -> &Set<TraitInvariant> {
+        &self.index.trait_invariants
     }
+}
 
-    pub fn empty() -> Self {
-        Self {
-            max_size: Decls::DEFAULT_MAX_SIZE,
-            trait_decls: vec![],
-            impl_decls: vec![],
-            neg_impl_decls: vec![],
-            alias_eq_decls: vec![],
-            alias_bound_decls: vec![],
-            adt_decls: vec![],
-            local_trait_ids: set![],
-            local_adt_ids: set![],
+fn lookup<'m, K: Ord, V>(map: &'m Map<K, Vec<V>>, key: &K) -> &'m [V] {
+    map.get(key).map(|v| &v[..]).unwrap_or(&[])
+}
+
+impl From<DeclsData> for Decls {
+    fn from(data: DeclsData) -> Self {
+        Decls::new(data)
+    }
+}
+
+cast_impl!(Decls);
+
+/// Serialized as its [`DeclsData`]; the index is rebuilt on deserialization.
+#[cfg(feature = "serde")]
+impl formality_core::serde::Serialize for Decls {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: formality_core::serde::Serializer,
+    {
+        self.data.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> formality_core::serde::Deserialize<'de> for Decls {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: formality_core::serde::Deserializer<'de>,
+    {
+        DeclsData::deserialize(deserializer).map(Decls::new)
+    }
+}
+
+impl PartialEq for Decls {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data) || (self.hash == other.hash && self.data == other.data)
+    }
+}
+
+impl Eq for Decls {}
+
+impl PartialOrd for Decls {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decls {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.data.cmp(&other.data)
+    }
+}
+
+impl Hash for Decls {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl Debug for Decls {
+    // Matches the output of `#[term]`, which `Decls` used to be.
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let DeclsData {
+            max_size,
+            max_depth,
+            trait_decls,
+            impl_decls,
+            neg_impl_decls,
+            alias_eq_decls,
+            alias_bound_decls,
+            adt_decls,
+            drop_decls,
+            local_trait_ids,
+            local_adt_ids,
+        } = &*self.data;
+        if fmt.alternate() {
+            fmt.debug_struct("Decls")
+                .field("max_size", max_size)
+                .field("max_depth", max_depth)
+                .field("trait_decls", trait_decls)
+                .field("impl_decls", impl_decls)
+                .field("neg_impl_decls", neg_impl_decls)
+                .field("alias_eq_decls", alias_eq_decls)
+                .field("alias_bound_decls", alias_bound_decls)
+                .field("adt_decls", adt_decls)
+                .field("drop_decls", drop_decls)
+                .field("local_trait_ids", local_trait_ids)
+                .field("local_adt_ids", local_adt_ids)
+                .finish()
+        } else {
+            fmt.debug_tuple("decls")
+                .field(max_size)
+                .field(max_depth)
+                .field(trait_decls)
+                .field(impl_decls)
+                .field(neg_impl_decls)
+                .field(alias_eq_decls)
+                .field(alias_bound_decls)
+                .field(adt_decls)
+                .field(drop_decls)
+                .field(local_trait_ids)
+                .field(local_adt_ids)
+                .finish()
         }
     }
 }
@@ -267,10 +473,9 @@ pub struct AliasBoundDeclBoundData {
     pub where_clause: Wcs,
 }
 
-/// An "ADT declaration" declares an ADT name, its generics, and its where-clauses.
-/// It doesn't capture the ADT fields, yet.
+/// An "ADT declaration" declares an ADT name, its generics, its where-clauses, and its variants.
 ///
-/// In Rust syntax, it covers the `struct Foo<X> where X: Bar` part of the declaration, but not what appears in the `{...}`.
+/// In Rust syntax, it covers `struct Foo<X> where X: Bar { ... }`. Structs have a single variant.
 #[term(adt $id $binder)]
 pub struct AdtDecl {
     /// The name of the ADT.
@@ -281,8 +486,49 @@ pub struct AdtDecl {
 }
 
 /// The "bound data" for a [`AdtDecl`][].
-#[term($:where $where_clause)]
+#[term($:where $where_clause ${?variants})]
 pub struct AdtDeclBoundData {
     /// The where-clauses declared on the ADT,
     pub where_clause: Wcs,
+
+    /// The variants of the ADT; used to compute drop glue.
+    pub variants: Vec<AdtDeclVariant>,
+}
+
+impl AdtDeclBoundData {
+    /// The types of all fields across all variants.
+    pub fn field_tys(&self) -> Vec<Ty> {
+        self.variants
+            .iter()
+            .flat_map(|v| v.fields.iter().map(|f| f.ty.clone()))
+            .collect()
+    }
+}
+
+#[term($name { $,fields })]
+pub struct AdtDeclVariant {
+    pub name: VariantId,
+    pub fields: Vec<AdtDeclField>,
+}
+
+#[term($name : $ty)]
+pub struct AdtDeclField {
+    pub name: FieldName,
+    pub ty: Ty,
+}
+
+/// A "drop declaration" indicates that an ADT has a destructor, i.e., an impl of the `Drop` lang item.
+/// The binder covers the generics of the ADT (which, for a well-formed impl, are the same as the impl's).
+#[term(drop $adt_id $binder)]
+pub struct DropDecl {
+    pub adt_id: AdtId,
+    pub binder: Binder<DropDeclBoundData>,
+}
+
+/// Data bound under the ADT generics for a [`DropDecl`][]
+#[term($:may_dangle $(may_dangle))]
+pub struct DropDeclBoundData {
+    /// Parameters the destructor promises not to access (`#[may_dangle]`);
+    /// they need not outlive the point of the drop.
+    pub may_dangle: Vec<Parameter>,
 }
@@ -7,6 +7,11 @@ mod prove;
 
 pub use decls::*;
 pub use prove::prove;
+pub use prove::prove_failures;
+pub use prove::prove_with_proof_trees;
+pub use prove::type_of_const;
+pub use prove::AmbiguityCause;
+pub use prove::CandidatePreference;
 pub use prove::Constraints;
 pub use prove::Env;
 
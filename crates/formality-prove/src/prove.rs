@@ -4,14 +4,27 @@ mod env;
 mod is_local;
 mod minimize;
 mod prove_after;
+mod prove_drop;
 mod prove_eq;
 mod prove_normalize;
+mod prove_sized;
+mod prove_valtree;
 mod prove_via;
 mod prove_wc;
 mod prove_wc_list;
 mod prove_wf;
+mod trait_candidate;
+mod type_of_const;
 
-pub use constraints::Constraints;
+pub use constraints::{AmbiguityCause, Constraints};
+pub(crate) use prove_valtree::prove_valtree_wf;
+pub use type_of_const::type_of_const;
+
+use std::cell::Cell;
+
+use formality_core::fixed_point;
+use formality_core::judgment::failure::{self, FailedJudgment, Input};
+use formality_core::judgment::proof_tree::{self, ProofTree};
 use formality_core::visit::CoreVisit;
 use formality_core::{set, Set, Upcast};
 use formality_types::grammar::Wcs;
@@ -19,9 +32,14 @@ use tracing::Level;
 
 use crate::decls::Decls;
 
-pub use self::env::Env;
+pub use self::env::{CandidatePreference, Env};
 use self::prove_wc_list::prove_wc_list;
 
+thread_local! {
+    /// Number of calls to [`prove`] in progress on this thread.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
 /// Top-level entry point for proving things; other rules recurse to this one.
 pub fn prove(
     decls: impl Upcast<Decls>,
@@ -34,24 +52,47 @@ pub fn prove(
     let assumptions: Wcs = assumptions.upcast();
     let goal: Wcs = goal.upcast();
 
+    let proof_tree_inputs = proof_tree::is_recording().then(|| {
+        vec![
+            ("goal", format!("{goal:?}")),
+            ("assumptions", format!("{assumptions:?}")),
+            ("env", format!("{env:?}")),
+            ("decls", format!("{decls:?}")),
+        ]
+    });
+
     let (env, (assumptions, goal), min) = minimize::minimize(env, (assumptions, goal));
 
     let span = tracing::span!(Level::DEBUG, "prove", ?goal, ?assumptions, ?env, ?decls);
     let _guard = span.enter();
 
     let term_in = (&assumptions, &goal);
-    if term_in.size() > decls.max_size {
+    if term_in.size() > decls.max_size() {
         tracing::debug!(
             "term has size {} which exceeds max size of {}",
             term_in.size(),
-            decls.max_size
+            decls.max_size()
         );
-        return set![Constraints::none(env).ambiguous()];
+        let result = min.reconstitute(Constraints::overflow(env, goal));
+        report_proof_trees(proof_tree_inputs, "max size exceeded", [(&result, vec![])]);
+        return set![result];
     }
 
     assert!(env.encloses(term_in));
 
-    let result_set = prove_wc_list(decls, &env, assumptions, goal);
+    let depth = DEPTH.with(|d| d.get());
+    if depth >= decls.max_depth() {
+        tracing::debug!("exceeded max depth of {}", decls.max_depth());
+        // Unlike the size check, this depends on the callers and not only on our inputs.
+        fixed_point::uncacheable();
+        let result = min.reconstitute(Constraints::overflow(env, goal));
+        report_proof_trees(proof_tree_inputs, "max depth exceeded", [(&result, vec![])]);
+        return set![result];
+    }
+
+    DEPTH.with(|d| d.set(depth + 1));
+    let (result_set, trees) = proof_tree::capture(|| prove_wc_list(decls, &env, assumptions, goal));
+    DEPTH.with(|d| d.set(depth));
 
     result_set.iter().for_each(|constraints1| {
         assert!(constraints1.is_valid_extension_of(&env));
@@ -59,8 +100,96 @@ pub fn prove(
 
     tracing::debug!(?result_set);
 
+    // Merge answers that differ only in their fresh variables, then drop
+    // those that are instances of a more general answer.
+    let canonical: Set<(Constraints, Constraints)> = result_set
+        .into_iter()
+        .map(|r| (r.clone().canonicalize(&env), r))
+        .collect();
+    let answers: Set<&Constraints> = canonical.iter().map(|(c, _)| c).collect();
+    let result_set: Set<(Constraints, &Constraints)> = answers
+        .iter()
+        .filter(|c| !answers.iter().any(|a| a.subsumes(c, &env)))
+        .map(|&c| (min.reconstitute(c.clone()), c))
+        .collect();
+
+    report_proof_trees(
+        proof_tree_inputs,
+        "prove",
+        result_set.iter().map(|(result, c)| {
+            let outputs: Vec<String> = canonical
+                .iter()
+                .filter(|(c1, _)| c1 == *c)
+                .map(|(_, r)| format!("{r:?}"))
+                .collect();
+            let children = trees
+                .iter()
+                .filter(|t| outputs.contains(&t.output))
+                .cloned()
+                .collect();
+            (result, children)
+        }),
+    );
+
+    result_set.into_iter().map(|(result, _)| result).collect()
+}
+
+/// Like [`prove`], but also returns the proof tree that derives each result.
+pub fn prove_with_proof_trees(
+    decls: impl Upcast<Decls>,
+    env: impl Upcast<Env>,
+    assumptions: impl Upcast<Wcs>,
+    goal: impl Upcast<Wcs>,
+) -> Set<(Constraints, ProofTree)> {
+    let (result_set, trees) =
+        proof_tree::record(|| proof_tree::capture(|| prove(decls, env, assumptions, goal)));
     result_set
         .into_iter()
-        .map(|r| min.reconstitute(r))
+        .map(|c| {
+            let output = format!("{c:?}");
+            let tree = trees.iter().find(|t| t.output == output).unwrap();
+            (c, tree.clone())
+        })
         .collect()
 }
+
+/// Proves `goal` while recording failures, returning the judgments that failed.
+/// Used to explain why [`prove`] produced no results.
+/// The declarations are the same throughout, so they are left out of the failures.
+pub fn prove_failures(
+    decls: impl Upcast<Decls>,
+    env: impl Upcast<Env>,
+    assumptions: impl Upcast<Wcs>,
+    goal: impl Upcast<Wcs>,
+) -> Vec<FailedJudgment> {
+    let decls: Decls = decls.upcast();
+    let elided = vec![("decls", Input::new(decls.clone()))];
+    let (_, failures) = failure::record_eliding(elided, || {
+        failure::capture(|| prove(decls, env, assumptions, goal))
+    });
+    failures
+}
+
+/// Reports a tree for each `(output, children)` pair to the enclosing judgment, if recording.
+/// `prove` is not a judgment itself, but it appears in proof trees as though it were.
+fn report_proof_trees<'a>(
+    inputs: Option<Vec<(&'static str, String)>>,
+    rule: &'static str,
+    outputs: impl IntoIterator<Item = (&'a Constraints, Vec<ProofTree>)>,
+) {
+    let Some(inputs) = inputs else {
+        return;
+    };
+    proof_tree::report(
+        outputs
+            .into_iter()
+            .map(|(output, children)| ProofTree {
+                judgment: "prove",
+                inputs: inputs.clone(),
+                rule,
+                output: format!("{output:?}"),
+                children,
+            })
+            .collect(),
+    );
+}
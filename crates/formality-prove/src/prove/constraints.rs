@@ -1,15 +1,48 @@
 use super::env::Env;
-use formality_core::{cast_impl, visit::CoreVisit, Downcast, Upcast, UpcastFrom};
+use formality_core::{cast_impl, set, visit::CoreVisit, Downcast, Set, Upcast, UpcastFrom};
+use formality_macros::term;
 use formality_types::{
-    grammar::{ExistentialVar, Parameter, Substitution, Variable},
+    grammar::{ExistentialVar, Parameter, Substitution, TraitRef, Variable, Wcs},
     rust::Visit,
 };
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
 pub struct Constraints {
     pub env: Env,
     pub known_true: bool,
     pub substitution: Substitution,
+
+    /// Why the constraints are not known to be true; empty if `known_true`.
+    /// The causes are kept for diagnostics only; their variables are not
+    /// tracked as part of the constraints.
+    pub ambiguity: Set<AmbiguityCause>,
+}
+
+/// Why the solver could not tell whether a goal holds.
+#[term]
+pub enum AmbiguityCause {
+    /// In coherence mode, an impl of the trait ref may exist in another crate.
+    #[grammar(may_be_remote($v0))]
+    MayBeRemote(TraitRef),
+
+    /// The answer depends on how an existential variable is inferred.
+    #[grammar(unresolved($v0))]
+    Unresolved(Parameter),
+
+    /// The solver gave up while proving the goal because it exceeded the size or
+    /// depth limits of the [`Decls`](`crate::Decls`).
+    #[grammar(overflow($v0))]
+    Overflow(Wcs),
+
+    /// The goal has several solutions that bind variables differently;
+    /// see [`Constraints::collapse`].
+    #[grammar(multiple_solutions)]
+    MultipleSolutions,
 }
 
 cast_impl!(Constraints);
@@ -52,6 +85,7 @@ impl Constraints {
             env,
             substitution,
             known_true: true,
+            ambiguity: set![],
         };
         c2.assert_valid();
         c2
@@ -65,13 +99,74 @@ impl Constraints {
         &self.substitution
     }
 
-    pub fn ambiguous(self) -> Constraints {
+    pub fn ambiguous(self, cause: impl Upcast<AmbiguityCause>) -> Constraints {
+        let mut ambiguity = self.ambiguity;
+        ambiguity.insert(cause.upcast());
         Self {
             known_true: false,
+            ambiguity,
             ..self
         }
     }
 
+    /// Constraints reporting that the solver gave up while proving `goal`.
+    pub fn overflow(env: Env, goal: impl Upcast<Wcs>) -> Constraints {
+        Self::none(env).ambiguous(AmbiguityCause::Overflow(goal.upcast()))
+    }
+
+    /// The goal that overflowed, if the solver gave up.
+    pub fn overflowed(&self) -> Option<&Wcs> {
+        self.ambiguity.iter().find_map(|cause| match cause {
+            AmbiguityCause::Overflow(goal) => Some(goal),
+            _ => None,
+        })
+    }
+
+    /// Collapses the `solutions` to a goal proven in `env` into a single one, in the
+    /// style of chalk. A single solution is returned as is. Several solutions yield
+    /// ambiguous constraints whose substitution is the *guidance*: the bindings for the
+    /// variables of `env` that all the solutions agree on. Returns `None` if there are
+    /// no solutions.
+    pub fn collapse<'a>(
+        env: &Env,
+        solutions: impl IntoIterator<Item = &'a Constraints>,
+    ) -> Option<Constraints> {
+        let solutions: Vec<&Constraints> = solutions.into_iter().collect();
+        match &solutions[..] {
+            [] => None,
+            [solution] => Some((*solution).clone()),
+            [first, rest @ ..] => {
+                let guidance: Substitution = first
+                    .substitution
+                    .iter()
+                    .filter(|(v, p)| {
+                        env.encloses(v)
+                            && env.encloses(p)
+                            && p.free_variables()
+                                .iter()
+                                .all(|fv| env.universe(fv) < env.universe(v))
+                            && rest
+                                .iter()
+                                .all(|c| c.substitution.get(*v).as_ref() == Some(p))
+                    })
+                    .collect();
+                let ambiguity = solutions
+                    .iter()
+                    .flat_map(|c| c.ambiguity.iter().cloned())
+                    .chain(Some(AmbiguityCause::MultipleSolutions))
+                    .collect();
+                let c = Constraints {
+                    env: env.clone(),
+                    known_true: false,
+                    substitution: guidance,
+                    ambiguity,
+                };
+                c.assert_valid();
+                Some(c)
+            }
+        }
+    }
+
     /// Given constraings from solving the subparts of `(A /\ B)`, yield combined constraints.
     ///
     /// # Parameters
@@ -103,11 +198,17 @@ impl Constraints {
         // Apply c2's substitution to our substitution (since it may have bound
         // existential variables that we reference)
         let c1_substitution = c2.substitution.apply(&self.substitution);
+        let substitution: Substitution =
+            c1_substitution.into_iter().chain(c2.substitution).collect();
+
+        // Keep the causes up to date with what we know about their variables.
+        let ambiguity = substitution.apply(&(&self.ambiguity | &c2.ambiguity));
 
         Constraints {
             env: c2.env,
             known_true: self.known_true && c2.known_true,
-            substitution: c1_substitution.into_iter().chain(c2.substitution).collect(),
+            substitution,
+            ambiguity,
         }
     }
 
@@ -120,6 +221,7 @@ impl Constraints {
         }
 
         let vars = self.env.pop_vars(v);
+        self.ambiguity = self.substitution.apply(&self.ambiguity);
         self.substitution -= vars;
 
         self
@@ -128,6 +230,61 @@ impl Constraints {
     pub fn is_valid_extension_of(&self, env0: &Env) -> bool {
         self.env.is_valid_extension_of(env0)
     }
+
+    /// Canonicalizes the variables created while solving a goal in `env0`, so that
+    /// answers differing only in the names of those variables are equal: variables
+    /// that are not mentioned are dropped and the rest are renumbered in order.
+    pub fn canonicalize(self, env0: &Env) -> Constraints {
+        let Constraints {
+            mut env,
+            known_true,
+            substitution,
+            ambiguity,
+        } = self;
+
+        let mentioned: Vec<Variable> = env0
+            .variables()
+            .iter()
+            .copied()
+            .chain(substitution.free_variables())
+            .chain(ambiguity.free_variables())
+            .collect();
+        env.remove_variables_unless_within(&mentioned);
+
+        let renaming = env.canonical_renaming(env0);
+        let substitution: Substitution = substitution
+            .iter()
+            .map(|(x, p)| -> (Variable, Parameter) {
+                (renaming.map_var(x).unwrap_or(x), renaming.apply(&p))
+            })
+            .collect();
+        let c = Constraints {
+            env: env.substitute(&renaming),
+            known_true,
+            substitution,
+            ambiguity: renaming.apply(&ambiguity),
+        };
+        c.assert_valid();
+        c
+    }
+
+    /// True if `self` makes `other` redundant as an answer to a goal proven in `env0`:
+    /// `self` is at least as certain, and binds the variables of `env0` only as `other`
+    /// does, to terms that do not mention fresh variables. Of two answers that are
+    /// equally general, the smaller one subsumes the other.
+    pub fn subsumes(&self, other: &Constraints, env0: &Env) -> bool {
+        self != other
+            && self.generalizes(other, env0)
+            && (!other.generalizes(self, env0) || self < other)
+    }
+
+    fn generalizes(&self, other: &Constraints, env0: &Env) -> bool {
+        (self.known_true || !other.known_true)
+            && self.substitution.iter().all(|(v, p)| {
+                !env0.defines(v)
+                    || (env0.encloses(&p) && other.substitution.get(v).as_ref() == Some(&p))
+            })
+    }
 }
 
 impl CoreVisit<crate::FormalityLang> for Constraints {
@@ -136,6 +293,7 @@ impl CoreVisit<crate::FormalityLang> for Constraints {
             env,
             known_true: _,
             substitution,
+            ambiguity: _,
         } = self;
 
         // Debatable if `env.free_variables()` should be considered
@@ -152,6 +310,7 @@ impl CoreVisit<crate::FormalityLang> for Constraints {
             env,
             known_true: _,
             substitution,
+            ambiguity: _,
         } = self;
         env.size() + substitution.size()
     }
@@ -159,10 +318,13 @@ impl CoreVisit<crate::FormalityLang> for Constraints {
     fn assert_valid(&self) {
         let Constraints {
             env,
-            known_true: _,
+            known_true,
             substitution,
+            ambiguity,
         } = self;
 
+        assert_eq!(*known_true, ambiguity.is_empty());
+
         let domain = substitution.domain();
         let range = substitution.range();
 
@@ -2,15 +2,44 @@ use formality_core::{cast_impl, visit::CoreVisit, Set, To, Upcast};
 use formality_macros::term;
 use formality_types::{
     grammar::{
-        Binder, ExistentialVar, ParameterKind, UniversalVar, VarIndex, VarSubstitution, Variable,
+        Binder, ExistentialVar, ParameterKind, UniversalVar, VarIndex, VarName, VarSubstitution,
+        Variable,
     },
     rust::{Fold, Visit},
 };
 
 #[derive(Default, Debug, Clone, Hash, Ord, Eq, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
 pub struct Env {
     variables: Vec<Variable>,
     coherence_mode: bool,
+    candidate_preference: CandidatePreference,
+}
+
+/// How to choose between the candidates (where-clauses and impls) that can prove a trait goal.
+#[derive(Default, Debug, Clone, Copy, Hash, Ord, Eq, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
+pub enum CandidatePreference {
+    /// Use the solutions from every candidate.
+    #[default]
+    All,
+
+    /// Apply the preference rules of rustc's old trait solver: where-clauses that mention
+    /// generic parameters win over everything else, and *global* where-clauses
+    /// (those that do not) are dropped in favor of an impl that applies unconditionally.
+    OldSolver,
+
+    /// Apply the preference rules of rustc's new trait solver: where-clauses that mention
+    /// generic parameters win over everything else.
+    NewSolver,
 }
 
 impl Env {
@@ -28,6 +57,17 @@ impl Env {
             ..self.clone()
         }
     }
+
+    pub fn candidate_preference(&self) -> CandidatePreference {
+        self.candidate_preference
+    }
+
+    pub fn with_candidate_preference(&self, candidate_preference: CandidatePreference) -> Env {
+        Env {
+            candidate_preference,
+            ..self.clone()
+        }
+    }
 }
 
 cast_impl!(Env);
@@ -70,14 +110,22 @@ impl Env {
 
     pub fn fresh_existential(&mut self, kind: ParameterKind) -> ExistentialVar {
         let var_index = self.fresh_index();
-        let v = ExistentialVar { kind, var_index };
+        let v = ExistentialVar {
+            kind,
+            var_index,
+            name: VarName::NONE,
+        };
         self.variables.push(v.upcast());
         v
     }
 
     pub fn insert_fresh_before(&mut self, kind: ParameterKind, rank: Universe) -> ExistentialVar {
         let var_index = self.fresh_index();
-        let v = ExistentialVar { kind, var_index };
+        let v = ExistentialVar {
+            kind,
+            var_index,
+            name: VarName::NONE,
+        };
         self.variables.insert(rank.index, v.upcast());
         v
     }
@@ -115,22 +163,29 @@ impl Env {
             })
     }
 
-    fn fresh_substitution<V>(
+    /// Creates fresh variables for the variables bound by `b`, adding them to the env.
+    /// They keep the names from `b`, with a suffix if another variable in the env has the same name.
+    fn fresh_substitution<T, V>(
         &mut self,
-        kinds: &[ParameterKind],
-        v: impl Fn(ParameterKind, VarIndex) -> V,
+        b: &Binder<T>,
+        v: impl Fn(ParameterKind, VarIndex, VarName) -> V,
     ) -> Vec<V>
     where
+        T: Fold,
         V: Upcast<Variable>,
     {
         let fresh_index = self.fresh_index();
-        let vars: Vec<_> = kinds
-            .iter()
-            .zip(0..)
-            .map(|(&kind, offset)| v(kind, fresh_index + offset))
-            .collect();
-        self.variables
-            .extend(vars.iter().map(|v| v.to::<Variable>()));
+        let mut vars = vec![];
+        for ((&kind, &name), offset) in b.kinds().iter().zip(b.names()).zip(0..) {
+            let name = name.disambiguate(|name| {
+                self.variables
+                    .iter()
+                    .any(|variable| variable.name().same_as(name))
+            });
+            let var = v(kind, fresh_index + offset, name);
+            self.variables.push(var.to());
+            vars.push(var);
+        }
         vars
     }
 
@@ -139,9 +194,10 @@ impl Env {
         T: Fold,
     {
         let mut env = self.clone();
-        let subst = env.fresh_substitution(b.kinds(), |kind, var_index| UniversalVar {
+        let subst = env.fresh_substitution(b, |kind, var_index, name| UniversalVar {
             kind,
             var_index,
+            name,
         });
         (env, subst)
     }
@@ -150,9 +206,10 @@ impl Env {
     where
         T: Fold,
     {
-        let subst = self.fresh_substitution(b.kinds(), |kind, var_index| UniversalVar {
+        let subst = self.fresh_substitution(b, |kind, var_index, name| UniversalVar {
             kind,
             var_index,
+            name,
         });
         b.instantiate_with(&subst).unwrap()
     }
@@ -162,9 +219,10 @@ impl Env {
         T: Fold,
     {
         let mut env = self.clone();
-        let subst = env.fresh_substitution(b.kinds(), |kind, var_index| ExistentialVar {
+        let subst = env.fresh_substitution(b, |kind, var_index, name| ExistentialVar {
             kind,
             var_index,
+            name,
         });
         (env, subst)
     }
@@ -209,12 +267,48 @@ impl Env {
                 .map(|&v| vs.map_var(v).unwrap_or(v))
                 .collect(),
             coherence_mode: self.coherence_mode,
+            candidate_preference: self.candidate_preference,
         }
     }
 
     pub fn defines(&self, v: Variable) -> bool {
         self.variables.contains(&v)
     }
+
+    /// Renames the variables that are not in `env0` to sequential indices following
+    /// those of `env0`, in the order they appear in `self`.
+    pub fn canonical_renaming(&self, env0: &Env) -> VarSubstitution {
+        let fresh_index = env0.fresh_index();
+        self.variables
+            .iter()
+            .filter(|v| !env0.defines(**v))
+            .zip(0..)
+            .map(|(&v, offset)| -> (Variable, Variable) {
+                let var_index = fresh_index + offset;
+                match v {
+                    Variable::UniversalVar(UniversalVar { kind, name, .. }) => (
+                        v,
+                        UniversalVar {
+                            kind,
+                            var_index,
+                            name,
+                        }
+                        .upcast(),
+                    ),
+                    Variable::ExistentialVar(ExistentialVar { kind, name, .. }) => (
+                        v,
+                        ExistentialVar {
+                            kind,
+                            var_index,
+                            name,
+                        }
+                        .upcast(),
+                    ),
+                    Variable::BoundVar(_) => panic!("unexpected bound variable {v:?}"),
+                }
+            })
+            .collect()
+    }
 }
 
 impl CoreVisit<crate::FormalityLang> for Env {
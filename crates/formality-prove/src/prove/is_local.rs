@@ -1,11 +1,11 @@
-use formality_core::{judgment_fn, set, Set};
+use formality_core::{judgment_fn, set, Set, Upcast};
 use formality_types::grammar::{
-    Lt, Parameter, RigidName, RigidTy, TraitRef, TyData, Variable, Wcs,
+    Lt, Parameter, ParameterKind, RigidName, RigidTy, TraitRef, TyData, Variable, Wcs,
 };
 
 use crate::{
     decls::Decls,
-    prove::{combinators::for_all, prove_normalize::prove_normalize, Constraints},
+    prove::{combinators::for_all, prove_normalize::prove_normalize, AmbiguityCause, Constraints},
     Env,
 };
 
@@ -41,7 +41,7 @@ use crate::{
 pub fn may_be_remote(decls: Decls, env: Env, assumptions: Wcs, goal: TraitRef) -> Set<Constraints> {
     assert!(env.is_in_coherence_mode());
 
-    let c = is_local_trait_ref(decls, &env, assumptions, goal);
+    let c = is_local_trait_ref(decls, &env, assumptions, &goal);
 
     if c.is_empty() {
         // Cannot possibly be local, so always remote.
@@ -54,7 +54,7 @@ pub fn may_be_remote(decls: Decls, env: Env, assumptions: Wcs, goal: TraitRef) -
     }
 
     // Otherwise it is ambiguous
-    set![Constraints::none(env).ambiguous()]
+    set![Constraints::none(env).ambiguous(AmbiguityCause::MayBeRemote(goal))]
 }
 
 judgment_fn! {
@@ -117,7 +117,7 @@ judgment_fn! {
             // existential variables *could* be inferred to downstream types; depends on the substitution
             // we ultimately have.
             --- ("type variable")
-            (not_downstream(_decls, env, _assumptions, TyData::Variable(Variable::ExistentialVar(_))) => Constraints::none(env).ambiguous())
+            (not_downstream(_decls, env, _assumptions, TyData::Variable(v @ Variable::ExistentialVar(_))) => Constraints::none(env).ambiguous(AmbiguityCause::Unresolved(v.upcast())))
         )
     }
 }
@@ -143,10 +143,12 @@ judgment_fn! {
             (is_local_parameter(decls, env, assumptions, goal) => c1.seq(c2))
         )
 
-        // Fundamental types are local if all their arguments are local.
+        // Fundamental types are local if all their type arguments are local
+        // (e.g., `&'a T` is local if `T` is, whatever `'a` is).
         (
             (if is_fundamental(&decls, &name))
-            (for_all(&decls, &env, &assumptions, &parameters, &is_local_parameter) => c) // FIXME: should be `is_local_parameter`
+            (let ty_parameters = parameters.iter().filter(|p| p.kind() == ParameterKind::Ty).cloned().collect::<Vec<_>>())
+            (for_all(&decls, &env, &assumptions, &ty_parameters, &is_local_parameter) => c)
             --- ("fundamental rigid type")
             (is_local_parameter(decls, env, assumptions, RigidTy { name, parameters }) => c)
         )
@@ -161,7 +163,7 @@ judgment_fn! {
         // existential variables might or might not be local, depending on how they are instantiated.
         (
             --- ("existential variable")
-            (is_local_parameter(_decls, env, _assumptions, TyData::Variable(Variable::ExistentialVar(_))) => Constraints::none(env).ambiguous())
+            (is_local_parameter(_decls, env, _assumptions, TyData::Variable(v @ Variable::ExistentialVar(_))) => Constraints::none(env).ambiguous(AmbiguityCause::Unresolved(v.upcast())))
         )
     }
 }
@@ -180,9 +182,16 @@ fn is_fundamental(_decls: &Decls, name: &RigidName) -> bool {
 
         RigidName::Ref(_) => true,
 
+        // Raw pointers, arrays, and slices are not fundamental: `*const T` is never local,
+        // even if `T` is.
         RigidName::ScalarId(_)
         | RigidName::Tuple(_)
         | RigidName::FnPtr(_)
-        | RigidName::FnDef(_) => false,
+        | RigidName::FnDef(_)
+        | RigidName::Array
+        | RigidName::Slice
+        | RigidName::Str
+        | RigidName::Never
+        | RigidName::RawPtr(_) => false,
     }
 }
@@ -23,14 +23,16 @@ pub fn minimize<T: Term>(env_max: Env, term: T) -> (Env, T, Minimization) {
         .iter()
         .zip(0..)
         .map(|(&fv, index)| match fv {
-            Variable::UniversalVar(UniversalVar { kind, var_index: _ }) => UniversalVar {
+            Variable::UniversalVar(UniversalVar { kind, name, .. }) => UniversalVar {
                 kind,
                 var_index: VarIndex { index },
+                name,
             }
             .upcast(),
-            Variable::ExistentialVar(ExistentialVar { kind, var_index: _ }) => ExistentialVar {
+            Variable::ExistentialVar(ExistentialVar { kind, name, .. }) => ExistentialVar {
                 kind,
                 var_index: VarIndex { index },
+                name,
             }
             .upcast(),
             Variable::BoundVar(_) => panic!("unexpected bound variable {fv:?}"),
@@ -117,6 +119,7 @@ impl Minimization {
             env: _,
             known_true,
             substitution,
+            ambiguity,
         } = constraints;
         let substitution: Substitution = substitution
             .iter()
@@ -128,6 +131,7 @@ impl Minimization {
             env: env_out,
             known_true,
             substitution,
+            ambiguity: env2out_subst.apply(&ambiguity),
         }
     }
 }
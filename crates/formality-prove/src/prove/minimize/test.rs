@@ -1,5 +1,5 @@
 use expect_test::expect;
-use formality_core::To;
+use formality_core::{set, To};
 use formality_macros::test;
 use formality_types::{
     grammar::{Binder, Parameter, ScalarId, Ty},
@@ -17,13 +17,13 @@ fn minimize_a() {
     let (env, subst) = env.existential_substitution(&term);
     let term = term.instantiate_with(&subst).unwrap();
 
-    expect!["(Env { variables: [?ty_1, ?ty_2, ?ty_3], coherence_mode: false }, [?ty_1, ?ty_3])"]
+    expect!["(Env { variables: [?A, ?B, ?C], coherence_mode: false, candidate_preference: All }, [?A, ?C])"]
         .assert_eq(&format!("{:?}", (&env, &term)));
 
     let (mut env_min, term_min, m) = minimize(env, term);
 
-    expect!["(Env { variables: [?ty_0, ?ty_1], coherence_mode: false }, [?ty_0, ?ty_1])"]
-    .assert_eq(&format!("{:?}", (&env_min, &term_min)));
+    expect!["(Env { variables: [?A, ?C], coherence_mode: false, candidate_preference: All }, [?A, ?C])"]
+        .assert_eq(&format!("{:?}", (&env_min, &term_min)));
 
     let ty0 = term_min[0].as_variable().unwrap();
     let ty1 = term_min[1].as_variable().unwrap();
@@ -38,6 +38,7 @@ fn minimize_a() {
         substitution: vec![(ty1, ty2.to::<Ty>()), (ty0, ScalarId::U32.to::<Ty>())]
             .into_iter()
             .collect(),
+        ambiguity: set![],
     };
     let c = m.reconstitute(c_min);
 
@@ -45,18 +46,20 @@ fn minimize_a() {
         Constraints {
             env: Env {
                 variables: [
-                    ?ty_1,
-                    ?ty_2,
+                    ?A,
+                    ?B,
                     ?ty_4,
-                    ?ty_3,
+                    ?C,
                 ],
                 coherence_mode: false,
+                candidate_preference: All,
             },
             known_true: true,
             substitution: {
-                ?ty_1 => u32,
-                ?ty_3 => ?ty_4,
+                ?A => u32,
+                ?C => ?ty_4,
             },
+            ambiguity: {},
         }
     "#]]
     .assert_debug_eq(&c);
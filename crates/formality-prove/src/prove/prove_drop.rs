@@ -0,0 +1,181 @@
+use formality_core::{judgment_fn, Downcast, Upcast, Upcasted};
+use formality_types::grammar::{
+    AdtId, AliasTy, Lt, Parameter, Parameters, Relation, RigidName, RigidTy, Ty, TyData, Variable,
+    Wcs,
+};
+
+use crate::{
+    decls::Decls,
+    prove::{prove, prove_after::prove_after, prove_normalize::prove_normalize},
+};
+
+use super::{
+    constraints::{AmbiguityCause, Constraints},
+    env::Env,
+};
+
+judgment_fn! {
+    /// "needs_drop(..., T)" means that dropping a value of type `T` may run a destructor,
+    /// either of `T` itself or of something that `T` owns. Computed structurally.
+    pub fn needs_drop(
+        decls: Decls,
+        env: Env,
+        assumptions: Wcs,
+        goal: Ty,
+    ) => Constraints {
+        debug(goal, assumptions, env, decls)
+
+        (
+            (if decls.drop_decls(&adt_id).next().is_some())
+            --- ("ADT with destructor")
+            (needs_drop(decls, env, _assumptions, RigidTy { name: RigidName::AdtId(adt_id), parameters: _ }) => Constraints::none(env))
+        )
+
+        (
+            (let t = decls.adt_decl(&adt_id).binder.instantiate_with(&parameters).unwrap())
+            (t.field_tys() => field_ty)
+            (needs_drop(&decls, &env, &assumptions, field_ty) => c)
+            --- ("ADT field")
+            (needs_drop(decls, env, assumptions, RigidTy { name: RigidName::AdtId(adt_id), parameters }) => c)
+        )
+
+        (
+            (parameters => p)
+            (if let Some(ty) = p.downcast::<Ty>())
+            (needs_drop(&decls, &env, &assumptions, ty) => c)
+            --- ("tuple")
+            (needs_drop(decls, env, assumptions, RigidTy { name: RigidName::Tuple(_), parameters }) => c)
+        )
+
+        (
+            (if let [ty, ..] = &parameters[..])
+            (if let Some(ty) = ty.downcast::<Ty>())
+            (needs_drop(&decls, &env, &assumptions, ty) => c)
+            --- ("array or slice")
+            (needs_drop(decls, env, assumptions, RigidTy { name: RigidName::Array | RigidName::Slice, parameters }) => c)
+        )
+
+        (
+            // Generic types may be instantiated with types that have destructors.
+            --- ("universal variable")
+            (needs_drop(_decls, env, _assumptions, TyData::Variable(Variable::UniversalVar(_))) => Constraints::none(env))
+        )
+
+        (
+            --- ("existential variable")
+            (needs_drop(_decls, env, _assumptions, TyData::Variable(v @ Variable::ExistentialVar(_))) => Constraints::none(env).ambiguous(AmbiguityCause::Unresolved(v.upcast())))
+        )
+
+        (
+            // We don't know what the alias normalizes to in general, so be conservative.
+            --- ("alias")
+            (needs_drop(_decls, env, _assumptions, AliasTy { .. }) => Constraints::none(env))
+        )
+    }
+}
+
+judgment_fn! {
+    /// "dropck_outlives(..., T, 'a)" means that a value of type `T` may be dropped while `'a`
+    /// is live: every generic parameter that a destructor reachable from `T` may access
+    /// must outlive `'a`. Parameters marked as `may_dangle` are exempt.
+    pub fn dropck_outlives(
+        decls: Decls,
+        env: Env,
+        assumptions: Wcs,
+        goal: Ty,
+        lt: Lt,
+    ) => Constraints {
+        debug(goal, lt, assumptions, env, decls)
+
+        (
+            // Dropping scalars, pointers, and function pointers/definitions never runs user code.
+            (if let RigidName::ScalarId(_) | RigidName::Ref(_) | RigidName::RawPtr(_) | RigidName::FnPtr(_) | RigidName::FnDef(_) | RigidName::Str | RigidName::Never = name)
+            --- ("trivial")
+            (dropck_outlives(_decls, env, _assumptions, RigidTy { name, parameters: _ }, _lt) => Constraints::none(env))
+        )
+
+        (
+            (prove(&decls, &env, &assumptions, dropck_outlives_goals(&parameters, &lt)) => c)
+            --- ("tuple")
+            (dropck_outlives(decls, env, assumptions, RigidTy { name: RigidName::Tuple(_), parameters }, lt) => c)
+        )
+
+        (
+            (if let [ty, ..] = &parameters[..])
+            (prove(&decls, &env, &assumptions, dropck_outlives_goals([ty], &lt)) => c)
+            --- ("array or slice")
+            (dropck_outlives(decls, env, assumptions, RigidTy { name: RigidName::Array | RigidName::Slice, parameters }, lt) => c)
+        )
+
+        (
+            (let t = decls.adt_decl(&adt_id).binder.instantiate_with(&parameters).unwrap())
+            (prove(&decls, &env, &assumptions, dropck_outlives_goals(t.field_tys(), &lt)) => c)
+            (prove_after(&decls, c, &assumptions, destructor_outlives_goals(&decls, &adt_id, &parameters, &lt)) => c)
+            --- ("ADT")
+            (dropck_outlives(decls, env, assumptions, RigidTy { name: RigidName::AdtId(adt_id), parameters }, lt) => c)
+        )
+
+        (
+            (prove(&decls, &env, &assumptions, Relation::outlives(goal, &lt)) => c)
+            --- ("universal variable")
+            (dropck_outlives(decls, env, assumptions, TyData::Variable(goal @ Variable::UniversalVar(_)), lt) => c)
+        )
+
+        (
+            --- ("existential variable")
+            (dropck_outlives(_decls, env, _assumptions, TyData::Variable(v @ Variable::ExistentialVar(_)), _lt) => Constraints::none(env).ambiguous(AmbiguityCause::Unresolved(v.upcast())))
+        )
+
+        (
+            (prove_normalize(&decls, &env, &assumptions, &goal) => (c, p))
+            (if let Some(ty) = p.downcast::<Ty>())
+            (let assumptions = c.substitution().apply(&assumptions))
+            (let lt = c.substitution().apply(&lt))
+            (dropck_outlives(&decls, c.env(), assumptions, ty, lt) => c1)
+            --- ("normalize alias")
+            (dropck_outlives(decls, env, assumptions, goal @ AliasTy { .. }, lt) => c.seq(c1))
+        )
+
+        (
+            (prove(&decls, &env, &assumptions, Relation::outlives(&goal, &lt)) => c)
+            --- ("alias outlives")
+            (dropck_outlives(decls, env, assumptions, goal @ AliasTy { .. }, lt) => c)
+        )
+    }
+}
+
+/// `@dropck_outlives(P, 'a)` for each type `P` in `parameters`.
+fn dropck_outlives_goals(
+    parameters: impl IntoIterator<Item = impl Upcast<Parameter>>,
+    lt: &Lt,
+) -> Wcs {
+    parameters
+        .into_iter()
+        .filter_map(|p| Upcast::<Parameter>::upcast(p).downcast::<Ty>())
+        .map(|ty| Relation::dropck_outlives(ty, lt))
+        .upcasted()
+        .collect()
+}
+
+/// If `adt_id` has a destructor, the destructor may access any of the ADT's type and
+/// lifetime parameters except those marked `may_dangle`, so each of them must outlive `lt`.
+/// If there are several destructors, a parameter is exempt only if all of them mark it.
+fn destructor_outlives_goals(
+    decls: &Decls,
+    adt_id: &AdtId,
+    parameters: &Parameters,
+    lt: &Lt,
+) -> Wcs {
+    decls
+        .drop_decls(adt_id)
+        .flat_map(|drop_decl| {
+            let drop_data = drop_decl.binder.instantiate_with(parameters).unwrap();
+            parameters
+                .iter()
+                .filter(|p| !matches!(p, Parameter::Const(_)))
+                .filter(move |p| !drop_data.may_dangle.contains(p))
+                .map(|p| Relation::outlives(p, lt))
+        })
+        .upcasted()
+        .collect()
+}
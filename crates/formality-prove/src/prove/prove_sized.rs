@@ -0,0 +1,71 @@
+use formality_core::{judgment_fn, Downcast, Upcast, Upcasted};
+use formality_types::grammar::{
+    AliasTy, Parameter, Relation, RigidName, RigidTy, Ty, TyData, Variable, Wcs,
+};
+
+use crate::{decls::Decls, prove::prove};
+
+use super::{
+    constraints::{AmbiguityCause, Constraints},
+    env::Env,
+};
+
+judgment_fn! {
+    /// "is_sized(..., T)" means that the size of `T` is known at compile time.
+    /// Slices and `str` are the only unsized types; tuples and ADTs are sized if
+    /// their components are. Like in Rust, generic parameters and associated types
+    /// are implicitly sized (there is no `?Sized`).
+    pub fn is_sized(
+        decls: Decls,
+        env: Env,
+        assumptions: Wcs,
+        goal: Ty,
+    ) => Constraints {
+        debug(goal, assumptions, env, decls)
+
+        (
+            // Arrays are sized because their element type must be sized for them to be well-formed.
+            (if let RigidName::ScalarId(_) | RigidName::Ref(_) | RigidName::RawPtr(_) | RigidName::FnPtr(_) | RigidName::FnDef(_) | RigidName::Never | RigidName::Array = name)
+            --- ("sized rigid type")
+            (is_sized(_decls, env, _assumptions, RigidTy { name, parameters: _ }) => Constraints::none(env))
+        )
+
+        (
+            (prove(&decls, &env, &assumptions, sized_goals(&parameters)) => c)
+            --- ("tuple")
+            (is_sized(decls, env, assumptions, RigidTy { name: RigidName::Tuple(_), parameters }) => c)
+        )
+
+        (
+            (let t = decls.adt_decl(&adt_id).binder.instantiate_with(&parameters).unwrap())
+            (prove(&decls, &env, &assumptions, sized_goals(t.field_tys())) => c)
+            --- ("ADT")
+            (is_sized(decls, env, assumptions, RigidTy { name: RigidName::AdtId(adt_id), parameters }) => c)
+        )
+
+        (
+            --- ("universal variable")
+            (is_sized(_decls, env, _assumptions, TyData::Variable(Variable::UniversalVar(_))) => Constraints::none(env))
+        )
+
+        (
+            --- ("existential variable")
+            (is_sized(_decls, env, _assumptions, TyData::Variable(v @ Variable::ExistentialVar(_))) => Constraints::none(env).ambiguous(AmbiguityCause::Unresolved(v.upcast())))
+        )
+
+        (
+            --- ("alias")
+            (is_sized(_decls, env, _assumptions, AliasTy { .. }) => Constraints::none(env))
+        )
+    }
+}
+
+/// `@sized(P)` for each type `P` in `parameters`.
+fn sized_goals(parameters: impl IntoIterator<Item = impl Upcast<Parameter>>) -> Wcs {
+    parameters
+        .into_iter()
+        .filter_map(|p| Upcast::<Parameter>::upcast(p).downcast::<Ty>())
+        .map(Relation::sized)
+        .upcasted()
+        .collect()
+}
@@ -0,0 +1,160 @@
+use formality_core::{judgment_fn, Downcast};
+use formality_types::grammar::{
+    ConstData, RefKind, RigidName, RigidTy, Scalar, Ty, ValTree, VariantId, Wcs,
+};
+
+use crate::decls::{AdtDeclBoundData, AdtDeclVariant, Decls};
+
+use super::{constraints::Constraints, env::Env};
+
+judgment_fn! {
+    /// "prove_valtree_wf(..., V, T)" means that the valtree `V` is a valid value of type `T`.
+    /// Scalars must be in range for their type; tuples and structs are branches with one
+    /// entry per field; enums are a branch whose first entry is the variant index, followed
+    /// by the fields of that variant; arrays and slices are branches with one entry per element,
+    /// and `str` is a branch of UTF-8 bytes; shared references are represented by their referent.
+    pub fn prove_valtree_wf(
+        decls: Decls,
+        env: Env,
+        assumptions: Wcs,
+        valtree: ValTree,
+        ty: Ty,
+    ) => Constraints {
+        debug(valtree, ty, assumptions, env, decls)
+
+        (
+            (if let ValTree::Leaf(scalar) = valtree)
+            (if scalar_id.contains(&scalar))
+            --- ("scalar")
+            (prove_valtree_wf(_decls, env, _assumptions, valtree, RigidTy { name: RigidName::ScalarId(scalar_id), parameters: _ }) => Constraints::none(env))
+        )
+
+        (
+            (if let ValTree::Branches(branches) = valtree)
+            (if branches.len() == parameters.len())
+            (let tys = parameters.iter().filter_map(|p| p.downcast::<Ty>()).collect::<Vec<_>>())
+            (prove_valtrees_wf(decls, env, assumptions, branches, tys) => c)
+            --- ("tuple")
+            (prove_valtree_wf(decls, env, assumptions, valtree, RigidTy { name: RigidName::Tuple(_), parameters }) => c)
+        )
+
+        (
+            (if let [_, referent] = &parameters[..])
+            (if let Some(referent) = referent.downcast::<Ty>())
+            (prove_valtree_wf(decls, env, assumptions, valtree, referent) => c)
+            --- ("shared reference")
+            (prove_valtree_wf(decls, env, assumptions, valtree, RigidTy { name: RigidName::Ref(RefKind::Shared), parameters }) => c)
+        )
+
+        (
+            (if let ValTree::Branches(branches) = valtree)
+            (if let [ty, len] = &parameters[..])
+            (if let Some(ty) = ty.downcast::<Ty>())
+            (if let Some(ConstData::Value(ValTree::Leaf(len), _)) = len.downcast::<ConstData>())
+            (if len.bits() == branches.len() as u128)
+            (let tys = vec![ty; branches.len()])
+            (prove_valtrees_wf(decls, env, assumptions, branches, tys) => c)
+            --- ("array")
+            (prove_valtree_wf(decls, env, assumptions, valtree, RigidTy { name: RigidName::Array, parameters }) => c)
+        )
+
+        (
+            // Slices only occur behind references, where they may have any length.
+            (if let ValTree::Branches(branches) = valtree)
+            (if let [ty, ..] = &parameters[..])
+            (if let Some(ty) = ty.downcast::<Ty>())
+            (let tys = vec![ty; branches.len()])
+            (prove_valtrees_wf(decls, env, assumptions, branches, tys) => c)
+            --- ("slice")
+            (prove_valtree_wf(decls, env, assumptions, valtree, RigidTy { name: RigidName::Slice, parameters }) => c)
+        )
+
+        (
+            // Like a `[u8]` containing UTF-8.
+            (if let ValTree::Branches(branches) = valtree)
+            (if is_utf8(&branches))
+            --- ("str")
+            (prove_valtree_wf(_decls, env, _assumptions, valtree, RigidTy { name: RigidName::Str, parameters: _ }) => Constraints::none(env))
+        )
+
+        (
+            (let adt = decls.adt_decl(&adt_id).binder.instantiate_with(&parameters).unwrap())
+            (if let Some(variant) = struct_variant(&adt))
+            (if let ValTree::Branches(branches) = valtree)
+            (if branches.len() == variant.fields.len())
+            (let tys = variant.fields.iter().map(|f| f.ty.clone()).collect::<Vec<_>>())
+            (prove_valtrees_wf(&decls, &env, &assumptions, branches, tys) => c)
+            --- ("struct")
+            (prove_valtree_wf(decls, env, assumptions, valtree, RigidTy { name: RigidName::AdtId(adt_id), parameters }) => c)
+        )
+
+        (
+            (let adt = decls.adt_decl(&adt_id).binder.instantiate_with(&parameters).unwrap())
+            (if struct_variant(&adt).is_none())
+            (if let ValTree::Branches(branches) = valtree)
+            (if let [ValTree::Leaf(index), fields @ ..] = &branches[..])
+            (if let Some(variant) = enum_variant(&adt, index))
+            (if fields.len() == variant.fields.len())
+            (let tys = variant.fields.iter().map(|f| f.ty.clone()).collect::<Vec<_>>())
+            (prove_valtrees_wf(&decls, &env, &assumptions, fields.to_vec(), tys) => c)
+            --- ("enum")
+            (prove_valtree_wf(decls, env, assumptions, valtree, RigidTy { name: RigidName::AdtId(adt_id), parameters }) => c)
+        )
+    }
+}
+
+judgment_fn! {
+    /// Each valtree in the list is a valid value of the corresponding type.
+    fn prove_valtrees_wf(
+        decls: Decls,
+        env: Env,
+        assumptions: Wcs,
+        valtrees: Vec<ValTree>,
+        tys: Vec<Ty>,
+    ) => Constraints {
+        debug(valtrees, tys, assumptions, env, decls)
+
+        (
+            (if valtrees.is_empty() && tys.is_empty())
+            --- ("none")
+            (prove_valtrees_wf(_decls, env, _assumptions, valtrees, tys) => Constraints::none(env))
+        )
+
+        (
+            (if let Some((valtree, valtrees)) = valtrees.split_first())
+            (if let Some((ty, tys)) = tys.split_first())
+            (prove_valtree_wf(&decls, &env, &assumptions, valtree, ty) => c)
+            (let assumptions = c.substitution().apply(&assumptions))
+            (let tys = c.substitution().apply(&tys.to_vec()))
+            (prove_valtrees_wf(&decls, c.env(), assumptions, valtrees.to_vec(), tys) => c1)
+            --- ("some")
+            (prove_valtrees_wf(decls, env, assumptions, valtrees, tys) => c.seq(c1))
+        )
+    }
+}
+
+/// The single variant of a struct, or `None` if the ADT is an enum.
+fn struct_variant(adt: &AdtDeclBoundData) -> Option<&AdtDeclVariant> {
+    match &adt.variants[..] {
+        [variant] if variant.name == VariantId::for_struct() => Some(variant),
+        _ => None,
+    }
+}
+
+/// The variant of an enum with the given index, if any.
+fn enum_variant<'a>(adt: &'a AdtDeclBoundData, index: &Scalar) -> Option<&'a AdtDeclVariant> {
+    let index = usize::try_from(index.bits()).ok()?;
+    adt.variants.get(index)
+}
+
+/// True if `branches` are all bytes and together form valid UTF-8.
+fn is_utf8(branches: &[ValTree]) -> bool {
+    let bytes: Option<Vec<u8>> = branches
+        .iter()
+        .map(|b| match b {
+            ValTree::Leaf(s) => u8::try_from(s.bits()).ok(),
+            ValTree::Branches(_) => None,
+        })
+        .collect();
+    bytes.is_some_and(|bytes| std::str::from_utf8(&bytes).is_ok())
+}
@@ -1,5 +1,5 @@
-use formality_core::judgment_fn;
-use formality_types::grammar::{Predicate, Relation, Wc, WcData, Wcs};
+use formality_core::{judgment_fn, Downcast};
+use formality_types::grammar::{Predicate, Relation, TraitRef, Wc, WcData, Wcs};
 
 use crate::{
     decls::Decls,
@@ -8,13 +8,17 @@ use crate::{
         is_local::{is_local_trait_ref, may_be_remote},
         prove,
         prove_after::prove_after,
+        prove_drop::{dropck_outlives, needs_drop},
         prove_eq::prove_eq,
+        prove_sized::is_sized,
         prove_via::prove_via,
         prove_wf::prove_wf,
+        trait_candidate::{trait_candidate, winnow},
+        type_of_const::type_of_const,
     },
 };
 
-use super::constraints::Constraints;
+use super::constraints::{AmbiguityCause, Constraints};
 
 judgment_fn! {
     pub fn prove_wc(
@@ -40,6 +44,8 @@ judgment_fn! {
         )
 
         (
+            // Trait goals are proven via their assumptions by `trait_candidate`.
+            (if !goal.is_a::<TraitRef>())
             (&assumptions => a)
             (prove_via(&decls, &env, &assumptions, a, &goal) => c)
             ----------------------------- ("assumption")
@@ -47,23 +53,16 @@ judgment_fn! {
         )
 
         (
-            (decls.impl_decls(&trait_ref.trait_id) => i)
-            (let (env, subst) = env.existential_substitution(&i.binder))
-            (let i = i.binder.instantiate_with(&subst).unwrap())
-            (let t = decls.trait_decl(&i.trait_ref.trait_id).binder.instantiate_with(&i.trait_ref.parameters).unwrap())
-            (let co_assumptions = (&assumptions, &trait_ref))
-            (prove(&decls, env, co_assumptions, Wcs::all_eq(&trait_ref.parameters, &i.trait_ref.parameters)) => c)
-            (prove_after(&decls, c, co_assumptions, &i.where_clause) => c)
-            (prove_after(&decls, c, &assumptions, &t.where_clause) => c)
-            ----------------------------- ("positive impl")
-            (prove_wc(decls, env, assumptions, Predicate::IsImplemented(trait_ref)) => c.pop_subst(&subst))
+            (winnow(env.candidate_preference(), trait_candidate(&decls, &env, &assumptions, &trait_ref)) => c)
+            ----------------------------- ("trait candidates")
+            (prove_wc(decls, env, assumptions, Predicate::IsImplemented(trait_ref)) => c)
         )
 
         (
             (if env.is_in_coherence_mode())
-            (may_be_remote(decls, env, assumptions, trait_ref) => c)
+            (may_be_remote(decls, env, assumptions, trait_ref.clone()) => c)
             ----------------------------- ("coherence / remote impl")
-            (prove_wc(decls, env, assumptions, Predicate::IsImplemented(trait_ref)) => c.ambiguous())
+            (prove_wc(decls, env, assumptions, Predicate::IsImplemented(trait_ref)) => c.ambiguous(AmbiguityCause::MayBeRemote(trait_ref.clone())))
         )
 
         (
@@ -82,16 +81,6 @@ judgment_fn! {
             (prove_wc(decls, env, assumptions, Predicate::AliasEq(alias_ty, ty)) => c)
         )
 
-        (
-            (decls.trait_invariants() => ti)
-            (let (env, subst) = env.existential_substitution(&ti.binder))
-            (let ti = ti.binder.instantiate_with(&subst).unwrap())
-            (prove_via(&decls, env, &assumptions, &ti.where_clause, &trait_ref) => c)
-            (prove_after(&decls, c, &assumptions, &ti.trait_ref) => c)
-            ----------------------------- ("trait implied bound")
-            (prove_wc(decls, env, assumptions, Predicate::IsImplemented(trait_ref)) => c.pop_subst(&subst))
-        )
-
         (
             (prove_eq(decls, env, assumptions, a, b) => c)
             ----------------------------- ("eq")
@@ -120,8 +109,26 @@ judgment_fn! {
         )
 
         (
-            (if let Some((_, const_ty)) = ct.as_value())
-            (prove(decls, env, assumptions, Wcs::all_eq(vec![const_ty], vec![ty])) => c)
+            (is_sized(decls, env, assumptions, ty) => c)
+            ----------------------------- ("sized")
+            (prove_wc(decls, env, assumptions, Relation::Sized(ty)) => c)
+        )
+
+        (
+            (needs_drop(decls, env, assumptions, ty) => c)
+            ----------------------------- ("needs drop")
+            (prove_wc(decls, env, assumptions, Relation::NeedsDrop(ty)) => c)
+        )
+
+        (
+            (dropck_outlives(decls, env, assumptions, ty, lt) => c)
+            ----------------------------- ("dropck outlives")
+            (prove_wc(decls, env, assumptions, Relation::DropckOutlives(ty, lt)) => c)
+        )
+
+        (
+            (type_of_const(&decls, &env, &assumptions, &ct) => const_ty)
+            (prove(&decls, &env, &assumptions, Wcs::all_eq(vec![const_ty], vec![&ty])) => c)
             ----------------------------- ("const has ty")
             (prove_wc(decls, env, assumptions, Predicate::ConstHasType(ct, ty)) => c)
         )
@@ -1,11 +1,12 @@
-use formality_core::{judgment_fn, Set};
+use formality_core::{judgment_fn, Downcast, Set, Upcast};
 use formality_types::grammar::{
-    AliasName, AliasTy, ConstData, Parameter, Parameters, RigidName, RigidTy, UniversalVar, Wcs,
+    AliasName, AliasTy, Const, ConstData, Parameter, Parameters, Predicate, Relation, RigidName,
+    RigidTy, Ty, UniversalVar, Wcs,
 };
 
 use crate::{
     decls::Decls,
-    prove::{combinators::for_all, prove_after::prove_after},
+    prove::{combinators::for_all, prove, prove_after::prove_after, prove_valtree_wf},
 };
 
 use super::{constraints::Constraints, env::Env};
@@ -42,6 +43,35 @@ judgment_fn! {
             (prove_wf(decls, env, assumptions, RigidTy { name: RigidName::ScalarId(_), parameters }) => c)
         )
 
+        (
+            --- ("str and never")
+            (prove_wf(_decls, env, _assumptions, RigidTy { name: RigidName::Str | RigidName::Never, parameters: _ }) => Constraints::none(env))
+        )
+
+        (
+            (if let [ty, len] = &parameters[..])
+            (if let Some(ty) = ty.downcast::<Ty>())
+            (if let Some(len) = len.downcast::<Const>())
+            (prove(&decls, &env, &assumptions, (ty.well_formed(), Relation::sized(&ty), Relation::WellFormed((&len).upcast()), Predicate::ConstHasType(len, Ty::usize()))) => c)
+            --- ("arrays")
+            (prove_wf(decls, env, assumptions, RigidTy { name: RigidName::Array, parameters }) => c)
+        )
+
+        (
+            (if let [ty] = &parameters[..])
+            (if let Some(ty) = ty.downcast::<Ty>())
+            (prove(&decls, &env, &assumptions, (ty.well_formed(), Relation::sized(&ty))) => c)
+            --- ("slices")
+            (prove_wf(decls, env, assumptions, RigidTy { name: RigidName::Slice, parameters }) => c)
+        )
+
+        (
+            // Raw pointers may point to unsized types.
+            (for_all(&decls, &env, &assumptions, &parameters, &prove_wf) => c)
+            --- ("raw pointers")
+            (prove_wf(decls, env, assumptions, RigidTy { name: RigidName::RawPtr(_), parameters }) => c)
+        )
+
         (
             (for_all(&decls, &env, &assumptions, &parameters, &prove_wf) => c)
             (let t = decls.adt_decl(&adt_id))
@@ -52,9 +82,13 @@ judgment_fn! {
         )
 
         (
-            (prove_wf(&decls, &env, &assumptions, ty) => c)
+            (prove_wf(&decls, &env, &assumptions, &ty) => c)
+            (let valtree = c.substitution().apply(&valtree))
+            (let ty = c.substitution().apply(&ty))
+            (let assumptions = c.substitution().apply(&assumptions))
+            (prove_valtree_wf(&decls, c.env(), assumptions, valtree, ty) => c1)
             --- ("rigid constants")
-            (prove_wf(decls, env, assumptions, ConstData::Value(_, ty)) => c)
+            (prove_wf(decls, env, assumptions, ConstData::Value(valtree, ty)) => c.seq(c1))
         )
 
         (
@@ -0,0 +1,111 @@
+use formality_core::{cast_impl, judgment_fn, visit::CoreVisit, Set};
+use formality_types::grammar::{TraitRef, Wcs};
+
+use crate::{
+    decls::Decls,
+    prove::{
+        constraints::Constraints,
+        env::{CandidatePreference, Env},
+        prove,
+        prove_after::prove_after,
+        prove_via::prove_via,
+    },
+};
+
+/// The kind of candidate that a solution to a trait goal came from.
+#[derive(Clone, Copy, Debug, Hash, Ord, Eq, PartialEq, PartialOrd)]
+pub enum Candidate {
+    /// An assumption, or a bound implied by one. It is *global* if the trait ref
+    /// it proves mentions no variables.
+    WhereClause { global: bool },
+
+    /// An impl.
+    Impl,
+}
+
+cast_impl!(Candidate);
+
+impl Candidate {
+    fn where_clause(c: &Constraints, trait_ref: &TraitRef) -> Self {
+        let trait_ref = c.substitution().apply(trait_ref);
+        Candidate::WhereClause {
+            global: trait_ref.free_variables().is_empty(),
+        }
+    }
+}
+
+judgment_fn! {
+    /// The ways to prove `trait_ref`, along with the kind of candidate each came from,
+    /// so that [`winnow`] can choose between them.
+    pub fn trait_candidate(
+        decls: Decls,
+        env: Env,
+        assumptions: Wcs,
+        trait_ref: TraitRef,
+    ) => (Candidate, Constraints) {
+        debug(trait_ref, assumptions, env, decls)
+
+        (
+            (&assumptions => a)
+            (prove_via(&decls, &env, &assumptions, a, &trait_ref) => c)
+            ----------------------------- ("assumption")
+            (trait_candidate(decls, env, assumptions, trait_ref) => (Candidate::where_clause(&c, &trait_ref), c))
+        )
+
+        (
+            (decls.impl_decls(&trait_ref.trait_id) => i)
+            (let (env, subst) = env.existential_substitution(&i.binder))
+            (let i = i.binder.instantiate_with(&subst).unwrap())
+            (let t = decls.trait_decl(&i.trait_ref.trait_id).binder.instantiate_with(&i.trait_ref.parameters).unwrap())
+            (let co_assumptions = (&assumptions, &trait_ref))
+            (prove(&decls, env, co_assumptions, Wcs::all_eq(&trait_ref.parameters, &i.trait_ref.parameters)) => c)
+            (prove_after(&decls, c, co_assumptions, &i.where_clause) => c)
+            (prove_after(&decls, c, &assumptions, &t.where_clause) => c)
+            ----------------------------- ("positive impl")
+            (trait_candidate(decls, env, assumptions, trait_ref) => (Candidate::Impl, c.pop_subst(&subst)))
+        )
+
+        (
+            (decls.trait_invariants() => ti)
+            (let (env, subst) = env.existential_substitution(&ti.binder))
+            (let ti = ti.binder.instantiate_with(&subst).unwrap())
+            (prove_via(&decls, env, &assumptions, &ti.where_clause, &trait_ref) => c)
+            (prove_after(&decls, c, &assumptions, &ti.trait_ref) => c)
+            ----------------------------- ("trait implied bound")
+            (trait_candidate(decls, env, assumptions, trait_ref) => (Candidate::where_clause(&c, &trait_ref), c.pop_subst(&subst)))
+        )
+    }
+}
+
+/// Chooses between the solutions from each candidate according to `preference`.
+pub fn winnow(
+    preference: CandidatePreference,
+    candidates: Set<(Candidate, Constraints)>,
+) -> Set<Constraints> {
+    let non_global_where_clauses: Set<Constraints> = candidates
+        .iter()
+        .filter(|(candidate, _)| *candidate == Candidate::WhereClause { global: false })
+        .map(|(_, c)| c.clone())
+        .collect();
+
+    match preference {
+        CandidatePreference::All => candidates.into_iter().map(|(_, c)| c).collect(),
+
+        _ if !non_global_where_clauses.is_empty() => non_global_where_clauses,
+
+        CandidatePreference::OldSolver => {
+            let impl_applies = candidates
+                .iter()
+                .any(|(candidate, c)| *candidate == Candidate::Impl && c.unconditionally_true());
+            candidates
+                .into_iter()
+                .filter(|(candidate, _)| {
+                    !(impl_applies && *candidate == Candidate::WhereClause { global: true })
+                })
+                .map(|(_, c)| c)
+                .collect()
+        }
+
+        CandidatePreference::NewSolver => candidates.into_iter().map(|(_, c)| c).collect(),
+    }
+}
@@ -0,0 +1,35 @@
+use formality_core::{judgment_fn, Downcast};
+use formality_types::grammar::{Const, Predicate, Ty, Wcs};
+
+use crate::decls::Decls;
+
+use super::env::Env;
+
+judgment_fn! {
+    /// The types that the constant `ct` is known to have. Values carry their type;
+    /// for const variables the type comes from `type_of_const` assumptions (which
+    /// are derived from `const N: ty` generics). In a well-formed environment,
+    /// every const variable has exactly one type.
+    pub fn type_of_const(
+        decls: Decls,
+        env: Env,
+        assumptions: Wcs,
+        ct: Const,
+    ) => Ty {
+        debug(ct, assumptions, env, decls)
+
+        (
+            (if let Some((_, ty)) = ct.as_value())
+            --- ("value")
+            (type_of_const(_decls, _env, _assumptions, ct) => ty)
+        )
+
+        (
+            (&assumptions => a)
+            (if let Some(Predicate::ConstHasType(ct1, ty)) = a.downcast::<Predicate>())
+            (if ct1 == ct)
+            --- ("assumption")
+            (type_of_const(_decls, _env, assumptions, ct) => ty)
+        )
+    }
+}
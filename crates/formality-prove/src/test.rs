@@ -1,10 +1,21 @@
 mod adt_wf;
+mod ambiguity;
+mod cache;
+mod decls;
+mod drop;
 mod eq_assumptions;
 mod eq_partial_eq;
 mod exists_constraints;
 mod expanding;
+mod interning;
 mod is_local;
 mod magic_copy;
 mod occurs_check;
+mod proof_tree;
 mod simple_impl;
+mod subsumption;
+mod sized;
+mod threads;
 mod universes;
+mod valtree;
+mod winnow;
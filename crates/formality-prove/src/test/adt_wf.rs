@@ -5,15 +5,18 @@ use formality_types::{
     rust::term,
 };
 
-use crate::{decls::Decls, prove::prove};
+use crate::{
+    decls::{Decls, DeclsData},
+    prove::prove,
+};
 
 fn decls() -> Decls {
-    Decls {
+    Decls::new(DeclsData {
         trait_decls: vec![term("trait Foo<ty Self> where {}")],
         impl_decls: vec![term("impl Foo(u32) where {}")],
         adt_decls: vec![term("adt X<ty T> where {Foo(T)}")],
-        ..Decls::empty()
-    }
+        ..DeclsData::empty()
+    })
 }
 
 #[test]
@@ -27,9 +30,11 @@ fn well_formed_adt() {
                 env: Env {
                     variables: [],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {},
+                ambiguity: {},
             },
         }
     "#]]
@@ -0,0 +1,142 @@
+use expect_test::expect;
+use formality_macros::test;
+use formality_types::rust::term;
+
+use crate::{test_util::test_prove, Decls, DeclsData};
+
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        trait_decls: vec![term("trait Debug<ty Self> where {}")],
+        impl_decls: vec![
+            term("impl Debug(u32) where {}"),
+            term("impl Debug(i32) where {}"),
+        ],
+        ..DeclsData::empty()
+    })
+}
+
+/// `A` could be either `u32` or `i32`, but all solutions agree that `B` is `u32`.
+#[test]
+fn multiple_solutions_guidance() {
+    let constraints = test_prove(
+        decls(),
+        term("collapse exists<ty A, ty B> {} => {Debug(A), B = u32}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?A,
+                        ?B,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: false,
+                substitution: {
+                    ?B => u32,
+                },
+                ambiguity: {
+                    MultipleSolutions,
+                },
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+/// A single solution is not affected by collapsing.
+#[test]
+fn single_solution() {
+    let constraints = test_prove(
+        decls(),
+        term("collapse exists<ty A> {} => {A = u32, Debug(A)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?A,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {
+                    ?A => u32,
+                },
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn unresolved_sized() {
+    let constraints = test_prove(decls(), term("exists<ty A> {} => {@sized(A)}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?A,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: false,
+                substitution: {},
+                ambiguity: {
+                    Unresolved(
+                        Ty(
+                            Ty {
+                                data: Variable(
+                                    ?A,
+                                ),
+                            },
+                        ),
+                    ),
+                },
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn may_be_remote() {
+    let constraints = test_prove(decls(), term("coherence_mode {} => {Debug(u64)}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: true,
+                    candidate_preference: All,
+                },
+                known_true: false,
+                substitution: {},
+                ambiguity: {
+                    MayBeRemote(
+                        TraitRef {
+                            trait_id: Debug,
+                            parameters: [
+                                Ty(
+                                    Ty {
+                                        data: RigidTy(
+                                            u64,
+                                        ),
+                                    },
+                                ),
+                            ],
+                        },
+                    ),
+                },
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
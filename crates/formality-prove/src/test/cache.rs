@@ -0,0 +1,40 @@
+use formality_core::fixed_point::{cache_stats, clear_cache};
+use formality_macros::test;
+use formality_types::rust::term;
+
+use crate::decls::{Decls, DeclsData};
+use crate::test_util::test_prove;
+
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        trait_decls: vec![term("trait Debug<ty Self> where {}")],
+        impl_decls: vec![term("impl<ty T> Debug(Vec<T>) where {Debug(T)}")],
+        ..DeclsData::empty()
+    })
+}
+
+/// `prove` minimizes its input before proving it, so the same goal in a different
+/// environment is answered from the cache...
+#[test]
+fn unrelated_variables_share_entries() {
+    clear_cache();
+    test_prove(decls(), term("forall<ty T> {Debug(T)} => {Debug(Vec<T>)}"));
+    let before = cache_stats();
+    test_prove(
+        decls(),
+        term("forall<ty A, ty B, ty T> {Debug(T)} => {Debug(Vec<T>)}"),
+    );
+    let after = cache_stats();
+    assert!(after.hits > before.hits);
+    assert_eq!(after.misses, before.misses);
+}
+
+/// ...but not if its variables have different names, as the names appear in the results.
+#[test]
+fn renamed_variables_do_not_share_entries() {
+    clear_cache();
+    test_prove(decls(), term("forall<ty T> {Debug(T)} => {Debug(Vec<T>)}"));
+    let before = cache_stats();
+    test_prove(decls(), term("forall<ty U> {Debug(U)} => {Debug(Vec<U>)}"));
+    assert!(cache_stats().misses > before.misses);
+}
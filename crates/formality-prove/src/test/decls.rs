@@ -0,0 +1,92 @@
+use expect_test::expect;
+use formality_macros::test;
+use formality_types::grammar::{TraitId, Wc};
+use formality_types::rust::term;
+
+use crate::{prove::prove, Decls, DeclsData, ImplDecl};
+
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        trait_decls: vec![
+            term("trait Debug<ty Self> where {}"),
+            term("trait Ord<ty Self> where {PartialOrd(Self)}"),
+            term("trait PartialOrd<ty Self> where {}"),
+        ],
+        impl_decls: vec![
+            term("impl Debug(u32) where {}"),
+            term("impl PartialOrd(u32) where {}"),
+            term("impl<ty T> Debug(Vec<T>) where {Debug(T)}"),
+        ],
+        ..DeclsData::empty()
+    })
+}
+
+#[test]
+fn impls_by_trait() {
+    let decls = decls();
+    let debug: TraitId = term("Debug");
+    let impls: Vec<&ImplDecl> = decls.impl_decls(&debug).collect();
+    expect![[r#"
+        [
+            ImplDecl {
+                binder: Debug(u32),
+            },
+            ImplDecl {
+                binder: <ty T> Debug(Vec<^T>) where {Debug(^T)},
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&impls);
+
+    let ord: TraitId = term("Ord");
+    assert_eq!(decls.impl_decls(&ord).count(), 0);
+}
+
+#[test]
+fn trait_invariants() {
+    expect![[r#"
+        {
+            TraitInvariant {
+                binder: <ty Self> Ord(^Self) => PartialOrd(^Self),
+            },
+        }
+    "#]]
+    .assert_debug_eq(decls().trait_invariants());
+}
+
+/// The debug output lists the declarations in program order.
+#[test]
+fn debug() {
+    expect!["decls(222, 64, [trait Debug <ty Self> , trait Ord <ty Self> where {PartialOrd(^Self)}, trait PartialOrd <ty Self> ], [impl Debug(u32), impl PartialOrd(u32), impl <ty T> Debug(Vec<^T>) where {Debug(^T)}], [], [], [], [], [], {}, {})"]
+    .assert_eq(&format!("{:?}", decls()));
+}
+
+#[test]
+#[should_panic(expected = "multiple traits named `Debug`")]
+fn duplicate_trait() {
+    let decls = Decls::new(DeclsData {
+        trait_decls: vec![
+            term("trait Debug<ty Self> where {}"),
+            term("trait Debug<ty Self> where {}"),
+        ],
+        ..DeclsData::empty()
+    });
+    decls.trait_decl(&term("Debug"));
+}
+
+/// Proving a goal should not get slower with the number of unrelated impls.
+#[test]
+fn many_impls() {
+    let mut data = DeclsData::empty();
+    for i in 0..300 {
+        data.trait_decls
+            .push(term(&format!("trait Trait{i}<ty Self> where {{}}")));
+        data.impl_decls
+            .push(term(&format!("impl Trait{i}(u32) where {{}}")));
+    }
+    let decls = Decls::new(data);
+    let goal: Wc = term("Trait150(u32)");
+    assert!(prove(decls, (), (), goal)
+        .iter()
+        .any(|c| c.unconditionally_true()));
+}
@@ -0,0 +1,232 @@
+use expect_test::expect;
+use formality_macros::test;
+use formality_types::rust::term;
+
+use crate::decls::{Decls, DeclsData};
+
+use crate::test_util::test_prove;
+
+/// `Guard` and `Box` have destructors; `Box`'s destructor does not access its `T`.
+/// `Wrapper` has no destructor of its own but owns a `Guard`.
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        adt_decls: vec![
+            term("adt Guard<ty T> where {} {Guard {value : T}}"),
+            term("adt Box<ty T> where {} {Box {value : T}}"),
+            term("adt Wrapper<ty T> where {} {Wrapper {guard : Guard<T>}}"),
+            term("adt Plain<ty T> where {} {Plain {value : T}}"),
+        ],
+        drop_decls: vec![
+            term("drop Guard <ty T> "),
+            term("drop Box <ty T> may_dangle (T)"),
+        ],
+        ..DeclsData::empty()
+    })
+}
+
+#[test]
+fn scalar_does_not_need_drop() {
+    let constraints = test_prove(decls(), term("{} => {@needs_drop(u32)}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn adt_with_destructor_needs_drop() {
+    let constraints = test_prove(decls(), term("{} => {@needs_drop(Guard<u32>)}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn adt_owning_destructor_needs_drop() {
+    let constraints = test_prove(decls(), term("{} => {@needs_drop((u32, Wrapper<u32>))}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn plain_adt_does_not_need_drop() {
+    let constraints = test_prove(decls(), term("{} => {@needs_drop(Plain<u32>)}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn generic_type_needs_drop() {
+    let constraints = test_prove(decls(), term("{} => {for<ty T> @needs_drop(Plain<T>)}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn dropck_requires_parameter_to_outlive() {
+    let constraints = test_prove(
+        decls(),
+        term("forall<lt a, ty T> {} => {@dropck_outlives(Wrapper<T>, a)}"),
+    );
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn dropck_with_outlives_assumption() {
+    let constraints = test_prove(
+        decls(),
+        term("forall<lt a, ty T> {T : a} => {@dropck_outlives(Wrapper<T>, a)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        !a,
+                        !T,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn dropck_may_dangle() {
+    let constraints = test_prove(
+        decls(),
+        term("forall<lt a, ty T> {} => {@dropck_outlives(Box<&a T>, a)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        !a,
+                        !T,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn dropck_plain_adt_checks_fields() {
+    let constraints = test_prove(
+        decls(),
+        term("forall<lt a, ty T> {} => {@dropck_outlives(Plain<T>, a)}"),
+    );
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+/// Two destructors for one ADT are rejected by coherence; the solver requires both,
+/// so `T` must outlive `'a` even though one of them marks it `may_dangle`.
+#[test]
+fn multiple_destructors() {
+    let decls = Decls::new(DeclsData {
+        adt_decls: vec![term("adt Box<ty T> where {} {Box {value : T}}")],
+        drop_decls: vec![
+            term("drop Box <ty T> may_dangle (T)"),
+            term("drop Box <ty T> "),
+        ],
+        ..DeclsData::empty()
+    });
+    let constraints = test_prove(
+        decls,
+        term("forall<lt a, ty T> {} => {@dropck_outlives(Box<&a T>, a)}"),
+    );
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+/// Consts contain no lifetimes, so a destructor places no requirement on them.
+#[test]
+fn dropck_const_parameter() {
+    let decls = Decls::new(DeclsData {
+        adt_decls: vec![term("adt Buf<const N> where {} {Buf {}}")],
+        drop_decls: vec![term("drop Buf <const N> ")],
+        ..DeclsData::empty()
+    });
+    let constraints = test_prove(
+        decls,
+        term("forall<lt a> {} => {@dropck_outlives(Buf<const 3_usize>, a)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        !a,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
@@ -18,9 +18,11 @@ fn test_a() {
                 env: Env {
                     variables: [],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {},
+                ambiguity: {},
             },
         }
     "#]]
@@ -39,15 +41,17 @@ fn test_b() {
                 env: Env {
                     variables: [
                         ?ty_2,
-                        ?ty_1,
+                        ?A,
                     ],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {
-                    ?ty_1 => Vec<u32>,
+                    ?A => Vec<u32>,
                     ?ty_2 => u32,
                 },
+                ambiguity: {},
             },
         }
     "#]]
@@ -66,9 +70,11 @@ fn test_normalize_assoc_ty() {
                 env: Env {
                     variables: [],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {},
+                ambiguity: {},
             },
         }
     "#]]
@@ -103,15 +109,17 @@ fn test_normalize_assoc_ty_existential1() {
             Constraints {
                 env: Env {
                     variables: [
-                        !ty_1,
-                        ?ty_2,
+                        !T,
+                        ?A,
                     ],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {
-                    ?ty_2 => !ty_1,
+                    ?A => !T,
                 },
+                ambiguity: {},
             },
         }
     "#]]
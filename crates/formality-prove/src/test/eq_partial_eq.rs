@@ -5,17 +5,20 @@ use formality_types::{
     rust::term,
 };
 
-use crate::{decls::Decls, prove::prove};
+use crate::{
+    decls::{Decls, DeclsData},
+    prove::prove,
+};
 
 /// Simple example decls consisting only of two trait declarations.
 fn decls() -> Decls {
-    Decls {
+    Decls::new(DeclsData {
         trait_decls: vec![
             term("trait Eq<ty Self> where {PartialEq(Self)}"),
             term("trait PartialEq<ty Self> where {}"),
         ],
-        ..Decls::empty()
-    }
+        ..DeclsData::empty()
+    })
 }
 
 #[test]
@@ -29,9 +32,11 @@ fn eq_implies_partial_eq() {
                 env: Env {
                     variables: [],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {},
+                ambiguity: {},
             },
         }
     "#]]
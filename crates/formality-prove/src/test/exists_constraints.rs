@@ -2,17 +2,17 @@ use expect_test::expect;
 use formality_macros::test;
 use formality_types::rust::term;
 
-use crate::decls::Decls;
+use crate::decls::{Decls, DeclsData};
 
 use crate::test_util::test_prove;
 
 /// Simple example decls consisting only of two trait declarations.
 fn decls() -> Decls {
-    Decls {
+    Decls::new(DeclsData {
         trait_decls: vec![term("trait Foo<ty Self> where {}")],
         impl_decls: vec![term("impl<ty T> Foo(Vec<T>) where {}")],
-        ..Decls::empty()
-    }
+        ..DeclsData::empty()
+    })
 }
 
 /// Test that `exists<T> Foo(U)` yields `U = Vec<X>` for some fresh `X`
@@ -25,14 +25,16 @@ fn exists_u_for_t() {
                 env: Env {
                     variables: [
                         ?ty_2,
-                        ?ty_1,
+                        ?U,
                     ],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {
-                    ?ty_1 => Vec<?ty_2>,
+                    ?U => Vec<?ty_2>,
                 },
+                ambiguity: {},
             },
         }
     "#]]
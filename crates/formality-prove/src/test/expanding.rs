@@ -2,16 +2,16 @@ use expect_test::expect;
 use formality_macros::test;
 use formality_types::rust::term;
 
-use crate::{test_util::test_prove, Decls};
+use crate::{test_util::test_prove, Decls, DeclsData};
 
 /// Simple example decls consisting only of two trait declarations.
 fn decls() -> Decls {
-    Decls {
+    Decls::new(DeclsData {
         max_size: 10,
         trait_decls: vec![term("trait Debug<ty Self> where {}")],
         impl_decls: vec![term("impl<ty T> Debug(Vec<T>) where {Debug(T)}")],
-        ..Decls::empty()
-    }
+        ..DeclsData::empty()
+    })
 }
 
 /// There is no U that is equal to all T.
@@ -23,12 +23,95 @@ fn expanding() {
             Constraints {
                 env: Env {
                     variables: [
-                        ?ty_0,
+                        ?T,
                     ],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: false,
                 substitution: {},
+                ambiguity: {
+                    Overflow(
+                        Wcs {
+                            set: {
+                                Wc {
+                                    data: PR(
+                                        Predicate(
+                                            IsImplemented(
+                                                TraitRef {
+                                                    trait_id: Debug,
+                                                    parameters: [
+                                                        Ty(
+                                                            Ty {
+                                                                data: Variable(
+                                                                    ?T,
+                                                                ),
+                                                            },
+                                                        ),
+                                                    ],
+                                                },
+                                            ),
+                                        ),
+                                    ),
+                                },
+                            },
+                        },
+                    ),
+                },
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+/// Like `expanding`, but with a small depth limit rather than a small size limit.
+#[test]
+fn expanding_depth() {
+    let decls = Decls::new(DeclsData {
+        max_depth: 3,
+        ..decls().data().clone()
+    });
+    let constraints = test_prove(decls, term("exists<ty T> {} => {Debug(T)}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?T,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: false,
+                substitution: {},
+                ambiguity: {
+                    Overflow(
+                        Wcs {
+                            set: {
+                                Wc {
+                                    data: PR(
+                                        Predicate(
+                                            IsImplemented(
+                                                TraitRef {
+                                                    trait_id: Debug,
+                                                    parameters: [
+                                                        Ty(
+                                                            Ty {
+                                                                data: Variable(
+                                                                    ?T,
+                                                                ),
+                                                            },
+                                                        ),
+                                                    ],
+                                                },
+                                            ),
+                                        ),
+                                    ),
+                                },
+                            },
+                        },
+                    ),
+                },
             },
         }
     "#]]
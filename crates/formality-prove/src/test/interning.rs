@@ -0,0 +1,34 @@
+use formality_macros::test;
+use formality_types::grammar::{Binder, Ty, TyData, Wc, WcData};
+use formality_types::rust::term;
+
+fn same_data(a: &Ty, b: &Ty) -> bool {
+    std::ptr::eq::<TyData>(a.data(), b.data())
+}
+
+#[test]
+fn equal_types_share_data() {
+    let a: Ty = term("Vec<u32>");
+    let b: Ty = term("Vec<u32>");
+    assert!(same_data(&a, &b));
+
+    let c: Ty = term("Vec<u64>");
+    assert!(!same_data(&a, &c));
+    assert!(a < c);
+}
+
+#[test]
+fn substitution_preserves_interning() {
+    let binder: Binder<Ty> = term("<ty T> Vec<T>");
+    let u32: Ty = term("u32");
+    let a = binder.instantiate_with(&[u32]).unwrap();
+    let b: Ty = term("Vec<u32>");
+    assert!(same_data(&a, &b));
+}
+
+#[test]
+fn where_clauses() {
+    let a: Wc = term("Debug(Vec<u32>)");
+    let b: Wc = term("Debug(Vec<u32>)");
+    assert!(std::ptr::eq::<WcData>(a.data(), b.data()));
+}
@@ -29,12 +29,24 @@ fn test_exists_not_local() {
             Constraints {
                 env: Env {
                     variables: [
-                        ?ty_1,
+                        ?T,
                     ],
                     coherence_mode: true,
+                    candidate_preference: All,
                 },
                 known_true: false,
                 substitution: {},
+                ambiguity: {
+                    Unresolved(
+                        Ty(
+                            Ty {
+                                data: Variable(
+                                    ?T,
+                                ),
+                            },
+                        ),
+                    ),
+                },
             },
         }
     "#]] // FIXME: really this should be ambiguous, not sure if it matters
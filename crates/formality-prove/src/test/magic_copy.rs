@@ -2,13 +2,13 @@ use expect_test::expect;
 use formality_macros::test;
 use formality_types::rust::term;
 
-use crate::decls::Decls;
+use crate::decls::{Decls, DeclsData};
 
 use crate::test_util::test_prove;
 
 /// Simple example decls consisting only of two trait declarations.
 fn decls() -> Decls {
-    Decls {
+    Decls::new(DeclsData {
         trait_decls: vec![
             term("trait Copy<ty Self> where {}"),
             term("trait Magic<ty Self> where {Copy(Self)}"),
@@ -17,8 +17,8 @@ fn decls() -> Decls {
             term("impl<ty T> Magic(T) where {Magic(T)}"),
             term("impl Copy(u32) where {}"),
         ],
-        ..Decls::empty()
-    }
+        ..DeclsData::empty()
+    })
 }
 
 #[test]
@@ -2,17 +2,17 @@ use expect_test::expect;
 use formality_macros::test;
 use formality_types::rust::term;
 
-use crate::decls::Decls;
+use crate::decls::{Decls, DeclsData};
 
 use crate::test_util::test_prove;
 
 /// Simple example decls consisting only of two trait declarations.
 fn decls() -> Decls {
-    Decls {
+    Decls::new(DeclsData {
         trait_decls: vec![term("trait Foo<ty Self> where {}")],
         impl_decls: vec![term("impl<ty T> Foo(Vec<T>) where {}")],
-        ..Decls::empty()
-    }
+        ..DeclsData::empty()
+    })
 }
 
 /// Test that `X = Vec<X>` cannot be solved
@@ -35,16 +35,18 @@ fn eq_variable_to_rigid() {
                 env: Env {
                     variables: [
                         ?ty_3,
-                        ?ty_1,
-                        ?ty_2,
+                        ?X,
+                        ?Y,
                     ],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {
-                    ?ty_1 => Vec<?ty_3>,
-                    ?ty_2 => ?ty_3,
+                    ?X => Vec<?ty_3>,
+                    ?Y => ?ty_3,
                 },
+                ambiguity: {},
             },
         }
     "#]]
@@ -61,16 +63,18 @@ fn eq_rigid_to_variable() {
                 env: Env {
                     variables: [
                         ?ty_3,
-                        ?ty_1,
-                        ?ty_2,
+                        ?X,
+                        ?Y,
                     ],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {
-                    ?ty_1 => Vec<?ty_3>,
-                    ?ty_2 => ?ty_3,
+                    ?X => Vec<?ty_3>,
+                    ?Y => ?ty_3,
                 },
+                ambiguity: {},
             },
         }
     "#]]
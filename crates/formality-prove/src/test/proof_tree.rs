@@ -0,0 +1,71 @@
+use expect_test::expect;
+use formality_macros::test;
+use formality_types::grammar::Wc;
+use formality_types::rust::term;
+
+use crate::{
+    decls::{Decls, DeclsData},
+    prove::prove_with_proof_trees,
+};
+
+/// Simple example decls consisting only of two trait declarations.
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        trait_decls: vec![term("trait Debug<ty Self> where {}")],
+        impl_decls: vec![
+            term("impl<ty T> Debug(Vec<T>) where {Debug(T)}"),
+            term("impl Debug(u32) where {}"),
+        ],
+        ..DeclsData::empty()
+    })
+}
+
+#[test]
+fn vec_u32_debug() {
+    let goal: Wc = term("Debug(Vec<u32>)");
+    let results = prove_with_proof_trees(decls(), (), (), goal);
+    assert_eq!(results.len(), 1);
+    let (_, tree) = results.into_iter().next().unwrap();
+    expect![[r#"
+        ("prove") prove(goal: {Debug(Vec<u32>)}, assumptions: {}, env: Env { variables: [], coherence_mode: false, candidate_preference: All }, decls: decls(222, 64, [trait Debug <ty Self> ], [impl <ty T> Debug(Vec<^T>) where {Debug(^T)}, impl Debug(u32)], [], [], [], [], [], {}, {})) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+            ("some") prove_wc_list() => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                ("trait candidates") prove_wc(goal: Debug(Vec<u32>)) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                    ("positive impl") trait_candidate(trait_ref: Debug(Vec<u32>)) => (Impl, Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} })
+                        ("prove") prove(goal: {Vec<u32> = Vec<?T>}, assumptions: {Debug(Vec<u32>)}, env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }
+                            ("some") prove_wc_list() => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }
+                                ("eq") prove_wc(goal: Vec<u32> = Vec<?T>) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }
+                                    ("symmetric") prove_eq(a: Vec<u32>, b: Vec<?T>) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }
+                                        ("symmetric") prove_eq(a: Vec<?T>, b: Vec<u32>) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }
+                                ("prove_after") prove_after(constraints: Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }, goal: {}) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }
+                                    ("prove") prove(env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                        ("none") prove_wc_list(env: Env { variables: [], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                        ("prove_after") prove_after(constraints: Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }, goal: {Debug(?T)}, assumptions: {Debug(Vec<u32>)}) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }
+                            ("prove") prove(goal: {Debug(u32)}, env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                ("some") prove_wc_list(env: Env { variables: [], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                    ("trait candidates") prove_wc(goal: Debug(u32)) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                        ("positive impl") trait_candidate(trait_ref: Debug(u32)) => (Impl, Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} })
+                                            ("prove") prove(goal: {u32 = u32}, assumptions: {Debug(Vec<u32>), Debug(u32)}) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                ("some") prove_wc_list() => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                    ("eq") prove_wc(goal: u32 = u32) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                        ("trivial") prove_eq(a: u32, b: u32) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                    ("prove_after") prove_after(constraints: Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }, goal: {}) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                        ("prove") prove(env: Env { variables: [], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                            ("none") prove_wc_list() => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                            ("prove_after") prove_after(constraints: Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }, goal: {}, assumptions: {Debug(Vec<u32>), Debug(u32)}) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                ("prove") prove(env: Env { variables: [], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                    ("none") prove_wc_list() => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                            ("prove_after") prove_after(constraints: Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }, goal: {}) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                ("prove") prove(env: Env { variables: [], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                                    ("none") prove_wc_list() => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                    ("prove_after") prove_after(constraints: Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }, goal: {}) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                        ("prove") prove(env: Env { variables: [], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                            ("none") prove_wc_list() => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                        ("prove_after") prove_after(constraints: Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }, goal: {}) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {?T => u32}, ambiguity: {} }
+                            ("prove") prove(env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [?T], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                                ("none") prove_wc_list(env: Env { variables: [], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                ("prove_after") prove_after(constraints: Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }, goal: {}) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                    ("prove") prove(env: Env { variables: [], coherence_mode: false, candidate_preference: All }) => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+                        ("none") prove_wc_list() => Constraints { env: Env { variables: [], coherence_mode: false, candidate_preference: All }, known_true: true, substitution: {}, ambiguity: {} }
+    "#]]
+    .assert_eq(&tree.to_string());
+}
@@ -3,18 +3,21 @@ use formality_macros::test;
 use formality_types::grammar::Wc;
 use formality_types::rust::term;
 
-use crate::{decls::Decls, prove::prove};
+use crate::{
+    decls::{Decls, DeclsData},
+    prove::prove,
+};
 
 /// Simple example decls consisting only of two trait declarations.
 fn decls() -> Decls {
-    Decls {
+    Decls::new(DeclsData {
         trait_decls: vec![term("trait Debug<ty Self> where {}")],
         impl_decls: vec![
             term("impl<ty T> Debug(Vec<T>) where {Debug(T)}"),
             term("impl Debug(u32) where {}"),
         ],
-        ..Decls::empty()
-    }
+        ..DeclsData::empty()
+    })
 }
 
 #[test]
@@ -27,9 +30,11 @@ fn vec_u32_debug() {
                 env: Env {
                     variables: [],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {},
+                ambiguity: {},
             },
         }
     "#]]
@@ -46,9 +51,11 @@ fn vec_vec_u32_debug() {
                 env: Env {
                     variables: [],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {},
+                ambiguity: {},
             },
         }
     "#]]
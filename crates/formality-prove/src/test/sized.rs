@@ -0,0 +1,138 @@
+use expect_test::expect;
+use formality_macros::test;
+use formality_types::rust::term;
+
+use crate::decls::{Decls, DeclsData};
+
+use crate::test_util::test_prove;
+
+/// `Wrapper` is sized exactly when its field is.
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        adt_decls: vec![term("adt Wrapper<ty T> where {} {Wrapper {value : T}}")],
+        ..DeclsData::empty()
+    })
+}
+
+#[test]
+fn sized_rigid_types() {
+    let constraints = test_prove(
+        decls(),
+        term("{} => {@sized(u128), @sized(char), @sized(!), @sized(*const str), @sized([u8; 3_usize]), @sized((f32, Wrapper<i128>))}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn slice_is_not_sized() {
+    let constraints = test_prove(decls(), term("{} => {@sized([u8])}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn tuple_with_str_is_not_sized() {
+    let constraints = test_prove(decls(), term("{} => {@sized((u8, str))}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn adt_with_str_is_not_sized() {
+    let constraints = test_prove(decls(), term("{} => {@sized(Wrapper<str>)}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn generic_type_is_sized() {
+    let constraints = test_prove(decls(), term("{} => {for<ty T> @sized(Wrapper<T>)}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn array_of_slices_is_not_wf() {
+    let constraints = test_prove(decls(), term("{} => {@wf([[u8]; 3_usize])}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn array_length_must_be_usize() {
+    let constraints = test_prove(decls(), term("{} => {@wf([u8; 3_u32])}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn array_valtree() {
+    let constraints = test_prove(
+        decls(),
+        term("{} => {@wf(const value([1, 2], [u8; 2_usize])), @wf(const value([1, 300], [u16; 2_usize]))}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn array_valtree_wrong_length() {
+    let constraints = test_prove(
+        decls(),
+        term("{} => {@wf(const value([1, 2, 3], [u8; 2_usize]))}"),
+    );
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
@@ -0,0 +1,98 @@
+use expect_test::expect;
+use formality_macros::test;
+use formality_types::rust::term;
+
+use crate::{test_util::test_prove, Decls, DeclsData};
+
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        trait_decls: vec![term("trait Foo<ty Self> where {}")],
+        impl_decls: vec![
+            term("impl<ty T> Foo(T) where {}"),
+            term("impl<ty T, ty U> Foo(T) where {}"),
+            term("impl Foo(u32) where {}"),
+        ],
+        ..DeclsData::empty()
+    })
+}
+
+/// The blanket impls give the same answer up to the unused variable `U`,
+/// and it is more general than the answer `X = u32` from the last impl.
+#[test]
+fn general_answer_subsumes() {
+    let constraints = test_prove(decls(), term("exists<ty X> {} => {Foo(X)}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?X,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+/// An ambiguous answer does not make a more specific, known answer redundant.
+#[test]
+fn ambiguous_answer_does_not_subsume() {
+    let decls = Decls::new(DeclsData {
+        trait_decls: vec![term("trait Bar<ty Self> where {}")],
+        impl_decls: vec![term("impl Bar(u32) where {}")],
+        ..DeclsData::empty()
+    });
+    let constraints = test_prove(decls, term("coherence_mode exists<ty X> {} => {Bar(X)}"));
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?X,
+                    ],
+                    coherence_mode: true,
+                    candidate_preference: All,
+                },
+                known_true: false,
+                substitution: {},
+                ambiguity: {
+                    MayBeRemote(
+                        TraitRef {
+                            trait_id: Bar,
+                            parameters: [
+                                Ty(
+                                    Ty {
+                                        data: Variable(
+                                            ?X,
+                                        ),
+                                    },
+                                ),
+                            ],
+                        },
+                    ),
+                },
+            },
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?X,
+                    ],
+                    coherence_mode: true,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {
+                    ?X => u32,
+                },
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
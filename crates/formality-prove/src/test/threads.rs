@@ -0,0 +1,52 @@
+use formality_macros::test;
+use formality_types::grammar::Wc;
+use formality_types::rust::term;
+
+use crate::{
+    decls::{Decls, DeclsData},
+    prove::prove,
+};
+
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        trait_decls: vec![term("trait Debug<ty Self> where {}")],
+        impl_decls: vec![
+            term("impl<ty T> Debug(Vec<T>) where {Debug(T)}"),
+            term("impl Debug(u32) where {}"),
+        ],
+        ..DeclsData::empty()
+    })
+}
+
+/// Each thread has its own judgment stacks and caches, so proving the same
+/// goals from several threads at once gives the same answers as proving them
+/// on one thread.
+#[test]
+fn prove_from_many_threads() {
+    let goals: Vec<Wc> = vec![
+        term("Debug(Vec<u32>)"),
+        term("Debug(Vec<Vec<u32>>)"),
+        term("Debug(Vec<i32>)"),
+    ];
+    let decls = decls();
+    let expected: Vec<_> = goals
+        .iter()
+        .map(|goal| prove(&decls, (), (), goal))
+        .collect();
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                scope.spawn(|| {
+                    goals
+                        .iter()
+                        .map(|goal| prove(&decls, (), (), goal))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    });
+}
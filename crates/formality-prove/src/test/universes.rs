@@ -2,7 +2,7 @@ use expect_test::expect;
 use formality_macros::test;
 use formality_types::rust::term;
 
-use crate::decls::Decls;
+use crate::decls::{Decls, DeclsData};
 
 use crate::test_util::test_prove;
 
@@ -20,11 +20,11 @@ fn exists_u_for_t() {
 /// There is U that is equal to some T.
 #[test]
 fn for_t_exists_u() {
-    let decls = Decls {
+    let decls = Decls::new(DeclsData {
         trait_decls: vec![term("trait Test<ty Self, ty T> where {}")],
         impl_decls: vec![term("impl<ty X, ty Y> Test(X, Y) where {X = Y}")],
-        ..Decls::empty()
-    };
+        ..DeclsData::empty()
+    });
 
     let constraints = test_prove(decls, term("{} => {for<ty T> Test(T, T)}"));
     expect![[r#"
@@ -33,9 +33,11 @@ fn for_t_exists_u() {
                 env: Env {
                     variables: [],
                     coherence_mode: false,
+                    candidate_preference: All,
                 },
                 known_true: true,
                 substitution: {},
+                ambiguity: {},
             },
         }
     "#]]
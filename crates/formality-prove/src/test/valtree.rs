@@ -0,0 +1,259 @@
+use expect_test::expect;
+use formality_macros::test;
+use formality_types::{
+    grammar::{Const, Scalar, Ty, ValTree, Wcs},
+    rust::term,
+};
+
+use crate::decls::{Decls, DeclsData};
+use crate::prove::{prove_valtree_wf, Env};
+
+use crate::test_util::test_prove;
+
+/// `Opt` is an enum with a unit variant and a one-field variant.
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        adt_decls: vec![term("adt Opt<ty T> where {} {None {}, Some {0 : T}}")],
+        ..DeclsData::empty()
+    })
+}
+
+#[test]
+fn scalar_in_range() {
+    let constraints = test_prove(
+        decls(),
+        term("{} => {@wf(const 255_u8), @wf(const -128_i8), @wf(const true)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn unsigned_out_of_range() {
+    let constraints = test_prove(decls(), term("{} => {@wf(const 300_u8)}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn negative_unsigned() {
+    let constraints = test_prove(decls(), term("{} => {@wf(const -1_u32)}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn signed_out_of_range() {
+    let constraints = test_prove(decls(), term("{} => {@wf(const 128_i8)}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn negative_u128() {
+    let constraints = test_prove(decls(), term("{} => {@wf(const -1_u128)}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn i128_out_of_range() {
+    let constraints = test_prove(
+        decls(),
+        term("{} => {@wf(const 170141183460469231731687303715884105728_i128)}"),
+    );
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn u128_max() {
+    let constraints = test_prove(
+        decls(),
+        term("{} => {@wf(const 340282366920938463463374607431768211455_u128), @wf(const -170141183460469231731687303715884105728_i128)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+/// Large `u128` values are not mistaken for negative ones.
+#[test]
+fn u128_debug() {
+    let c: Const = term("340282366920938463463374607431768211455_u128");
+    expect!["value(340282366920938463463374607431768211455, u128)"].assert_eq(&format!("{c:?}"));
+}
+
+#[test]
+fn branches_are_not_bool() {
+    let constraints = test_prove(decls(), term("{} => {@wf(const value([], bool))}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn tuple() {
+    let constraints = test_prove(
+        decls(),
+        term("{} => {@wf(const value([1, -1, [true, 2]], (u8, i64, (bool, u32))))}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn tuple_wrong_arity() {
+    let constraints = test_prove(decls(), term("{} => {@wf(const value([1], (u8, u8)))}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+// Reference types are not (yet) well-formed, so call the valtree judgment directly.
+#[test]
+fn shared_reference() {
+    let constraints = prove_valtree_wf(
+        decls(),
+        Env::default(),
+        Wcs::t(),
+        ValTree::Leaf(Scalar::new(3)),
+        term::<Ty>("&static u8"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn shared_reference_out_of_range() {
+    let constraints = prove_valtree_wf(
+        decls(),
+        Env::default(),
+        Wcs::t(),
+        ValTree::Leaf(Scalar::new(300)),
+        term::<Ty>("&static u8"),
+    );
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn mutable_reference() {
+    let constraints = prove_valtree_wf(
+        decls(),
+        Env::default(),
+        Wcs::t(),
+        ValTree::Leaf(Scalar::new(3)),
+        term::<Ty>("&mut static u8"),
+    );
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn enum_variants() {
+    let constraints = test_prove(
+        decls(),
+        term("{} => {@wf(const value([0], Opt<u8>)), @wf(const value([1, 7], Opt<u8>))}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn enum_variant_index_out_of_range() {
+    let constraints = test_prove(decls(), term("{} => {@wf(const value([2], Opt<u8>))}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn enum_variant_field_count() {
+    let constraints = test_prove(decls(), term("{} => {@wf(const value([0, 7], Opt<u8>))}"));
+    expect![[r#"
+        {}
+    "#]]
+    .assert_debug_eq(&constraints);
+}
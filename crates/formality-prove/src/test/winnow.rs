@@ -0,0 +1,175 @@
+use expect_test::expect;
+use formality_macros::test;
+use formality_types::rust::term;
+
+use crate::{test_util::test_prove, Decls, DeclsData};
+
+/// A blanket impl that only applies for `X = u32`.
+fn decls() -> Decls {
+    Decls::new(DeclsData {
+        trait_decls: vec![term("trait Trait<ty Self, ty X> where {}")],
+        impl_decls: vec![term("impl<ty T> Trait(T, u32) where {}")],
+        ..DeclsData::empty()
+    })
+}
+
+/// A blanket impl that applies for every `X`.
+fn decls_any() -> Decls {
+    Decls::new(DeclsData {
+        trait_decls: vec![term("trait Trait<ty Self, ty X> where {}")],
+        impl_decls: vec![term("impl<ty T, ty U> Trait(T, U) where {}")],
+        ..DeclsData::empty()
+    })
+}
+
+/// By default, both the where-clause and the impl are used.
+#[test]
+fn where_clause_and_impl() {
+    let constraints = test_prove(
+        decls(),
+        term("forall<ty T> exists<ty X> {Trait(T, i32)} => {Trait(T, X)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        !T,
+                        ?X,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {
+                    ?X => u32,
+                },
+                ambiguity: {},
+            },
+            Constraints {
+                env: Env {
+                    variables: [
+                        !T,
+                        ?X,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: All,
+                },
+                known_true: true,
+                substitution: {
+                    ?X => i32,
+                },
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn where_clause_preferred_old_solver() {
+    let constraints = test_prove(
+        decls(),
+        term("old_solver forall<ty T> exists<ty X> {Trait(T, i32)} => {Trait(T, X)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        !T,
+                        ?X,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: OldSolver,
+                },
+                known_true: true,
+                substitution: {
+                    ?X => i32,
+                },
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+#[test]
+fn where_clause_preferred_new_solver() {
+    let constraints = test_prove(
+        decls(),
+        term("new_solver forall<ty T> exists<ty X> {Trait(T, i32)} => {Trait(T, X)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        !T,
+                        ?X,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: NewSolver,
+                },
+                known_true: true,
+                substitution: {
+                    ?X => i32,
+                },
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+/// The where-clause mentions no generic parameters, so it does not win over the impl.
+#[test]
+fn global_where_clause_new_solver() {
+    let constraints = test_prove(
+        decls_any(),
+        term("new_solver exists<ty X> {Trait(u32, i32)} => {Trait(u32, X)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?X,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: NewSolver,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
+
+/// The old solver drops global where-clauses in favor of impls that always apply.
+#[test]
+fn global_where_clause_old_solver() {
+    let constraints = test_prove(
+        decls_any(),
+        term("old_solver exists<ty X> {Trait(u32, i32)} => {Trait(u32, X)}"),
+    );
+    expect![[r#"
+        {
+            Constraints {
+                env: Env {
+                    variables: [
+                        ?X,
+                    ],
+                    coherence_mode: false,
+                    candidate_preference: OldSolver,
+                },
+                known_true: true,
+                substitution: {},
+                ambiguity: {},
+            },
+        }
+    "#]]
+    .assert_debug_eq(&constraints);
+}
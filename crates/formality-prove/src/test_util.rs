@@ -6,7 +6,7 @@ use formality_types::grammar::{Binder, Wcs};
 
 use crate::{
     decls::Decls,
-    prove::{prove, Constraints, Env},
+    prove::{prove, CandidatePreference, Constraints, Env},
 };
 
 /// Useful assertions for use in tests.
@@ -14,6 +14,12 @@ use crate::{
 pub enum TestAssertion {
     #[grammar(coherence_mode $v0)]
     CoherenceMode(Arc<TestAssertion>),
+    #[grammar(collapse $v0)]
+    Collapse(Arc<TestAssertion>),
+    #[grammar(old_solver $v0)]
+    OldSolver(Arc<TestAssertion>),
+    #[grammar(new_solver $v0)]
+    NewSolver(Arc<TestAssertion>),
     #[grammar(forall $v0)]
     ForAll(Binder<Arc<TestAssertion>>),
     #[grammar(exists $v0)]
@@ -28,6 +34,7 @@ pub enum TestAssertion {
 /// using with expect.
 pub fn test_prove(decls: Decls, mut assertion: Arc<TestAssertion>) -> Set<Constraints> {
     let mut env = Env::default();
+    let mut collapse = false;
 
     loop {
         match &*assertion {
@@ -46,13 +53,34 @@ pub fn test_prove(decls: Decls, mut assertion: Arc<TestAssertion>) -> Set<Constr
             }
 
             TestAssertion::Prove(assumptions, goals) => {
-                return prove(decls, env, assumptions, goals);
+                let solutions = prove(decls, &env, assumptions, goals);
+                if collapse {
+                    return Constraints::collapse(&env, &solutions)
+                        .into_iter()
+                        .collect();
+                }
+                return solutions;
             }
 
             TestAssertion::CoherenceMode(assertion1) => {
                 env = env.with_coherence_mode(true);
                 assertion = assertion1.clone();
             }
+
+            TestAssertion::OldSolver(assertion1) => {
+                env = env.with_candidate_preference(CandidatePreference::OldSolver);
+                assertion = assertion1.clone();
+            }
+
+            TestAssertion::NewSolver(assertion1) => {
+                env = env.with_candidate_preference(CandidatePreference::NewSolver);
+                assertion = assertion1.clone();
+            }
+
+            TestAssertion::Collapse(assertion1) => {
+                collapse = true;
+                assertion = assertion1.clone();
+            }
         }
     }
 }
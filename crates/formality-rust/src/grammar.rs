@@ -3,15 +3,18 @@ use std::sync::Arc;
 use formality_core::{term, Upcast};
 use formality_types::{
     grammar::{
-        AdtId, AliasTy, AssociatedItemId, Binder, Const, CrateId, Fallible, FieldId, FnId, Lt,
-        Parameter, TraitId, TraitRef, Ty, Wc,
+        AdtId, AliasTy, AssociatedItemId, Binder, Const, CrateId, Fallible, FnId, Lt, Parameter,
+        TraitId, TraitRef, Ty, Wc,
     },
     rust::Term,
 };
 
-use crate::grammar::mir::MirFnBody;
+use crate::{grammar::mir::MirFnBody, HasWhereClauses};
+
+pub use formality_types::grammar::{FieldName, VariantId};
 
 pub mod mir;
+mod parse_impls;
 
 #[term($crates)]
 pub struct Program {
@@ -41,6 +44,32 @@ impl Program {
             Ok(traits.pop().unwrap())
         }
     }
+
+    /// Returns the trait declared with the given lang item (e.g., `#[lang(drop)]`), if any.
+    /// Errors if more than one trait claims the same lang item.
+    pub fn lang_item_trait(&self, lang_item: LangItem) -> Fallible<Option<&Trait>> {
+        let mut traits: Vec<&Trait> = self
+            .items_from_all_crates()
+            .filter_map(|crate_item| match crate_item {
+                CrateItem::Trait(t) if t.is_lang_item(lang_item) => Some(t),
+                _ => None,
+            })
+            .collect();
+        if traits.len() > 1 {
+            anyhow::bail!("multiple traits declared as lang item `{lang_item:?}`")
+        } else {
+            Ok(traits.pop())
+        }
+    }
+
+    /// True if `trait_id` names the trait declared with the given lang item.
+    pub fn is_lang_item_trait(&self, trait_id: &TraitId, lang_item: LangItem) -> bool {
+        self.items_from_all_crates()
+            .any(|crate_item| match crate_item {
+                CrateItem::Trait(t) => t.id == *trait_id && t.is_lang_item(lang_item),
+                _ => false,
+            })
+    }
 }
 
 #[term(crate $id { $*items })]
@@ -68,10 +97,16 @@ pub enum CrateItem {
 #[term(struct $id $binder)]
 pub struct Struct {
     pub id: AdtId,
-    pub binder: Binder<StructBoundData>,
+    pub binder: ItemBinder<StructBoundData>,
 }
 
 impl Struct {
+    /// True for structs like `struct Foo(u32)`, i.e., `struct Foo { 0: u32 }`.
+    pub fn is_tuple_struct(&self) -> bool {
+        let StructBoundData { fields, .. } = self.binder.peek();
+        !fields.is_empty() && fields.iter().all(|f| matches!(f.name, FieldName::Index(_)))
+    }
+
     pub fn to_adt(&self) -> Adt {
         let (
             vars,
@@ -108,34 +143,17 @@ pub struct Field {
     pub ty: Ty,
 }
 
-#[term]
-pub enum FieldName {
-    #[cast]
-    Id(FieldId),
-    #[cast]
-    Index(usize),
-}
-
-formality_core::id!(VariantId);
-
-impl VariantId {
-    /// Returns the special variant-id used for the single variant of a struct.
-    pub fn for_struct() -> Self {
-        VariantId::new("struct")
-    }
-}
-
 #[term(enum $id $binder)]
 pub struct Enum {
     pub id: AdtId,
-    pub binder: Binder<AdtBoundData>,
+    pub binder: ItemBinder<AdtBoundData>,
 }
 
 impl Enum {
     pub fn to_adt(&self) -> Adt {
         Adt {
             id: self.id.clone(),
-            binder: self.binder.clone(),
+            binder: self.binder.binder.clone(),
         }
     }
 }
@@ -160,19 +178,61 @@ pub struct Variant {
     pub fields: Vec<Field>,
 }
 
-#[term(trait $id $binder)]
+#[term($*attributes trait $id $binder)]
 pub struct Trait {
+    pub attributes: Vec<TraitAttribute>,
     pub id: TraitId,
     pub binder: TraitBinder<TraitBoundData>,
 }
 
+impl Trait {
+    pub fn is_lang_item(&self, lang_item: LangItem) -> bool {
+        self.attributes
+            .iter()
+            .any(|attr| matches!(attr, TraitAttribute::Lang(l) if *l == lang_item))
+    }
+}
+
+#[term]
+pub enum TraitAttribute {
+    #[grammar(#[lang($v0)])]
+    Lang(LangItem),
+}
+
+/// Traits that the type system treats specially.
+#[term]
+#[derive(Copy)]
+pub enum LangItem {
+    /// The `Drop` trait: impls define destructors and are subject to extra restrictions.
+    #[grammar(drop)]
+    Drop,
+}
+
 // NB: TraitBinder is a manually implemented Term
 // that binds the `Self` variable.
 #[derive(Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
 pub struct TraitBinder<T: Term> {
     pub explicit_binder: Binder<T>,
 }
 
+// NB: ItemBinder is a manually implemented Term that
+// accepts `const N: ty` generics, desugaring the type into
+// a `type_of_const` where-clause. It derefs to the underlying binder.
+#[derive(Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
+pub struct ItemBinder<T: Term> {
+    pub binder: Binder<T>,
+}
+
 impl<T: Term> TraitBinder<T> {
     pub fn instantiate_with(&self, parameters: &[impl Upcast<Parameter>]) -> Fallible<T> {
         self.explicit_binder.instantiate_with(parameters)
@@ -196,7 +256,7 @@ pub enum TraitItem {
 #[term(fn $id $binder)]
 pub struct Fn {
     pub id: FnId,
-    pub binder: Binder<FnBoundData>,
+    pub binder: ItemBinder<FnBoundData>,
 }
 
 #[term($(input_tys) -> $output_ty $:where $,where_clauses $body)]
@@ -243,7 +303,7 @@ pub struct AssociatedTyBoundData {
 
 #[term(impl $binder)]
 pub struct TraitImpl {
-    pub binder: Binder<TraitImplBoundData>,
+    pub binder: ItemBinder<TraitImplBoundData>,
 }
 
 impl TraitImpl {
@@ -252,11 +312,16 @@ impl TraitImpl {
     }
 }
 
-#[term($trait_id $<?trait_parameters> for $self_ty $:where $,where_clauses { $*impl_items })]
+#[term($trait_id $<?trait_parameters> for $self_ty $:may_dangle $(may_dangle) $:where $,where_clauses { $*impl_items })]
 pub struct TraitImplBoundData {
     pub trait_id: TraitId,
     pub self_ty: Ty,
     pub trait_parameters: Vec<Parameter>,
+
+    /// Generic parameters that the destructor promises not to access,
+    /// modeling `#[may_dangle]`. Only permitted on impls of the `Drop` lang item.
+    pub may_dangle: Vec<Parameter>,
+
     pub where_clauses: Vec<WhereClause>,
     pub impl_items: Vec<ImplItem>,
 }
@@ -269,7 +334,7 @@ impl TraitImplBoundData {
 
 #[term(impl $binder)]
 pub struct NegTraitImpl {
-    pub binder: Binder<NegTraitImplBoundData>,
+    pub binder: ItemBinder<NegTraitImplBoundData>,
 }
 
 #[term(!$trait_id $<?trait_parameters> for $self_ty $:where $,where_clauses { })]
@@ -306,6 +371,27 @@ pub struct AssociatedTyValueBoundData {
     pub ty: Ty,
 }
 
+macro_rules! has_where_clauses {
+    ($($t:ty,)*) => {
+        $(
+            impl HasWhereClauses for $t {
+                fn where_clauses_mut(&mut self) -> &mut Vec<WhereClause> {
+                    &mut self.where_clauses
+                }
+            }
+        )*
+    }
+}
+
+has_where_clauses! {
+    StructBoundData,
+    AdtBoundData,
+    TraitBoundData,
+    FnBoundData,
+    TraitImplBoundData,
+    NegTraitImplBoundData,
+}
+
 #[term($data)]
 pub struct WhereClause {
     pub data: Arc<WhereClauseData>,
@@ -366,6 +452,7 @@ impl WhereBound {
 }
 
 #[term]
+#[customize(parse)]
 pub enum WhereBoundData {
     #[grammar($v0 $<?v1>)]
     IsImplemented(TraitId, Vec<Parameter>),
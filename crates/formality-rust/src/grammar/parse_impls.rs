@@ -0,0 +1,67 @@
+//! Handwritten parser impls.
+
+use formality_core::parse::{
+    CoreParse, Grammar, ParseError, ParseResult, Parser, Precedence, Production, Scope, Symbol,
+};
+use formality_types::grammar::{Binder, Lt, Parameter, TraitId};
+
+use super::{WhereBound, WhereBoundData};
+
+use crate::FormalityLang as Rust;
+
+// Like the derived parser for `#[grammar($v0 $<?v1>)]`, `#[grammar($v0)]` and
+// `#[grammar(for $v0)]`, except that a trait named `for` is not accepted where
+// the text is also a `for` bound (e.g., `for<> 'a` or `for static`).
+impl CoreParse<Rust> for WhereBoundData {
+    fn parse<'t>(scope: &Scope<Rust>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::multi_variant(scope, text, "WhereBoundData", |parser| {
+            parser.parse_variant(
+                "WhereBoundData::IsImplemented",
+                Precedence::default(),
+                |p| {
+                    p.reject(
+                        |p| {
+                            p.expect_keyword("for")?;
+                            p.nonterminal::<Binder<WhereBound>>()
+                        },
+                        |bound| ParseError::at(p.text(), format!("unexpected `for {bound:?}`")),
+                    )?;
+                    let trait_id: TraitId = p.nonterminal()?;
+                    let parameters: Vec<Parameter> = p.delimited_nonterminal('<', true, '>')?;
+                    Ok(WhereBoundData::IsImplemented(trait_id, parameters))
+                },
+            );
+
+            parser.parse_variant("WhereBoundData::Outlives", Precedence::default(), |p| {
+                let lt: Lt = p.nonterminal()?;
+                Ok(WhereBoundData::Outlives(lt))
+            });
+
+            parser.parse_variant("WhereBoundData::ForAll", Precedence::default(), |p| {
+                p.expect_keyword("for")?;
+                let bound: Binder<WhereBound> = p.nonterminal()?;
+                Ok(WhereBoundData::ForAll(bound))
+            });
+        })
+    }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.nonterminal::<Self>("WhereBoundData", |g| {
+            let production = |name, symbols| Production::new(name, Precedence::default(), symbols);
+            vec![
+                production(
+                    "IsImplemented, other than a \"for\" bound",
+                    vec![
+                        TraitId::grammar(g),
+                        Symbol::optional(Symbol::delimited('<', Parameter::grammar(g), '>')),
+                    ],
+                ),
+                production("Outlives", vec![Lt::grammar(g)]),
+                production(
+                    "ForAll",
+                    vec![Symbol::Keyword("for"), Binder::<WhereBound>::grammar(g)],
+                ),
+            ]
+        })
+    }
+}
@@ -0,0 +1,252 @@
+use std::{fmt::Debug, ops::Deref, sync::Arc};
+
+use formality_core::{
+    fold::CoreFold,
+    generate::{CoreGenerate, Generator},
+    parse::{
+        ActiveVariant, Binding, CoreParse, Grammar, ParseError, ParseResult, Parser, Precedence,
+        Production, Scope, Symbol,
+    },
+    print::{CorePrint, Printer},
+    term::CoreTerm,
+    visit::CoreVisit,
+    DowncastTo, Set, Upcast, UpcastFrom,
+};
+use formality_types::{
+    grammar::{Binder, BoundVar, Const, ConstData, ParameterKind, Ty, VarName},
+    rust::Term,
+};
+
+use crate::{
+    grammar::{ItemBinder, WhereClause, WhereClauseData},
+    FormalityLang,
+};
+
+/// Bound data of an item whose generics may be written as `const N: ty`.
+/// The annotation is desugared into a `type_of_const N is ty` where-clause.
+pub trait HasWhereClauses {
+    fn where_clauses_mut(&mut self) -> &mut Vec<WhereClause>;
+}
+
+/// A generic parameter as written on an item: `ty T`, `lt a`, `const N`, or `const N: ty`.
+/// The kind may also be left implicit, Rust-style: `'a` is a lifetime and a plain `T` is a type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ItemBinding {
+    pub binding: Binding<FormalityLang>,
+    pub ty: Option<Ty>,
+}
+
+impl ItemBinding {
+    /// The where-clause implied by the binding, if any.
+    pub fn where_clause(&self) -> Option<WhereClause> {
+        let ty = self.ty.as_ref()?;
+        let ct = Const::new(ConstData::Variable(self.binding.bound_var.upcast()));
+        Some(WhereClause {
+            data: Arc::new(WhereClauseData::TypeOfConst(ct, ty.clone())),
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for ItemBinding {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "ItemBinding", |p| {
+            let binding: Binding<FormalityLang> = match p.opt_nonterminal()? {
+                Some(binding) => binding,
+                None => {
+                    let name = p.identifier()?;
+                    let bound_var = BoundVar::fresh_named(ParameterKind::Ty, VarName::new(&name));
+                    Binding { name, bound_var }
+                }
+            };
+            let ty = match binding.bound_var.kind {
+                ParameterKind::Const if p.expect_char(':').is_ok() => Some(p.nonterminal()?),
+                _ => None,
+            };
+            Ok(ItemBinding { binding, ty })
+        })
+    }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.nonterminal::<Self>("ItemBinding", |g| {
+            vec![
+                // The `: ty` is only accepted after a `const` binding.
+                Production::new(
+                    "Binding",
+                    Precedence::default(),
+                    vec![
+                        Binding::<FormalityLang>::grammar(g),
+                        Symbol::optional(Symbol::Sequence(vec![Symbol::Char(':'), Ty::grammar(g)])),
+                    ],
+                ),
+                Production::new("Ty", Precedence::default(), vec![Symbol::Identifier]),
+            ]
+        })
+    }
+}
+
+impl<T> ItemBinder<T>
+where
+    T: Term,
+{
+    pub fn new(binder: Binder<T>) -> Self {
+        ItemBinder { binder }
+    }
+}
+
+impl<T> Deref for ItemBinder<T>
+where
+    T: Term,
+{
+    type Target = Binder<T>;
+
+    fn deref(&self) -> &Binder<T> {
+        &self.binder
+    }
+}
+
+impl<T> CoreTerm<FormalityLang> for ItemBinder<T> where T: Term + HasWhereClauses {}
+
+impl<T> CoreGenerate<FormalityLang> for ItemBinder<T>
+where
+    T: Term,
+{
+    fn generate(g: &mut Generator<FormalityLang>) -> Option<Self> {
+        Some(ItemBinder::new(g.generate()?))
+    }
+}
+
+impl<T> DowncastTo<ItemBinder<T>> for ItemBinder<T>
+where
+    T: Term,
+{
+    fn downcast_to(&self) -> Option<ItemBinder<T>> {
+        Some(self.clone())
+    }
+}
+
+impl<T> UpcastFrom<ItemBinder<T>> for ItemBinder<T>
+where
+    T: Term,
+{
+    fn upcast_from(term: ItemBinder<T>) -> Self {
+        term
+    }
+}
+
+impl<T> CoreVisit<FormalityLang> for ItemBinder<T>
+where
+    T: Term,
+{
+    fn free_variables(&self) -> Vec<formality_types::grammar::Variable> {
+        self.binder.free_variables()
+    }
+
+    fn size(&self) -> usize {
+        self.binder.size()
+    }
+
+    fn assert_valid(&self) {
+        self.binder.assert_valid()
+    }
+}
+
+impl<T> CoreFold<FormalityLang> for ItemBinder<T>
+where
+    T: Term,
+{
+    fn substitute(
+        &self,
+        substitution_fn: formality_core::fold::SubstitutionFn<'_, FormalityLang>,
+    ) -> Self {
+        ItemBinder {
+            binder: self.binder.substitute(substitution_fn),
+        }
+    }
+}
+
+impl<T> Debug for ItemBinder<T>
+where
+    T: Term,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The `const N: ty` annotations show up as where-clauses.
+        write!(f, "{:?}", self.binder)
+    }
+}
+
+impl<T> CorePrint<FormalityLang> for ItemBinder<T>
+where
+    T: Term,
+{
+    fn print(&self, p: &mut Printer<FormalityLang>) {
+        // The `const N: ty` annotations were desugared into where-clauses,
+        // which are printed (and parsed back) as such.
+        let kinds = self.binder.kinds();
+        let names: Vec<String> = kinds.iter().map(|kind| p.fresh_name(kind)).collect();
+        if !kinds.is_empty() {
+            p.bindings(kinds, &names);
+        }
+        p.with_bound_names(names, |p| p.print(self.binder.peek()));
+    }
+}
+
+impl<T> CoreParse<FormalityLang> for ItemBinder<T>
+where
+    T: Term + HasWhereClauses,
+{
+    #[tracing::instrument(level = "trace", ret)]
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "ItemBinder", |p| {
+            let bindings: Vec<ItemBinding> = match p.expect_char('<') {
+                Ok(()) => {
+                    let bindings = p.comma_nonterminal()?;
+                    p.expect_char('>')?;
+                    bindings
+                }
+                Err(_) => vec![],
+            };
+
+            let (bound_vars, data) = parse_item_bound_data(p, scope, &bindings)?;
+            Ok(ItemBinder::new(Binder::new(bound_vars, data)))
+        })
+    }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        item_binder_grammar::<T>(g)
+    }
+}
+
+/// The grammar of an item binder, `<ItemBinding, ...> T`, where the bindings are optional.
+pub(crate) fn item_binder_grammar<T>(g: &mut Grammar) -> Symbol
+where
+    T: Term,
+{
+    Symbol::Sequence(vec![
+        Symbol::optional(Symbol::delimited('<', ItemBinding::grammar(g), '>')),
+        T::grammar(g),
+    ])
+}
+
+/// Parses the data bound by `bindings`, with the bound names in scope, and adds
+/// the where-clauses implied by any `const N: ty` annotations.
+pub(crate) fn parse_item_bound_data<'t, T>(
+    p: &mut ActiveVariant<'_, 't, FormalityLang>,
+    scope: &Scope<FormalityLang>,
+    bindings: &[ItemBinding],
+) -> Result<(Vec<BoundVar>, T), Set<ParseError<'t>>>
+where
+    T: Term + HasWhereClauses,
+{
+    let scope1 = scope.with_bindings(
+        bindings
+            .iter()
+            .map(|b| (&b.binding.name, &b.binding.bound_var)),
+    );
+    let mut data: T = p.with_scope(scope1, |p| p.nonterminal())?;
+
+    let implied: Vec<WhereClause> = bindings.iter().filter_map(|b| b.where_clause()).collect();
+    data.where_clauses_mut().splice(0..0, implied);
+
+    let bound_vars: Vec<BoundVar> = bindings.iter().map(|b| b.binding.bound_var).collect();
+    Ok((bound_vars, data))
+}
@@ -4,6 +4,9 @@ use formality_types::rust::FormalityLang;
 // ANCHOR_END: use_rust_language
 
 pub mod grammar;
+mod item_binder;
 pub mod prove;
 mod test;
 mod trait_binder;
+
+pub use item_binder::HasWhereClauses;
@@ -1,28 +1,31 @@
 use crate::grammar::{
     Adt, AdtBoundData, AssociatedTy, AssociatedTyBoundData, AssociatedTyValue,
-    AssociatedTyValueBoundData, Crate, CrateItem, ImplItem, NegTraitImpl, NegTraitImplBoundData,
-    Program, Trait, TraitBoundData, TraitImpl, TraitImplBoundData, TraitItem, WhereBound,
-    WhereBoundData, WhereClause, WhereClauseData,
+    AssociatedTyValueBoundData, Crate, CrateItem, Field, ImplItem, LangItem, NegTraitImpl,
+    NegTraitImplBoundData, Program, Trait, TraitBoundData, TraitImpl, TraitImplBoundData,
+    TraitItem, Variant, WhereBound, WhereBoundData, WhereClause, WhereClauseData,
 };
 use formality_core::{seq, Set, To, Upcast, Upcasted};
 use formality_prove as prove;
 use formality_types::grammar::{
-    AdtId, AliasTy, Binder, BoundVar, ParameterKind, Predicate, Relation, TraitId, Ty, Wc, Wcs, PR,
+    AdtId, AliasTy, Binder, BoundVar, ParameterKind, Predicate, Relation, RigidName, RigidTy,
+    TraitId, Ty, TyData, Wc, Wcs, PR,
 };
 
 impl Program {
     pub fn to_prove_decls(&self) -> prove::Decls {
-        formality_prove::Decls {
+        formality_prove::Decls::new(formality_prove::DeclsData {
             max_size: formality_prove::Decls::DEFAULT_MAX_SIZE,
+            max_depth: formality_prove::Decls::DEFAULT_MAX_DEPTH,
             trait_decls: self.trait_decls(),
             impl_decls: self.impl_decls(),
             neg_impl_decls: self.neg_impl_decls(),
             alias_eq_decls: self.alias_eq_decls(),
             alias_bound_decls: self.alias_bound_decls(),
             adt_decls: self.adt_decls(),
+            drop_decls: self.drop_decls(),
             local_trait_ids: self.local_trait_ids(),
             local_adt_ids: self.local_adt_ids(),
-        }
+        })
     }
 
     fn trait_decls(&self) -> Vec<prove::TraitDecl> {
@@ -58,6 +61,13 @@ impl Program {
         self.crates.iter().flat_map(|c| c.adt_decls()).collect()
     }
 
+    fn drop_decls(&self) -> Vec<prove::DropDecl> {
+        self.crates
+            .iter()
+            .flat_map(|c| c.drop_decls(self))
+            .collect()
+    }
+
     fn local_trait_ids(&self) -> Set<TraitId> {
         self.crates
             .last()
@@ -80,7 +90,11 @@ impl Crate {
         self.items
             .iter()
             .flat_map(|item| match item {
-                CrateItem::Trait(Trait { id, binder }) => {
+                CrateItem::Trait(Trait {
+                    attributes: _,
+                    id,
+                    binder,
+                }) => {
                     let (
                         vars,
                         TraitBoundData {
@@ -117,6 +131,7 @@ impl Crate {
                             trait_id,
                             self_ty,
                             trait_parameters,
+                            may_dangle: _,
                             where_clauses,
                             impl_items: _,
                         },
@@ -176,6 +191,7 @@ impl Crate {
                             trait_id,
                             self_ty,
                             trait_parameters,
+                            may_dangle: _,
                             where_clauses: impl_wc,
                             impl_items,
                         },
@@ -226,6 +242,7 @@ impl Crate {
             .iter()
             .flat_map(|item| match item {
                 CrateItem::Trait(Trait {
+                    attributes: _,
                     id: trait_id,
                     binder,
                 }) => {
@@ -296,7 +313,7 @@ impl Crate {
                     vars,
                     AdtBoundData {
                         where_clauses,
-                        variants: _,
+                        variants,
                     },
                 ) = binder.open();
                 prove::AdtDecl {
@@ -305,6 +322,16 @@ impl Crate {
                         vars,
                         prove::AdtDeclBoundData {
                             where_clause: where_clauses.iter().flat_map(|wc| wc.to_wcs()).collect(),
+                            variants: variants
+                                .into_iter()
+                                .map(|Variant { name, fields }| prove::AdtDeclVariant {
+                                    name,
+                                    fields: fields
+                                        .into_iter()
+                                        .map(|Field { name, ty }| prove::AdtDeclField { name, ty })
+                                        .collect(),
+                                })
+                                .collect(),
                         },
                     ),
                 }
@@ -312,6 +339,45 @@ impl Crate {
             .collect()
     }
 
+    /// Each impl of the `Drop` lang item whose self type is an ADT becomes a [`prove::DropDecl`][].
+    /// The impl generics are renamed to the ADT parameters, so that the binder can be instantiated
+    /// with the parameters of the ADT type being dropped; ill-formed drop impls are rejected by the
+    /// checker and skipped here.
+    fn drop_decls(&self, program: &Program) -> Vec<prove::DropDecl> {
+        self.items
+            .iter()
+            .flat_map(|item| match item {
+                CrateItem::TraitImpl(TraitImpl { binder })
+                    if program.is_lang_item_trait(&binder.peek().trait_id, LangItem::Drop) =>
+                {
+                    let (vars, data) = binder.open();
+                    let TyData::RigidTy(RigidTy {
+                        name: RigidName::AdtId(adt_id),
+                        parameters,
+                    }) = data.self_ty.data()
+                    else {
+                        return None;
+                    };
+                    if parameters.len() != vars.len()
+                        || parameters.iter().zip(&vars).any(|(p, v)| *p != v.upcast())
+                    {
+                        return None;
+                    }
+                    Some(prove::DropDecl {
+                        adt_id: adt_id.clone(),
+                        binder: Binder::new(
+                            vars,
+                            prove::DropDeclBoundData {
+                                may_dangle: data.may_dangle,
+                            },
+                        ),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     fn adt_ids(&self) -> Set<AdtId> {
         self.items
             .iter()
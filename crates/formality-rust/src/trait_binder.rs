@@ -2,17 +2,23 @@ use std::fmt::Debug;
 
 use formality_core::{
     fold::CoreFold,
-    parse::{Binding, CoreParse, ParseResult, Parser, Scope},
+    generate::{CoreGenerate, Generator},
+    parse::{Binding, CoreParse, Grammar, ParseResult, Parser, Scope, Symbol},
+    print::{CorePrint, Printer},
     term::CoreTerm,
     visit::CoreVisit,
     DowncastTo, UpcastFrom,
 };
 use formality_types::{
-    grammar::{Binder, BoundVar, ParameterKind},
+    grammar::{Binder, BoundVar, ParameterKind, VarName},
     rust::Term,
 };
 
-use crate::{grammar::TraitBinder, FormalityLang};
+use crate::{
+    grammar::TraitBinder,
+    item_binder::{item_binder_grammar, parse_item_bound_data, HasWhereClauses, ItemBinding},
+    FormalityLang,
+};
 
 impl<T> TraitBinder<T>
 where
@@ -23,7 +29,21 @@ where
     }
 }
 
-impl<T> CoreTerm<FormalityLang> for TraitBinder<T> where T: Term {}
+impl<T> CoreTerm<FormalityLang> for TraitBinder<T> where T: Term + HasWhereClauses {}
+
+impl<T> CoreGenerate<FormalityLang> for TraitBinder<T>
+where
+    T: Term,
+{
+    fn generate(g: &mut Generator<FormalityLang>) -> Option<Self> {
+        // The first bound variable is the implicit `Self`.
+        let kinds = std::iter::once(ParameterKind::Ty)
+            .chain(g.kinds())
+            .collect();
+        let explicit_binder = g.binder(kinds, T::generate)?;
+        Some(TraitBinder { explicit_binder })
+    }
+}
 
 impl<T> DowncastTo<TraitBinder<T>> for TraitBinder<T>
 where
@@ -84,16 +104,33 @@ where
     }
 }
 
-impl<T> CoreParse<FormalityLang> for TraitBinder<T>
+impl<T> CorePrint<FormalityLang> for TraitBinder<T>
 where
     T: Term,
+{
+    fn print(&self, p: &mut Printer<FormalityLang>) {
+        // The first bound variable is the implicit `Self`.
+        let kinds = self.explicit_binder.kinds();
+        let names: Vec<String> = std::iter::once("Self".to_string())
+            .chain(kinds[1..].iter().map(|kind| p.fresh_name(kind)))
+            .collect();
+        if kinds.len() > 1 {
+            p.bindings(&kinds[1..], &names[1..]);
+        }
+        p.with_bound_names(names, |p| p.print(self.explicit_binder.peek()));
+    }
+}
+
+impl<T> CoreParse<FormalityLang> for TraitBinder<T>
+where
+    T: Term + HasWhereClauses,
 {
     #[tracing::instrument(level = "trace", ret)]
     fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
         Parser::single_variant(scope, text, "TraitBinder", |p| {
             let mut bindings = match p.expect_char('<') {
                 Ok(()) => {
-                    let bindings: Vec<Binding<FormalityLang>> = p.comma_nonterminal()?;
+                    let bindings: Vec<ItemBinding> = p.comma_nonterminal()?;
                     p.expect_char('>')?;
                     bindings
                 }
@@ -104,23 +141,27 @@ where
             };
 
             // insert the `Self` binding at position 0
-            let bound_var = BoundVar::fresh(ParameterKind::Ty);
+            let bound_var = BoundVar::fresh_named(ParameterKind::Ty, VarName::new("Self"));
             bindings.insert(
                 0,
-                Binding {
-                    name: "Self".to_string(),
-                    bound_var,
+                ItemBinding {
+                    binding: Binding {
+                        name: "Self".to_string(),
+                        bound_var,
+                    },
+                    ty: None,
                 },
             );
 
             // parse the contents with those names in scope
-            let scope1 = scope.with_bindings(bindings.iter().map(|b| (&b.name, &b.bound_var)));
-            let data: T = p.with_scope(scope1, |p| p.nonterminal())?;
-
-            let bound_vars: Vec<BoundVar> = bindings.iter().map(|b| b.bound_var).collect();
+            let (bound_vars, data) = parse_item_bound_data(p, scope, &bindings)?;
             let explicit_binder = Binder::new(bound_vars, data);
 
             Ok(TraitBinder { explicit_binder })
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        item_binder_grammar::<T>(g)
+    }
 }
@@ -16,6 +16,11 @@ use crate::{
 use super::{BoundVar, DebruijnIndex, Fallible, Parameter, ParameterKind, Substitution, Variable};
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
 pub struct Binder<T> {
     kinds: Vec<ParameterKind>,
     term: T,
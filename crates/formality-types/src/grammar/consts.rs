@@ -1,14 +1,13 @@
 mod valtree;
 
 use super::{Parameter, Ty, Variable};
-use formality_core::{term, DowncastTo, Upcast, UpcastFrom};
-use std::sync::Arc;
+use formality_core::{internable, term, DowncastTo, Interned, Upcast, UpcastFrom};
 pub use valtree::*;
 
 #[term]
 #[cast]
 pub struct Const {
-    data: Arc<ConstData>,
+    data: Interned<ConstData>,
 }
 impl Const {
     pub fn data(&self) -> &ConstData {
@@ -17,7 +16,7 @@ impl Const {
 
     pub fn new(data: impl Upcast<ConstData>) -> Self {
         Self {
-            data: Arc::new(data.upcast()),
+            data: Interned::new(data.upcast()),
         }
     }
 
@@ -49,6 +48,8 @@ pub enum ConstData {
     Variable(Variable),
 }
 
+internable!(ConstData);
+
 impl DowncastTo<ConstData> for Const {
     fn downcast_to(&self) -> Option<ConstData> {
         Some(self.data().clone())
@@ -1,3 +1,5 @@
+use formality_core::generate::{CoreGenerate, Generator};
+use formality_core::print::{CorePrint, Printer};
 use formality_core::Visit;
 
 use formality_core::{Upcast, UpcastFrom};
@@ -5,6 +7,11 @@ use formality_core::{Upcast, UpcastFrom};
 use super::Bool;
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
 pub enum ValTree {
     Leaf(Scalar),
     Branches(Vec<ValTree>),
@@ -19,20 +26,92 @@ impl std::fmt::Debug for ValTree {
     }
 }
 
+impl CorePrint<crate::FormalityLang> for ValTree {
+    fn print(&self, p: &mut Printer<crate::FormalityLang>) {
+        match self {
+            Self::Leaf(s) => p.print(s),
+            Self::Branches(branches) => p.delimited("[", branches, "]"),
+        }
+    }
+}
+
+impl CoreGenerate<crate::FormalityLang> for ValTree {
+    fn generate(g: &mut Generator<crate::FormalityLang>) -> Option<Self> {
+        g.one_of(&[
+            &|g| {
+                // Small values of either sign.
+                let n = g.below(200) as i128 - 100;
+                Some(Self::Leaf(Scalar::from_i128(n)))
+            },
+            &|g| {
+                if !g.spend() {
+                    return None;
+                }
+                Some(Self::Branches(g.generate()?))
+            },
+        ])
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(formality_core::serde::Serialize, formality_core::serde::Deserialize),
+    serde(crate = "formality_core::serde")
+)]
 pub struct Scalar {
     bits: u128,
+    negative: bool,
 }
 
 impl std::fmt::Debug for Scalar {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.bits.fmt(f)
+        if self.negative {
+            (self.bits as i128).fmt(f)
+        } else {
+            self.bits.fmt(f)
+        }
+    }
+}
+
+impl CorePrint<crate::FormalityLang> for Scalar {
+    fn print(&self, p: &mut Printer<crate::FormalityLang>) {
+        p.token(&format!("{self:?}"))
     }
 }
 
+/// Negative values are stored sign-extended to 128 bits, so `-1_i8` has the same
+/// bits as `-1_i64`. We also record whether the value is negative, as the bits alone
+/// cannot distinguish `-1_i128` from `u128::MAX`. Whether the value is in range is
+/// decided by the type (see `ScalarId::contains`).
 impl Scalar {
+    /// The non-negative value `bits`.
     pub fn new(bits: u128) -> Self {
-        Self { bits }
+        Self {
+            bits,
+            negative: false,
+        }
+    }
+
+    pub fn from_i128(value: i128) -> Self {
+        Self {
+            bits: value as u128,
+            negative: value < 0,
+        }
+    }
+
+    pub fn bits(&self) -> u128 {
+        self.bits
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The value as an `i128`, if it fits.
+    pub fn as_i128(&self) -> Option<i128> {
+        let value = self.bits as i128;
+        ((value < 0) == self.negative).then_some(value)
     }
 }
 
@@ -56,6 +135,10 @@ impl UpcastFrom<Self> for ValTree {
 
 impl UpcastFrom<Bool> for Scalar {
     fn upcast_from(term: Bool) -> Self {
-        Scalar { bits: term as u128 }
+        let bits = match term {
+            Bool::True => 1,
+            Bool::False => 0,
+        };
+        Scalar::new(bits)
     }
 }
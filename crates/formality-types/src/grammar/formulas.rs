@@ -7,6 +7,7 @@ use formality_core::Upcast;
 use super::AliasName;
 use super::AliasTy;
 use super::Const;
+use super::Lt;
 use super::Parameter;
 use super::Parameters;
 use super::TraitId;
@@ -94,6 +95,9 @@ pub enum Skeleton {
     Equals,
     Sub,
     Outlives,
+    Sized,
+    NeedsDrop,
+    DropckOutlives,
 }
 
 impl Predicate {
@@ -168,6 +172,19 @@ pub enum Relation {
 
     #[grammar(@wf($v0))]
     WellFormed(Parameter),
+
+    /// True if the size of the given type is known at compile time.
+    #[grammar(@sized($v0))]
+    Sized(Ty),
+
+    /// True if dropping a value of the given type may run a destructor.
+    #[grammar(@needs_drop($v0))]
+    NeedsDrop(Ty),
+
+    /// True if a value of the given type can be dropped at a point where the lifetime
+    /// is still live, i.e., everything that the destructors may access outlives it.
+    #[grammar(@dropck_outlives($v0, $v1))]
+    DropckOutlives(Ty, Lt),
 }
 
 impl Relation {
@@ -183,6 +200,18 @@ impl Relation {
         Self::Sub(p1.upcast(), p2.upcast())
     }
 
+    pub fn sized(ty: impl Upcast<Ty>) -> Self {
+        Self::Sized(ty.upcast())
+    }
+
+    pub fn needs_drop(ty: impl Upcast<Ty>) -> Self {
+        Self::NeedsDrop(ty.upcast())
+    }
+
+    pub fn dropck_outlives(ty: impl Upcast<Ty>, lt: impl Upcast<Lt>) -> Self {
+        Self::DropckOutlives(ty.upcast(), lt.upcast())
+    }
+
     #[tracing::instrument(level = "trace", ret)]
     pub fn debone(&self) -> (Skeleton, Vec<Parameter>) {
         match self {
@@ -190,6 +219,12 @@ impl Relation {
             Relation::Sub(a, b) => (Skeleton::Sub, vec![a.clone(), b.clone()]),
             Relation::Outlives(a, b) => (Skeleton::Outlives, vec![a.clone(), b.clone()]),
             Relation::WellFormed(p) => (Skeleton::WellFormed, vec![p.clone()]),
+            Relation::Sized(ty) => (Skeleton::Sized, vec![ty.clone().upcast()]),
+            Relation::NeedsDrop(ty) => (Skeleton::NeedsDrop, vec![ty.clone().upcast()]),
+            Relation::DropckOutlives(ty, lt) => (
+                Skeleton::DropckOutlives,
+                vec![ty.clone().upcast(), lt.clone().upcast()],
+            ),
         }
     }
 }
@@ -1,4 +1,4 @@
-use formality_core::id;
+use formality_core::{id, term};
 
 id!(FnId);
 id!(AdtId);
@@ -6,3 +6,19 @@ id!(TraitId);
 id!(AssociatedItemId);
 id!(CrateId);
 id!(FieldId);
+id!(VariantId);
+
+impl VariantId {
+    /// Returns the special variant-id used for the single variant of a struct.
+    pub fn for_struct() -> Self {
+        VariantId::new("struct")
+    }
+}
+
+#[term]
+pub enum FieldName {
+    #[cast]
+    Id(FieldId),
+    #[cast]
+    Index(usize),
+}
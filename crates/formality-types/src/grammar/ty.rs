@@ -1,26 +1,28 @@
-use formality_core::{cast_impl, term};
-use std::sync::Arc;
+use formality_core::{cast_impl, internable, term, Interned};
 
 mod debug_impls;
+mod generate_impls;
 mod parse_impls;
+mod print_impls;
 mod term_impls;
 use formality_core::{DowncastTo, To, Upcast, UpcastFrom};
 
 use super::{
-    consts::Const, AdtId, AssociatedItemId, Binder, BoundVar, ExistentialVar, FnId, TraitId,
-    UniversalVar, Variable,
+    consts::{Const, Scalar},
+    AdtId, AssociatedItemId, Binder, BoundVar, ExistentialVar, FnId, TraitId, UniversalVar,
+    Variable,
 };
 
 #[term]
 #[cast]
 pub struct Ty {
-    data: Arc<TyData>,
+    data: Interned<TyData>,
 }
 
 impl Ty {
     pub fn new(data: impl Upcast<TyData>) -> Self {
         Ty {
-            data: Arc::new(data.upcast()),
+            data: Interned::new(data.upcast()),
         }
     }
 
@@ -82,6 +84,14 @@ impl Ty {
         }
         .upcast()
     }
+
+    pub fn usize() -> Ty {
+        RigidTy {
+            name: RigidName::ScalarId(ScalarId::Usize),
+            parameters: vec![],
+        }
+        .upcast()
+    }
 }
 
 impl UpcastFrom<TyData> for Ty {
@@ -110,6 +120,8 @@ pub enum TyData {
     Variable(Variable),
 }
 
+internable!(TyData);
+
 impl UpcastFrom<Ty> for TyData {
     fn upcast_from(term: Ty) -> Self {
         term.data().clone()
@@ -118,7 +130,7 @@ impl UpcastFrom<Ty> for TyData {
 
 // ANCHOR: RigidTy_decl
 #[term((rigid $name $*parameters))]
-#[customize(parse, debug)]
+#[customize(parse, debug, print, generate)]
 pub struct RigidTy {
     pub name: RigidName,
     pub parameters: Parameters,
@@ -154,6 +166,19 @@ pub enum RigidName {
     Tuple(usize),
     FnPtr(usize),
     FnDef(FnId),
+    /// `[T; N]`, with parameters `T` and `N`.
+    #[grammar(array)]
+    Array,
+    /// `[T]`, with parameter `T`.
+    #[grammar(slice)]
+    Slice,
+    #[grammar(str)]
+    Str,
+    #[grammar(never)]
+    Never,
+    #[cast]
+    #[grammar(*($v0))]
+    RawPtr(RawPtrKind),
 }
 
 #[term]
@@ -162,6 +187,14 @@ pub enum RefKind {
     Mut,
 }
 
+#[term]
+pub enum RawPtrKind {
+    #[grammar(const)]
+    Const,
+    #[grammar(mut)]
+    Mut,
+}
+
 #[term]
 pub enum ScalarId {
     #[grammar(u8)]
@@ -172,6 +205,8 @@ pub enum ScalarId {
     U32,
     #[grammar(u64)]
     U64,
+    #[grammar(u128)]
+    U128,
     #[grammar(i8)]
     I8,
     #[grammar(i16)]
@@ -180,16 +215,55 @@ pub enum ScalarId {
     I32,
     #[grammar(i64)]
     I64,
+    #[grammar(i128)]
+    I128,
     #[grammar(bool)]
     Bool,
     #[grammar(usize)]
     Usize,
     #[grammar(isize)]
     Isize,
+    #[grammar(f32)]
+    F32,
+    #[grammar(f64)]
+    F64,
+    #[grammar(char)]
+    Char,
+}
+
+impl ScalarId {
+    /// True if `scalar` is a value of this type.
+    /// `usize` and `isize` are assumed to be 64 bits wide. Floats have no values,
+    /// as they are not permitted in const generics.
+    pub fn contains(&self, scalar: &Scalar) -> bool {
+        let in_range = |min: i128, max: i128| {
+            scalar
+                .as_i128()
+                .is_some_and(|value| (min..=max).contains(&value))
+        };
+        let bits = scalar.bits();
+        match self {
+            ScalarId::Bool => in_range(0, 1),
+            ScalarId::Char => {
+                !scalar.is_negative() && u32::try_from(bits).ok().and_then(char::from_u32).is_some()
+            }
+            ScalarId::U8 => in_range(0, u8::MAX as i128),
+            ScalarId::U16 => in_range(0, u16::MAX as i128),
+            ScalarId::U32 => in_range(0, u32::MAX as i128),
+            ScalarId::U64 | ScalarId::Usize => in_range(0, u64::MAX as i128),
+            ScalarId::U128 => !scalar.is_negative(),
+            ScalarId::F32 | ScalarId::F64 => false,
+            ScalarId::I8 => in_range(i8::MIN as i128, i8::MAX as i128),
+            ScalarId::I16 => in_range(i16::MIN as i128, i16::MAX as i128),
+            ScalarId::I32 => in_range(i32::MIN as i128, i32::MAX as i128),
+            ScalarId::I64 | ScalarId::Isize => in_range(i64::MIN as i128, i64::MAX as i128),
+            ScalarId::I128 => in_range(i128::MIN, i128::MAX),
+        }
+    }
 }
 
 #[term((alias $name $*parameters))]
-#[customize(parse, debug)]
+#[customize(parse, debug, print, generate)]
 pub struct AliasTy {
     pub name: AliasName,
     pub parameters: Parameters,
@@ -274,6 +348,7 @@ impl Parameter {
 pub type Parameters = Vec<Parameter>;
 
 #[term]
+#[customize(parse)]
 #[derive(Copy)]
 pub enum ParameterKind {
     Ty,
@@ -295,13 +370,13 @@ pub enum Variance {
 #[term]
 #[cast]
 pub struct Lt {
-    data: Arc<LtData>,
+    data: Interned<LtData>,
 }
 
 impl Lt {
     pub fn new(data: impl Upcast<LtData>) -> Self {
         Lt {
-            data: Arc::new(data.upcast()),
+            data: Interned::new(data.upcast()),
         }
     }
 
@@ -334,6 +409,7 @@ impl DowncastTo<LtData> for Lt {
 }
 
 #[term]
+#[customize(parse, print)]
 pub enum LtData {
     Static,
 
@@ -341,6 +417,8 @@ pub enum LtData {
     Variable(Variable),
 }
 
+internable!(LtData);
+
 impl UpcastFrom<Variable> for Parameter {
     fn upcast_from(v: Variable) -> Parameter {
         match v.kind() {
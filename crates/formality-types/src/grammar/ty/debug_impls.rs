@@ -1,4 +1,6 @@
-use super::{AliasName, AliasTy, AssociatedTyName, Parameter, RefKind, RigidName, RigidTy};
+use super::{
+    AliasName, AliasTy, AssociatedTyName, Parameter, RawPtrKind, RefKind, RigidName, RigidTy,
+};
 use std::fmt::Debug;
 
 // ANCHOR: RigidTy_impl
@@ -32,6 +34,26 @@ impl Debug for RigidTy {
                     write!(f, "()")
                 }
             }
+            // Print the length without the `const` prefix, as in `[u8; 4_usize]`.
+            RigidName::Array if parameters.len() == 2 => match &parameters[1] {
+                Parameter::Const(len) => write!(f, "[{:?}; {:?}]", parameters[0], len),
+                len => write!(f, "[{:?}; {:?}]", parameters[0], len),
+            },
+            RigidName::Slice if parameters.len() == 1 => {
+                write!(f, "[{:?}]", parameters[0])
+            }
+            RigidName::Str if parameters.is_empty() => {
+                write!(f, "str")
+            }
+            RigidName::Never if parameters.is_empty() => {
+                write!(f, "!")
+            }
+            RigidName::RawPtr(RawPtrKind::Const) if parameters.len() == 1 => {
+                write!(f, "*const {:?}", parameters[0])
+            }
+            RigidName::RawPtr(RawPtrKind::Mut) if parameters.len() == 1 => {
+                write!(f, "*mut {:?}", parameters[0])
+            }
             _ => {
                 write!(f, "{:?}{:?}", name, PrettyParameters::angle(parameters))
             }
@@ -0,0 +1,109 @@
+//! Handwritten generators, producing only the rigid and alias types that have a syntax.
+
+use formality_core::generate::{CoreGenerate, Generator};
+use formality_core::Upcast;
+
+use crate::grammar::{AdtId, AssociatedItemId, Const, TraitId};
+
+use super::{
+    AliasTy, AssociatedTyName, Lt, Parameter, RawPtrKind, RefKind, RigidName, RigidTy, ScalarId, Ty,
+};
+
+use crate::rust::FormalityLang as Rust;
+
+impl CoreGenerate<Rust> for RigidTy {
+    fn generate(g: &mut Generator<Rust>) -> Option<Self> {
+        g.one_of(&[
+            &|g| Some(g.generate::<ScalarId>()?.upcast()),
+            &|g| {
+                rigid(g, |g| {
+                    Some((g.generate::<AdtId>()?.upcast(), g.generate()?))
+                })
+            },
+            &|g| {
+                rigid(g, |g| {
+                    let kind: RefKind = g.generate()?;
+                    let lt: Lt = g.generate()?;
+                    let ty: Ty = g.generate()?;
+                    Some((kind.upcast(), vec![lt.upcast(), ty.upcast()]))
+                })
+            },
+            &|g| {
+                rigid(g, |g| {
+                    let tys: Vec<Ty> = g.generate()?;
+                    Some((RigidName::Tuple(tys.len()), tys.upcast()))
+                })
+            },
+            &|g| {
+                rigid(g, |g| {
+                    let ty: Ty = g.generate()?;
+                    let len: Const = g.generate()?;
+                    Some((RigidName::Array, vec![ty.upcast(), len.upcast()]))
+                })
+            },
+            &|g| {
+                rigid(g, |g| {
+                    let ty: Ty = g.generate()?;
+                    Some((RigidName::Slice, vec![ty.upcast()]))
+                })
+            },
+            &|_| {
+                Some(RigidTy {
+                    name: RigidName::Str,
+                    parameters: vec![],
+                })
+            },
+            &|_| {
+                Some(RigidTy {
+                    name: RigidName::Never,
+                    parameters: vec![],
+                })
+            },
+            &|g| {
+                rigid(g, |g| {
+                    let kind: RawPtrKind = g.generate()?;
+                    let ty: Ty = g.generate()?;
+                    Some((kind.upcast(), vec![ty.upcast()]))
+                })
+            },
+        ])
+    }
+}
+
+/// Spends one unit of the budget and builds a rigid type from the name and parameters that `op` generates.
+fn rigid(
+    g: &mut Generator<Rust>,
+    op: impl FnOnce(&mut Generator<Rust>) -> Option<(RigidName, Vec<Parameter>)>,
+) -> Option<RigidTy> {
+    if !g.spend() {
+        return None;
+    }
+    let (name, parameters) = op(g)?;
+    Some(RigidTy { name, parameters })
+}
+
+impl CoreGenerate<Rust> for AliasTy {
+    fn generate(g: &mut Generator<Rust>) -> Option<Self> {
+        if !g.spend() {
+            return None;
+        }
+        let trait_id: TraitId = g.generate()?;
+        let item_id: AssociatedItemId = g.generate()?;
+        let self_ty: Ty = g.generate()?;
+        let trait_parameters: Vec<Parameter> = g.generate()?;
+        let item_parameters: Vec<Parameter> = g.generate()?;
+        let name = AssociatedTyName {
+            trait_id,
+            item_id,
+            item_arity: item_parameters.len(),
+        };
+        let parameters = std::iter::once(self_ty.upcast())
+            .chain(trait_parameters)
+            .chain(item_parameters)
+            .collect();
+        Some(AliasTy {
+            name: name.upcast(),
+            parameters,
+        })
+    }
+}
@@ -1,16 +1,21 @@
 //! Handwritten parser impls.
 
 use formality_core::parse::{
-    ActiveVariant, CoreParse, ParseError, ParseResult, Parser, Precedence, Scope,
+    skip_whitespace, ActiveVariant, CoreParse, Grammar, ParseError, ParseResult, Parser,
+    Precedence, Production, Scope, Symbol,
 };
 use formality_core::Upcast;
 use formality_core::{seq, Set};
 
 use crate::grammar::{
-    AdtId, AssociatedItemId, Bool, ConstData, RefKind, RigidName, Scalar, TraitId,
+    AdtId, AssociatedItemId, Bool, Const, ConstData, RawPtrKind, RefKind, RigidName, Scalar,
+    TraitId, ValTree,
 };
 
-use super::{AliasTy, AssociatedTyName, Lt, Parameter, RigidTy, ScalarId, Ty};
+use super::{
+    AliasTy, AssociatedTyName, Lt, LtData, Parameter, ParameterKind, PredicateTy, RigidTy,
+    ScalarId, Ty,
+};
 
 use crate::rust::FormalityLang as Rust;
 
@@ -26,8 +31,11 @@ impl CoreParse<Rust> for RigidTy {
 
             // Parse something like `Id<...>` as an ADT.
             parser.parse_variant("Adt", Precedence::default(), |p| {
-                // Don't accept scalar-ids as Adt names.
+                // Don't accept scalar-ids or `str` as Adt names. An ADT may be named
+                // `for_all`, but `for_all(...)` is a `PredicateTy`.
                 p.reject_nonterminal::<ScalarId>()?;
+                p.reject_custom_keywords(&["str"])?;
+                p.reject_nonterminal::<PredicateTy>()?;
 
                 let name: AdtId = p.nonterminal()?;
                 let parameters: Vec<Parameter> = parse_parameters(p)?;
@@ -60,6 +68,66 @@ impl CoreParse<Rust> for RigidTy {
                 })
             });
 
+            // Rust puts the `mut` after the lifetime: `&'a mut T`.
+            parser.parse_variant("RefMut", Precedence::default(), |p| {
+                p.expect_char('&')?;
+                let lt: Lt = p.nonterminal()?;
+                p.expect_keyword("mut")?;
+                let ty: Ty = p.nonterminal()?;
+                Ok(RigidTy {
+                    name: RigidName::Ref(RefKind::Mut),
+                    parameters: seq![lt.upcast(), ty.upcast()],
+                })
+            });
+
+            parser.parse_variant("Array", Precedence::default(), |p| {
+                p.expect_char('[')?;
+                let ty: Ty = p.nonterminal()?;
+                p.expect_char(';')?;
+                let len: Const = p.nonterminal()?;
+                p.expect_char(']')?;
+                Ok(RigidTy {
+                    name: RigidName::Array,
+                    parameters: seq![ty.upcast(), len.upcast()],
+                })
+            });
+
+            parser.parse_variant("Slice", Precedence::default(), |p| {
+                p.expect_char('[')?;
+                let ty: Ty = p.nonterminal()?;
+                p.expect_char(']')?;
+                Ok(RigidTy {
+                    name: RigidName::Slice,
+                    parameters: seq![ty.upcast()],
+                })
+            });
+
+            parser.parse_variant("Str", Precedence::default(), |p| {
+                p.expect_keyword("str")?;
+                Ok(RigidTy {
+                    name: RigidName::Str,
+                    parameters: seq![],
+                })
+            });
+
+            parser.parse_variant("Never", Precedence::default(), |p| {
+                p.expect_char('!')?;
+                Ok(RigidTy {
+                    name: RigidName::Never,
+                    parameters: seq![],
+                })
+            });
+
+            parser.parse_variant("RawPtr", Precedence::default(), |p| {
+                p.expect_char('*')?;
+                let kind: RawPtrKind = p.nonterminal()?;
+                let ty: Ty = p.nonterminal()?;
+                Ok(RigidTy {
+                    name: RigidName::RawPtr(kind),
+                    parameters: seq![ty.upcast()],
+                })
+            });
+
             parser.parse_variant("Tuple", Precedence::default(), |p| {
                 p.expect_char('(')?;
                 p.reject_custom_keywords(&["alias", "rigid", "predicate"])?;
@@ -73,6 +141,62 @@ impl CoreParse<Rust> for RigidTy {
             });
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.nonterminal::<Self>("RigidTy", |g| {
+            let production = |name, symbols| Production::new(name, Precedence::default(), symbols);
+            vec![
+                production("ScalarId", vec![ScalarId::grammar(g)]),
+                production(
+                    "Adt, other than \"str\" or a PredicateTy",
+                    vec![AdtId::grammar(g), parameters_grammar(g)],
+                ),
+                production(
+                    "Ref",
+                    vec![Symbol::Char('&'), Lt::grammar(g), Ty::grammar(g)],
+                ),
+                production(
+                    "RefMut",
+                    vec![
+                        Symbol::Char('&'),
+                        Symbol::Keyword("mut"),
+                        Lt::grammar(g),
+                        Ty::grammar(g),
+                    ],
+                ),
+                production(
+                    "RefMut",
+                    vec![
+                        Symbol::Char('&'),
+                        Lt::grammar(g),
+                        Symbol::Keyword("mut"),
+                        Ty::grammar(g),
+                    ],
+                ),
+                production(
+                    "Array",
+                    vec![
+                        Symbol::Char('['),
+                        Ty::grammar(g),
+                        Symbol::Char(';'),
+                        Const::grammar(g),
+                        Symbol::Char(']'),
+                    ],
+                ),
+                production(
+                    "Slice",
+                    vec![Symbol::Char('['), Ty::grammar(g), Symbol::Char(']')],
+                ),
+                production("Str", vec![Symbol::Keyword("str")]),
+                production("Never", vec![Symbol::Char('!')]),
+                production(
+                    "RawPtr",
+                    vec![Symbol::Char('*'), RawPtrKind::grammar(g), Ty::grammar(g)],
+                ),
+                production("Tuple", vec![Symbol::delimited('(', Ty::grammar(g), ')')]),
+            ]
+        })
+    }
 }
 // ANCHOR_END: RigidTy_impl
 
@@ -106,6 +230,117 @@ impl CoreParse<Rust> for AliasTy {
             });
         })
     }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.nonterminal::<Self>("AliasTy", |g| {
+            vec![Production::new(
+                "associated type",
+                Precedence::default(),
+                vec![
+                    Symbol::Char('<'),
+                    Ty::grammar(g),
+                    Symbol::Keyword("as"),
+                    TraitId::grammar(g),
+                    parameters_grammar(g),
+                    Symbol::Char('>'),
+                    Symbol::Char(':'),
+                    Symbol::Char(':'),
+                    AssociatedItemId::grammar(g),
+                    parameters_grammar(g),
+                ],
+            )]
+        })
+    }
+}
+
+// Lifetimes may be written Rust-style, with a leading `'`: `'a` is the same as `a`
+// and `'static` the same as `static`.
+impl CoreParse<Rust> for LtData {
+    fn parse<'t>(scope: &Scope<Rust>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::multi_variant(scope, text, "LtData", |parser| {
+            parser.parse_variant("Static", Precedence::default(), |p| {
+                opt_lifetime_tick(p)?;
+                p.expect_keyword("static")?;
+                Ok(LtData::Static)
+            });
+
+            parser.parse_variant("Variable", Precedence::default(), |p| {
+                opt_lifetime_tick(p)?;
+                p.variable()
+            });
+        })
+    }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.nonterminal::<Self>("LtData", |_| {
+            let tick = Symbol::optional(Symbol::Char('\''));
+            vec![
+                Production::new(
+                    "Static",
+                    Precedence::default(),
+                    vec![tick.clone(), Symbol::Keyword("static")],
+                ),
+                Production::new(
+                    "Variable",
+                    Precedence::default(),
+                    vec![tick, Symbol::Variable],
+                ),
+            ]
+        })
+    }
+}
+
+// Besides the kind keywords, a `'` marks a lifetime binding, so that `'a` can be written for `lt a`.
+impl CoreParse<Rust> for ParameterKind {
+    fn parse<'t>(scope: &Scope<Rust>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::multi_variant(scope, text, "ParameterKind", |parser| {
+            parser.parse_variant("Ty", Precedence::default(), |p| {
+                p.expect_keyword("ty")?;
+                Ok(ParameterKind::Ty)
+            });
+
+            parser.parse_variant("Lt", Precedence::default(), |p| {
+                match opt_lifetime_tick(p)? {
+                    true => {}
+                    false => p.expect_keyword("lt")?,
+                }
+                Ok(ParameterKind::Lt)
+            });
+
+            parser.parse_variant("Const", Precedence::default(), |p| {
+                p.expect_keyword("const")?;
+                Ok(ParameterKind::Const)
+            });
+        })
+    }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.nonterminal::<Self>("ParameterKind", |_| {
+            let production =
+                |name, symbol| Production::new(name, Precedence::default(), vec![symbol]);
+            vec![
+                production("Ty", Symbol::Keyword("ty")),
+                production("Lt", Symbol::Char('\'')),
+                production("Lt", Symbol::Keyword("lt")),
+                production("Const", Symbol::Keyword("const")),
+            ]
+        })
+    }
+}
+
+/// Consumes the `'` at the start of a Rust-style lifetime name, if present, returning whether it was.
+/// The name must follow immediately, without whitespace.
+fn opt_lifetime_tick<'t>(p: &mut ActiveVariant<'_, 't, Rust>) -> Result<bool, Set<ParseError<'t>>> {
+    if p.expect_char('\'').is_err() {
+        return Ok(false);
+    }
+    match p.text().chars().next() {
+        Some(ch) if ch.is_ascii_alphabetic() || ch == '_' => Ok(true),
+        _ => Err(ParseError::at(
+            p.text(),
+            "expected a lifetime name after `'`".to_string(),
+        )),
+    }
 }
 
 fn parse_parameters<'t>(
@@ -119,6 +354,11 @@ fn parse_parameters<'t>(
     Ok(parameters)
 }
 
+/// The grammar of the parameters parsed by `parse_parameters`.
+fn parameters_grammar(g: &mut Grammar) -> Symbol {
+    Symbol::optional(Symbol::delimited('<', Parameter::grammar(g), '>'))
+}
+
 // For consts, we invest some effort into parsing them decently because it makes
 // writing tests so much more pleasant.
 impl CoreParse<Rust> for ConstData {
@@ -129,11 +369,100 @@ impl CoreParse<Rust> for ConstData {
             parser.parse_variant_cast::<Bool>(Precedence::default());
 
             parser.parse_variant("Int", Precedence::default(), |p| {
-                let n: u128 = p.number()?;
+                let n = signed_number(p)?;
                 p.expect_char('_')?;
                 let ty: Ty = p.nonterminal()?;
-                Ok(ConstData::Value(Scalar::new(n).upcast(), ty))
+                Ok(ConstData::Value(n.upcast(), ty))
             });
+
+            // Same syntax as the debug output, e.g. `value([1, -2], (u8, i8))`.
+            parser.parse_variant("Value", Precedence::default(), |p| {
+                p.expect_keyword("value")?;
+                p.expect_char('(')?;
+                let vt: ValTree = p.nonterminal()?;
+                p.expect_char(',')?;
+                let ty: Ty = p.nonterminal()?;
+                p.expect_char(')')?;
+                Ok(ConstData::Value(vt, ty))
+            });
+        })
+    }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.nonterminal::<Self>("ConstData", |g| {
+            let production = |name, symbols| Production::new(name, Precedence::default(), symbols);
+            vec![
+                production("Variable", vec![Symbol::Variable]),
+                production("Bool", vec![Bool::grammar(g)]),
+                production(
+                    "Int",
+                    vec![signed_number_grammar(), Symbol::Char('_'), Ty::grammar(g)],
+                ),
+                production(
+                    "Value",
+                    vec![
+                        Symbol::Keyword("value"),
+                        Symbol::Char('('),
+                        ValTree::grammar(g),
+                        Symbol::Char(','),
+                        Ty::grammar(g),
+                        Symbol::Char(')'),
+                    ],
+                ),
+            ]
+        })
+    }
+}
+
+impl CoreParse<Rust> for ValTree {
+    fn parse<'t>(scope: &Scope<Rust>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::multi_variant(scope, text, "ValTree", |parser| {
+            parser.parse_variant_cast::<Bool>(Precedence::default());
+
+            parser.parse_variant("Leaf", Precedence::default(), |p| {
+                Ok(ValTree::Leaf(signed_number(p)?))
+            });
+
+            parser.parse_variant("Branches", Precedence::default(), |p| {
+                Ok(ValTree::Branches(p.delimited_nonterminal('[', false, ']')?))
+            });
+        })
+    }
+
+    fn grammar(g: &mut Grammar) -> Symbol {
+        g.nonterminal::<Self>("ValTree", |g| {
+            let production = |name, symbols| Production::new(name, Precedence::default(), symbols);
+            vec![
+                production("Bool", vec![Bool::grammar(g)]),
+                production("Leaf", vec![signed_number_grammar()]),
+                production(
+                    "Branches",
+                    vec![Symbol::delimited('[', ValTree::grammar(g), ']')],
+                ),
+            ]
         })
     }
 }
+
+/// The grammar of the literals parsed by `signed_number`.
+fn signed_number_grammar() -> Symbol {
+    Symbol::Sequence(vec![Symbol::optional(Symbol::Char('-')), Symbol::Number])
+}
+
+/// An integer literal with an optional leading `-`.
+fn signed_number<'t>(p: &mut ActiveVariant<'_, 't, Rust>) -> Result<Scalar, Set<ParseError<'t>>> {
+    let negative = p.expect_char('-').is_ok();
+    let text0 = p.text();
+    let n: u128 = p.number()?;
+    if !negative {
+        return Ok(Scalar::new(n));
+    }
+    // The magnitude of `i128::MIN` does not fit in `i128`, so negate in two's complement.
+    if n > i128::MIN.unsigned_abs() {
+        return Err(ParseError::at(
+            skip_whitespace(text0),
+            "integer literal out of range".to_string(),
+        ));
+    }
+    Ok(Scalar::from_i128(n.wrapping_neg() as i128))
+}
@@ -0,0 +1,125 @@
+//! Handwritten printer impls, producing the syntax accepted by `parse_impls`.
+
+use formality_core::print::{CorePrint, Printer};
+
+use super::{
+    AliasName, AliasTy, AssociatedTyName, LtData, Parameter, RawPtrKind, RefKind, RigidName,
+    RigidTy,
+};
+
+use crate::rust::FormalityLang as Rust;
+
+// ANCHOR: RigidTy_impl
+impl CorePrint<Rust> for RigidTy {
+    fn print(&self, p: &mut Printer<Rust>) {
+        let RigidTy { name, parameters } = self;
+        match name {
+            RigidName::AdtId(name) => {
+                p.print(name);
+                print_parameters(p, parameters);
+            }
+            RigidName::ScalarId(s) if parameters.is_empty() => p.print(s),
+            RigidName::Ref(kind) if parameters.len() == 2 => {
+                p.token("&");
+                if let RefKind::Mut = kind {
+                    p.token("mut");
+                } else {
+                    p.no_space();
+                }
+                p.print(&parameters[0]);
+                p.print(&parameters[1]);
+            }
+            RigidName::Tuple(arity) if parameters.len() == *arity => {
+                p.delimited("(", parameters, ")");
+            }
+            RigidName::Array if parameters.len() == 2 => {
+                p.token("[");
+                p.no_space();
+                p.print(&parameters[0]);
+                p.no_space();
+                p.token(";");
+                // The length is written without the `const` prefix.
+                match &parameters[1] {
+                    Parameter::Const(len) => p.print(len),
+                    len => p.print(len),
+                }
+                p.no_space();
+                p.token("]");
+            }
+            RigidName::Slice if parameters.len() == 1 => {
+                p.token("[");
+                p.no_space();
+                p.print(&parameters[0]);
+                p.no_space();
+                p.token("]");
+            }
+            RigidName::Str if parameters.is_empty() => p.token("str"),
+            RigidName::Never if parameters.is_empty() => p.token("!"),
+            RigidName::RawPtr(kind) if parameters.len() == 1 => {
+                p.token("*");
+                p.no_space();
+                p.token(match kind {
+                    RawPtrKind::Const => "const",
+                    RawPtrKind::Mut => "mut",
+                });
+                p.print(&parameters[0]);
+            }
+            // There is no syntax for other rigid types (e.g. function types),
+            // so print them the same way as `Debug` does.
+            _ => {
+                p.print(name);
+                print_parameters(p, parameters);
+            }
+        }
+    }
+}
+// ANCHOR_END: RigidTy_impl
+
+impl CorePrint<Rust> for AliasTy {
+    fn print(&self, p: &mut Printer<Rust>) {
+        let AliasTy { name, parameters } = self;
+        match name {
+            AliasName::AssociatedTyId(AssociatedTyName {
+                trait_id,
+                item_id,
+                item_arity,
+            }) => {
+                let (trait_parameters, item_parameters) =
+                    parameters.split_at(parameters.len() - item_arity);
+                let (self_parameter, other_parameters) = trait_parameters.split_at(1);
+                p.token("<");
+                p.no_space();
+                p.print(&self_parameter[0]);
+                p.token("as");
+                p.print(trait_id);
+                print_parameters(p, other_parameters);
+                p.no_space();
+                p.token(">::");
+                p.no_space();
+                p.print(item_id);
+                print_parameters(p, item_parameters);
+            }
+        }
+    }
+}
+
+// Lifetimes are printed Rust-style, with a leading `'`, which tells them apart
+// from trait names in where-clauses like `T: 'a`.
+impl CorePrint<Rust> for LtData {
+    fn print(&self, p: &mut Printer<Rust>) {
+        p.token("'");
+        p.no_space();
+        match self {
+            LtData::Static => p.token("static"),
+            LtData::Variable(v) => p.print(v),
+        }
+    }
+}
+
+/// Prints `<P0, ..., Pn>` directly after the preceding name, or nothing if there are no parameters.
+fn print_parameters(p: &mut Printer<Rust>, parameters: &[Parameter]) {
+    if !parameters.is_empty() {
+        p.no_space();
+        p.delimited("<", parameters, ">");
+    }
+}
@@ -1,7 +1,6 @@
-use std::sync::Arc;
-
 use formality_core::{
-    cast_impl, set, term, DowncastFrom, DowncastTo, Set, SetExt, Upcast, UpcastFrom, Upcasted,
+    cast_impl, internable, set, term, DowncastFrom, DowncastTo, Interned, Set, SetExt, Upcast,
+    UpcastFrom, Upcasted,
 };
 
 use crate::grammar::PR;
@@ -106,7 +105,7 @@ impl DowncastTo<()> for Wcs {
 
 #[term($data)]
 pub struct Wc {
-    data: Arc<WcData>,
+    data: Interned<WcData>,
 }
 
 impl Wc {
@@ -131,11 +130,15 @@ pub enum WcData {
     Implies(Wcs, Wc),
 }
 
+internable!(WcData);
+
 // ---
 
 impl UpcastFrom<WcData> for Wc {
     fn upcast_from(v: WcData) -> Self {
-        Wc { data: Arc::new(v) }
+        Wc {
+            data: Interned::new(v),
+        }
     }
 }
 
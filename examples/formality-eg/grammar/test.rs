@@ -1,8 +1,8 @@
 use formality_core::test;
 
-use crate::eg::term;
+use crate::eg::{assert_fold_visit_agree, assert_round_trip, generate, term, Generate, Term};
 
-use super::{Expr, StructDecl, Ty};
+use super::{Expr, FnDecl, StructDecl, Ty};
 
 #[test]
 fn test_struct_decl() {
@@ -82,3 +82,28 @@ fn test_expression() {
     "#]]
     .assert_debug_eq(&r);
 }
+
+/// Generates `T`s from a range of seeds and checks that each one round-trips
+/// and that its `Fold` and `Visit` impls agree.
+fn fuzz<T: Term + Generate>() {
+    let mut count = 0;
+    for seed in 0..500 {
+        if let Some(term) = generate::<T>(seed, 20) {
+            assert!(!format!("{term:?}").is_empty());
+            assert_round_trip(&term);
+            assert_fold_visit_agree(&term);
+            count += 1;
+        }
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn fuzz_fn_decl() {
+    fuzz::<FnDecl>();
+}
+
+#[test]
+fn fuzz_struct_decl() {
+    fuzz::<StructDecl>();
+}
@@ -1,11 +1,11 @@
 use std::{path::PathBuf, sync::Arc};
 
 use clap::Parser;
-use formality_check::check_all_crates;
+use formality_check::{check_all_crates, check_all_crates_from_file};
 use formality_core::Set;
 use formality_prove::{test_util::TestAssertion, Constraints};
 use formality_rust::grammar::Program;
-use formality_types::rust::try_term;
+use formality_types::rust::{try_term, try_term_from_file};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -13,6 +13,10 @@ struct Args {
     #[arg(long)]
     print_rust: bool,
 
+    /// Print statistics about the judgment cache after checking.
+    #[arg(long)]
+    cache_stats: bool,
+
     #[arg(long, default_value = "")]
     error_format: String,
 
@@ -31,13 +35,19 @@ struct Args {
 pub fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     let input: String = std::fs::read_to_string(&args.input_path)?;
-    let program: Program = try_term(&input)?;
+    let (program, source_map): (Program, _) = try_term_from_file(&args.input_path, &input)?;
 
     if args.print_rust {
         eprintln!("{:#?}", program);
     }
 
-    check_all_crates(&program)
+    let result = check_all_crates_from_file(&program, &source_map);
+
+    if args.cache_stats {
+        eprintln!("cache: {}", formality_core::fixed_point::cache_stats());
+    }
+
+    result
 }
 
 pub fn test_program_ok(input: &str) -> anyhow::Result<()> {
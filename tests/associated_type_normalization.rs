@@ -21,26 +21,30 @@ fn test_mirror_normalizes_u32_to_u32() {
                 Constraints {
                     env: Env {
                         variables: [
-                            ?ty_1,
+                            ?T,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_1 => u32,
+                        ?T => u32,
                     },
+                    ambiguity: {},
                 },
                 Constraints {
                     env: Env {
                         variables: [
-                            ?ty_1,
+                            ?T,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_1 => <u32 as Mirror>::Assoc,
+                        ?T => <u32 as Mirror>::Assoc,
                     },
+                    ambiguity: {},
                 },
             },
         )
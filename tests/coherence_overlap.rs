@@ -53,7 +53,7 @@ fn test_overlap_normalize_alias_to_LocalType() {
 
     expect_test::expect![[r#"
         Err(
-            "impls may overlap:\nimpl <ty> LocalTrait for ^ty0_0 where ^ty0_0 : Iterator { }\nimpl LocalTrait for <LocalType as Mirror>::T { }",
+            "impls may overlap:\nimpl <ty T> LocalTrait for ^T where ^T : Iterator { }\nimpl LocalTrait for <LocalType as Mirror>::T { }",
         )
     "#]]
     .assert_debug_eq(&test_program_ok(&gen_program(
@@ -114,7 +114,7 @@ fn test_overlap_alias_not_normalizable() {
 
     expect_test::expect![[r#"
         Err(
-            "impls may overlap:\nimpl <ty> LocalTrait for ^ty0_0 where ^ty0_0 : Iterator { }\nimpl <ty> LocalTrait for <^ty0_0 as Mirror>::T where ^ty0_0 : Mirror { }",
+            "impls may overlap:\nimpl <ty T> LocalTrait for ^T where ^T : Iterator { }\nimpl <ty T> LocalTrait for <^T as Mirror>::T where ^T : Mirror { }",
         )
     "#]] // FIXME
     .assert_debug_eq(&test_program_ok(&gen_program(
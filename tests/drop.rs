@@ -0,0 +1,186 @@
+#![allow(non_snake_case)] // we embed type names into the names for our test functions
+
+use a_mir_formality::{test_program_ok, test_where_clause};
+use formality_macros::test;
+
+const CORE: &str = "
+    crate core {
+        #[lang(drop)]
+        trait Drop { }
+
+        trait Copy { }
+
+        struct CoreStruct<ty T> { value: T }
+    }
+";
+
+fn gen_program(addl: &str) -> String {
+    const BASE_PROGRAM: &str = "[
+        CORE,
+        crate foo {
+            struct Guard<ty T> where T: Copy { value: T }
+
+            struct Vec<ty T> { value: T }
+
+            ADDITIONAL
+        }
+    ]";
+
+    BASE_PROGRAM
+        .replace("CORE", CORE)
+        .replace("ADDITIONAL", addl)
+}
+
+#[test]
+fn test_drop_impl_ok() {
+    expect_test::expect![[r#"
+        Ok(
+            (),
+        )
+    "#]]
+    .assert_debug_eq(&test_program_ok(&gen_program(
+        "impl<ty T> Drop for Guard<T> where T: Copy { }
+         impl<ty T> Drop for Vec<T> may_dangle(T) { }",
+    )));
+}
+
+#[test]
+fn test_drop_impl_for_foreign_type() {
+    expect_test::expect![[r#"
+        Err(
+            Error {
+                context: "check_trait_impl(impl <ty T> Drop for CoreStruct<^T> { })",
+                source: Error {
+                    context: "check_drop_impl(impl <ty T> Drop for CoreStruct<^T> { })",
+                    source: "`Drop` impl for `CoreStruct` must be in the crate that defines `CoreStruct`",
+                },
+            },
+        )
+    "#]]
+    .assert_debug_eq(&test_program_ok(&gen_program(
+        "impl<ty T> Drop for CoreStruct<T> { }",
+    )));
+}
+
+#[test]
+fn test_drop_impl_specialized() {
+    expect_test::expect![[r#"
+        Err(
+            Error {
+                context: "check_trait_impl(impl Drop for Vec<u32> { })",
+                source: Error {
+                    context: "check_drop_impl(impl Drop for Vec<u32> { })",
+                    source: "`Drop` impl for `Vec<u32>` must be generic over exactly the parameters of `Vec`",
+                },
+            },
+        )
+    "#]]
+    .assert_debug_eq(&test_program_ok(&gen_program(
+        "impl Drop for Vec<u32> { }",
+    )));
+}
+
+#[test]
+fn test_drop_impl_extra_where_clause() {
+    expect_test::expect![[r#"
+        Err(
+            Error {
+                context: "check_trait_impl(impl <ty T> Drop for Vec<^T> where ^T : Copy { })",
+                source: Error {
+                    context: "check_drop_impl(impl <ty T> Drop for Vec<^T> where ^T : Copy { })",
+                    source: "failed to prove {Copy(!T)} given {}, got {}\nprove_wc_list(goal: {Copy(!T)}, assumptions: {}, env: Env { variables: [!T], coherence_mode: false, candidate_preference: All }) failed\n  rule \"some\": `prove_wc(&decls, env, &assumptions, wc0)` had no results\n    prove_wc(goal: Copy(!T)) failed\n      rule \"trait candidates\": `winnow(env.candidate_preference(), trait_candidate(&decls, &env, &assumptions, &trait_ref))` had no results\n        trait_candidate(trait_ref: Copy(!T)) failed\n          rule \"assumption\": `&assumptions` had no results\n          rule \"positive impl\": `decls.impl_decls(&trait_ref.trait_id)` had no results\n          rule \"trait implied bound\": `decls.trait_invariants()` had no results",
+                },
+            },
+        )
+    "#]]
+    .assert_debug_eq(&test_program_ok(&gen_program(
+        "impl<ty T> Drop for Vec<T> where T: Copy { }",
+    )));
+}
+
+#[test]
+fn test_may_dangle_on_non_drop_impl() {
+    expect_test::expect![[r#"
+        Err(
+            Error {
+                context: "check_trait_impl(impl <ty T> Copy for Vec<^T> may_dangle (^T) { })",
+                source: "`may_dangle` is only permitted on impls of the `Drop` lang item",
+            },
+        )
+    "#]]
+    .assert_debug_eq(&test_program_ok(&gen_program(
+        "impl<ty T> Copy for Vec<T> may_dangle(T) { }",
+    )));
+}
+
+#[test]
+fn test_duplicate_drop_lang_item() {
+    expect_test::expect![[r#"
+        Err(
+            Error {
+                context: "check_trait(OtherDrop)",
+                source: "multiple traits declared as lang item `drop`",
+            },
+        )
+    "#]]
+    .assert_debug_eq(&test_program_ok(&gen_program(
+        "#[lang(drop)] trait OtherDrop { }",
+    )));
+}
+
+#[test]
+fn test_dropck_outlives_uses_drop_impls() {
+    let program = gen_program(
+        "impl<ty T> Drop for Guard<T> where T: Copy { }
+         impl<ty T> Drop for Vec<T> may_dangle(T) { }",
+    );
+
+    // `Vec`'s destructor promises not to touch `T`, so the reference may dangle...
+    expect_test::expect![[r#"
+        Ok(
+            {
+                Constraints {
+                    env: Env {
+                        variables: [
+                            !a,
+                            !T,
+                        ],
+                        coherence_mode: false,
+                        candidate_preference: All,
+                    },
+                    known_true: true,
+                    substitution: {},
+                    ambiguity: {},
+                },
+            },
+        )
+    "#]]
+    .assert_debug_eq(&test_where_clause(
+        &program,
+        "forall<lt a, ty T> {} => {@dropck_outlives(Vec<&a T>, a)}",
+    ));
+
+    // ...but `Guard`'s destructor may access `T`, so `T` must outlive `a`.
+    expect_test::expect![[r#"
+        Ok(
+            {},
+        )
+    "#]]
+    .assert_debug_eq(&test_where_clause(
+        &program,
+        "forall<lt a, ty T> {} => {@dropck_outlives(Guard<T>, a)}",
+    ));
+}
+
+#[test]
+fn test_multiple_drop_impls() {
+    expect_test::expect![[r#"
+        Err(
+            "impls may overlap:\nimpl <ty T> Drop for Vec<^T> may_dangle (^T) { }\nimpl <ty T> Drop for Vec<^T> { }",
+        )
+    "#]]
+    .assert_debug_eq(&test_program_ok(&gen_program(
+        "impl<ty T> Drop for Vec<T> may_dangle(T) { }
+         impl<ty T> Drop for Vec<T> { }",
+    )));
+}
@@ -0,0 +1,64 @@
+use formality_core::generate::Generator;
+use formality_rust::grammar::{Program, WhereClause};
+use formality_types::grammar::{ParameterKind, Ty};
+use formality_types::rust::{
+    assert_fold_visit_agree, assert_round_trip, generate, FormalityLang, Term,
+};
+
+/// Checks that `term` can be debug-printed, printed and parsed back,
+/// and that folding and visiting it agree.
+fn check<T: Term>(term: &T) {
+    assert!(!format!("{term:?}").is_empty());
+    assert_round_trip(term);
+    assert_fold_visit_agree(term);
+}
+
+/// Types mentioning free variables of every kind.
+#[test]
+fn fuzz_ty() {
+    let mut count = 0;
+    for seed in 0..1000 {
+        let mut g = Generator::<FormalityLang>::new(seed, 10).with_free_variables([
+            ParameterKind::Ty,
+            ParameterKind::Lt,
+            ParameterKind::Const,
+        ]);
+        if let Some(ty) = g.generate::<Ty>() {
+            check(&ty);
+            count += 1;
+        }
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn fuzz_where_clause() {
+    let mut count = 0;
+    for seed in 0..1000 {
+        if let Some(where_clause) = generate::<WhereClause>(seed, 10) {
+            check(&where_clause);
+            count += 1;
+        }
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn fuzz_program() {
+    let mut count = 0;
+    for seed in 0..200 {
+        if let Some(program) = generate::<Program>(seed, 30) {
+            check(&program);
+            count += 1;
+        }
+    }
+    assert!(count > 0);
+}
+
+/// The same seed always generates the same term.
+#[test]
+fn generate_is_deterministic() {
+    for seed in 0..100 {
+        assert_eq!(generate::<Program>(seed, 30), generate::<Program>(seed, 30));
+    }
+}
@@ -0,0 +1,9 @@
+use formality_rust::grammar::Program;
+use formality_types::rust::ebnf;
+
+/// The grammar of `.🔬` programs is checked in at `book/src/rust_grammar.ebnf`
+/// as a reference; run with `UPDATE_EXPECT=1` to regenerate it.
+#[test]
+fn program_grammar() {
+    expect_test::expect_file!["../book/src/rust_grammar.ebnf"].assert_eq(&ebnf::<Program>());
+}
@@ -0,0 +1,59 @@
+use a_mir_formality::test_program_ok;
+use formality_macros::test;
+
+/// Items and overlap checks run in parallel, but the error reported
+/// is always the one from the first failing check.
+#[test]
+fn first_error_is_reported() {
+    const PROGRAM: &str = "[
+        crate core {
+            trait Foo { }
+            trait Bar { }
+            trait Baz { }
+
+            impl<ty T> Foo for T { }
+            impl Foo for u32 { }
+
+            impl<ty T> Bar for T { }
+            impl Bar for u32 { }
+
+            impl<ty T> Baz for T { }
+            impl Baz for u32 { }
+        }
+    ]";
+
+    let first = test_program_ok(PROGRAM).unwrap_err().to_string();
+    expect_test::expect![[r#"
+        impls may overlap:
+        impl <ty T> Foo for ^T { }
+        impl Foo for u32 { }"#]]
+    .assert_eq(&first);
+
+    for _ in 0..10 {
+        assert_eq!(test_program_ok(PROGRAM).unwrap_err().to_string(), first);
+    }
+}
+
+#[test]
+fn first_failing_item_is_reported() {
+    const PROGRAM: &str = "[
+        crate core {
+            trait Foo<ty T> where T: Bar { }
+            trait Bar { }
+            struct A { }
+            struct B { }
+            struct C { }
+
+            impl Foo<u32> for A { }
+            impl Foo<u32> for B { }
+            impl Foo<u32> for C { }
+        }
+    ]";
+
+    let first = test_program_ok(PROGRAM).unwrap_err().to_string();
+    expect_test::expect!["check_trait_impl(impl Foo <u32> for A { })"].assert_eq(&first);
+
+    for _ in 0..10 {
+        assert_eq!(test_program_ok(PROGRAM).unwrap_err().to_string(), first);
+    }
+}
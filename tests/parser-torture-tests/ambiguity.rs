@@ -65,3 +65,28 @@ fn reduce_reduce_ambig() {
     "#]]
     .assert_debug_eq(&term);
 }
+
+/// The panic for an ambiguous parse lists each of the competing parses.
+#[test]
+fn reduce_reduce_ambig_message() {
+    #[term]
+    pub enum Root {
+        #[grammar($v0)]
+        OneId(Id),
+        #[grammar($v0 $v1)]
+        TwoId(Id, Id),
+        #[grammar($v0 $v1)]
+        TwoRr(Arc<Root>, Arc<Root>),
+    }
+
+    formality_core::id!(Id);
+
+    let panic = std::panic::catch_unwind(|| crate::ptt::term::<Root>("a b c")).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    expect_test::expect![[r#"
+        ambiguous parse of `a b` as `Root`, no parse is preferable to all others. The competing parses are:
+          0: `Root::OneId` consumed `a` with reductions ["Id", "Root::OneId"], producing a
+          1: `Root::TwoId` consumed `a b` with reductions ["Id", "Id", "Root::TwoId"], producing a b
+    "#]]
+    .assert_eq(message);
+}
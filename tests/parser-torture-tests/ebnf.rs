@@ -0,0 +1,80 @@
+use formality_core::parse::{CoreParse, ParseResult, Parser, Scope};
+use formality_core::term;
+use std::sync::Arc;
+
+#[term]
+pub enum Expr {
+    #[cast]
+    Id(Id),
+
+    #[grammar($v0 + $v1)]
+    #[precedence(1)]
+    Add(Arc<Expr>, Arc<Expr>),
+
+    #[grammar($v0 * $v1)]
+    #[precedence(2, right)]
+    Mul(Arc<Expr>, Arc<Expr>),
+
+    #[grammar(call $v0 $(v1) $?v2 $*v3 $<?v4> $:where $,v5)]
+    Call(Id, Vec<Expr>, Option<Id>, Vec<Id>, Vec<Id>, Vec<Expr>),
+
+    Unit,
+
+    Pair(Id, Id),
+}
+
+formality_core::id!(Id);
+
+#[test]
+fn ebnf() {
+    expect_test::expect![[r#"
+        Expr =
+            Id
+          | Expr "+" Expr  (* Add, precedence 1, left-associative *)
+          | Expr "*" Expr  (* Mul, precedence 2, right-associative *)
+          | "call" Id "(" [ Expr { "," Expr } [ "," ] ] ")" [ Id ] { Id } [ "<" [ Id { "," Id } [ "," ] ] ">" ] [ "where" [ Expr { "," Expr } [ "," ] ] ]  (* Call *)
+          | "unit"  (* Unit *)
+          | "pair" "(" Id "," Id [ "," ] ")"  (* Pair *)
+          ;
+        Id = identifier ;
+
+        identifier = ? a letter or `_`, then letters, digits and `_`, other than a keyword ? ;
+        (* keywords: "struct" "fn" "let" "in" "integer" *)
+    "#]]
+    .assert_eq(&crate::ptt::ebnf::<Expr>());
+}
+
+/// Types that are not nonterminals themselves are described by a `start` rule.
+#[test]
+fn ebnf_start() {
+    expect_test::expect![[r#"
+        start = "[" [ Id { "," Id } [ "," ] ] "]" ;
+
+        Id = identifier ;
+
+        identifier = ? a letter or `_`, then letters, digits and `_`, other than a keyword ? ;
+        (* keywords: "struct" "fn" "let" "in" "integer" *)
+    "#]]
+    .assert_eq(&crate::ptt::ebnf::<Vec<Id>>());
+}
+
+/// A type with a handwritten parser that does not describe its grammar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Opaque(Id);
+
+impl CoreParse<crate::ptt::FormalityLang> for Opaque {
+    fn parse<'t>(scope: &Scope<crate::ptt::FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "Opaque", |p| Ok(Opaque(p.nonterminal()?)))
+    }
+}
+
+/// Types that do not describe their grammar are opaque nonterminals.
+#[test]
+fn ebnf_opaque() {
+    expect_test::expect![[r#"
+        start = "[" [ Opaque { "," Opaque } [ "," ] ] "]" ;
+
+        Opaque = ? not described ? ;
+    "#]]
+    .assert_eq(&crate::ptt::ebnf::<Vec<Opaque>>());
+}
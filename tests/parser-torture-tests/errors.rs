@@ -0,0 +1,72 @@
+use formality_core::{term, test};
+use std::sync::Arc;
+
+#[term]
+pub enum Expr {
+    #[grammar(let $v0 = $v1 in $v2)]
+    Let(Id, Arc<Expr>, Arc<Expr>),
+
+    #[grammar(($v0, $v1))]
+    Pair(Arc<Expr>, Arc<Expr>),
+
+    #[grammar(($v0; $v1))]
+    Seq(Arc<Expr>, Arc<Expr>),
+
+    #[cast]
+    Id(Id),
+}
+
+formality_core::id!(Id);
+
+/// Errors point at the line and column where parsing got furthest.
+#[test]
+fn error_location() {
+    let err = crate::ptt::try_term::<Expr>("let x = (a, b)\n  on c").unwrap_err();
+    expect_test::expect![[r#"
+        expected `in`
+         --> <input>:2:3
+          |
+        2 |   on c
+          |   ^"#]]
+    .assert_eq(&err.to_string());
+}
+
+/// When several tokens would be accepted at the furthest point, all are listed.
+#[test]
+fn error_expected_set() {
+    let err = crate::ptt::try_term::<Expr>("let x = (a b) in a").unwrap_err();
+    expect_test::expect![[r#"
+        parse failed, one of the following was expected:
+          - expected `,`
+          - expected `;`
+         --> <input>:1:12
+          |
+        1 | let x = (a b) in a
+          |            ^"#]]
+    .assert_eq(&err.to_string());
+}
+
+#[test]
+fn error_extra_tokens() {
+    let err = crate::ptt::try_term_from_file::<Expr>("example.ptt", "(a, b) c").unwrap_err();
+    expect_test::expect![[r#"
+        expected end of input
+         --> example.ptt:1:8
+          |
+        1 | (a, b) c
+          |        ^"#]]
+    .assert_eq(&err.to_string());
+}
+
+/// A failure that does not say what was expected still points at the input.
+#[test]
+fn error_nothing_expected() {
+    let failure = formality_core::parse::ParseFailure::new("(a, b)", Default::default());
+    expect_test::expect![[r#"
+        parse failed
+         --> <input>:1:1
+          |
+        1 | (a, b)
+          | ^"#]]
+    .assert_eq(&failure.to_string());
+}
@@ -1,9 +1,14 @@
 mod ambiguity;
+mod ebnf;
+mod errors;
 mod grammar;
 mod left_associative;
+mod memoization;
 mod none_associative;
 mod path;
+mod print;
 mod right_associative;
+mod spans;
 
 formality_core::declare_language! {
     mod ptt {
@@ -0,0 +1,83 @@
+use formality_core::{term, test};
+use formality_rust::grammar::Program;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::path::Path;
+
+#[term]
+pub enum Tree {
+    #[grammar(leaf)]
+    Leaf,
+
+    #[grammar(( $v0 ))]
+    Nested(Arc<Tree>),
+
+    #[grammar(( $v0 ) maybe)]
+    Maybe(Arc<Tree>),
+
+    #[grammar(( $v0 ) never)]
+    Never(Arc<Tree>),
+}
+
+/// Every variant but `Leaf` starts with a parenthesized `Tree`, so without memoization
+/// parsing nested parentheses would take time exponential in their depth.
+#[test]
+fn shared_prefixes() {
+    let depth = 40;
+    let text = format!("{}leaf{}", "(".repeat(depth), ")".repeat(depth));
+    let mut tree: Tree = crate::ptt::term(&text);
+    for _ in 0..depth {
+        let Tree::Nested(t) = tree else {
+            panic!("expected `Nested`, found `{tree:?}`")
+        };
+        tree = (*t).clone();
+    }
+    assert_eq!(tree, Tree::Leaf);
+}
+
+/// Each round of growing a left-recursive parse re-parses the indices,
+/// which are themselves left-recursive.
+#[test]
+fn nested_left_recursion() {
+    let depth = 20;
+    let text = format!("a{}.b{}", "[a".repeat(depth), "]".repeat(depth));
+    let path: Path = crate::ptt::term(&text);
+    assert_eq!(format!("{path:?}").matches('[').count(), depth);
+
+    let path: Path = crate::ptt::term("a[b.c[d]].e[f]");
+    expect_test::expect!["a [b . c [d]] . e [f]"].assert_eq(&crate::ptt::print(&path).text);
+}
+
+/// A program of about 1000 lines, the size of a large `.🔬` file, should parse in milliseconds.
+/// The bound is generous so that the test only fails if parsing is much slower than linear.
+#[test]
+fn large_program() {
+    let items: usize = 100;
+    let mut text = String::from("[\n    crate large {\n");
+    for i in 0..items {
+        let j = i.saturating_sub(1);
+        text += &format!(
+            "        trait Trait{i}<ty T> where T: Trait{j}<u32> {{
+            fn method<ty U>(U, T) -> Vec<(T, [U])> where U: Trait{i}<T> {{ trusted }}
+        }}
+        struct Struct{i}<ty T> {{
+            a: Vec<T>,
+            b: &'static Struct{j}<(T, u32)>,
+        }}
+        impl<ty T> Trait{i}<T> for Struct{i}<T> where T: Trait{j}<u32>, for<ty U> U: Trait{i}<T> {{
+            fn method<ty U>(U, T) -> Vec<(T, [U])> where U: Trait{i}<T> {{ trusted }}
+        }}
+        fn f{i}<ty T, lt a>(&a Struct{i}<T>) -> () where T: Trait{i}<u32> {{ trusted }}
+"
+        );
+    }
+    text += "    }\n]\n";
+    assert!(text.lines().count() >= 1000);
+
+    let start = Instant::now();
+    let program: Program = formality_types::rust::term(&text);
+    let elapsed = start.elapsed();
+    assert_eq!(program.crates[0].items.len(), 4 * items);
+    assert!(elapsed < Duration::from_secs(5), "parsing took {elapsed:?}");
+}
@@ -0,0 +1,84 @@
+use formality_core::{term, test};
+use std::sync::Arc;
+
+#[term]
+pub enum Expr {
+    #[cast]
+    Id(Id),
+
+    #[grammar($v0 + $v1)]
+    #[precedence(1)]
+    Add(Arc<Expr>, Arc<Expr>),
+
+    #[grammar($v0 * $v1)]
+    #[precedence(2)]
+    Mul(Arc<Expr>, Arc<Expr>),
+
+    #[grammar(call $v0 $(v1))]
+    Call(Id, Vec<Expr>),
+}
+
+formality_core::id!(Id);
+
+fn id(name: &str) -> Arc<Expr> {
+    Arc::new(Expr::Id(Id::new(name)))
+}
+
+fn add(a: Arc<Expr>, b: Arc<Expr>) -> Arc<Expr> {
+    Arc::new(Expr::Add(a, b))
+}
+
+fn mul(a: Arc<Expr>, b: Arc<Expr>) -> Arc<Expr> {
+    Arc::new(Expr::Mul(a, b))
+}
+
+fn print(e: &Expr) -> String {
+    crate::ptt::print(e).text
+}
+
+#[test]
+fn round_trip() {
+    let term: Expr = crate::ptt::term("a + b * c + call f(d * e, g + h)");
+    crate::ptt::assert_round_trip(&term);
+    expect_test::expect!["a + b * c + call f (d * e, g + h)"].assert_eq(&print(&term));
+}
+
+#[test]
+fn parenthesize_by_precedence() {
+    // `+` is left-associative, so only the right operand needs parentheses.
+    expect_test::expect!["a + b + c"].assert_eq(&print(&add(add(id("a"), id("b")), id("c"))));
+    expect_test::expect!["a + (b + c)"].assert_eq(&print(&add(id("a"), add(id("b"), id("c")))));
+
+    // `*` binds tighter than `+`.
+    expect_test::expect!["(a + b) * c"].assert_eq(&print(&mul(add(id("a"), id("b")), id("c"))));
+    expect_test::expect!["a * b + c"].assert_eq(&print(&add(mul(id("a"), id("b")), id("c"))));
+
+    // Arguments are delimited, so they never need parentheses.
+    let call = Expr::Call(Id::new("f"), vec![(*add(id("a"), id("b"))).clone()]);
+    expect_test::expect!["c * call f (a + b)"].assert_eq(&print(&mul(id("c"), Arc::new(call))));
+}
+
+#[test]
+fn break_long_lists() {
+    let args: Vec<Expr> = (0..12)
+        .map(|i| (*add(id(&format!("argument{i}")), id("x"))).clone())
+        .collect();
+    let term = Expr::Call(Id::new("f"), args);
+    crate::ptt::assert_round_trip(&term);
+    expect_test::expect![[r#"
+        call f (
+            argument0 + x,
+            argument1 + x,
+            argument2 + x,
+            argument3 + x,
+            argument4 + x,
+            argument5 + x,
+            argument6 + x,
+            argument7 + x,
+            argument8 + x,
+            argument9 + x,
+            argument10 + x,
+            argument11 + x,
+        )"#]]
+    .assert_eq(&print(&term));
+}
@@ -0,0 +1,64 @@
+use formality_core::{term, test};
+use std::sync::Arc;
+
+#[term]
+pub enum Expr {
+    #[grammar(let $v0 = $v1 in $v2)]
+    Let(Id, Arc<Expr>, Arc<Expr>),
+
+    #[grammar(($v0, $v1))]
+    Pair(Arc<Expr>, Arc<Expr>),
+
+    #[cast]
+    Id(Id),
+}
+
+formality_core::id!(Id);
+
+/// Terms parsed from a file can be mapped back to where they came from.
+#[test]
+fn spans_of_parsed_terms() {
+    let text = "let x = (a, b)\n  in (x,\n      c)";
+    let (term, source_map): (Expr, _) =
+        crate::ptt::try_term_from_file("example.ptt", text).unwrap();
+    let Expr::Let(_, _, body) = &term else {
+        panic!("expected a let, got {term:?}")
+    };
+
+    let spans: Vec<String> = [&term, body, &crate::ptt::term("c"), &crate::ptt::term("z")]
+        .into_iter()
+        .map(|e| match source_map.span_of(e) {
+            Some(span) => format!("{span}: {:?}", span.text()),
+            None => "no span".to_string(),
+        })
+        .collect();
+    expect_test::expect![[r#"
+        [
+            "example.ptt:1:1: \"let x = (a, b)\\n  in (x,\\n      c)\"",
+            "example.ptt:2:6: \"(x,\\n      c)\"",
+            "example.ptt:3:7: \"c\"",
+            "no span",
+        ]
+    "#]]
+    .assert_debug_eq(&spans);
+}
+
+/// Each file has its own spans, even for terms that appear in both.
+#[test]
+fn spans_are_per_file() {
+    let (a, a_map): (Expr, _) = crate::ptt::try_term_from_file("a.ptt", "(x, y)").unwrap();
+    let (b, b_map): (Expr, _) = crate::ptt::try_term_from_file("b.ptt", "\n\n(x, y)").unwrap();
+    assert_eq!(a, b);
+
+    let spans: Vec<String> = [a_map.span_of(&a), b_map.span_of(&b)]
+        .into_iter()
+        .map(|span| span.unwrap().to_string())
+        .collect();
+    expect_test::expect![[r#"
+        [
+            "a.ptt:1:1",
+            "b.ptt:3:1",
+        ]
+    "#]]
+    .assert_debug_eq(&spans);
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use formality_core::test;
+use formality_rust::grammar::Program;
+use formality_types::rust::{assert_round_trip, print, term, try_term};
+
+/// Every program in the UI tests prints as text that parses back to the same program.
+#[test]
+fn ui_programs_round_trip() {
+    fn visit(dir: &Path, count: &mut usize) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                visit(&path, count);
+            } else if path.extension().is_some_and(|ext| ext == "🔬") {
+                let text = std::fs::read_to_string(&path).unwrap();
+                // Some UI tests are about programs that do not parse.
+                if let Ok(program) = try_term::<Program>(&text) {
+                    assert_round_trip(&program);
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    let mut count = 0;
+    visit(Path::new("tests/ui"), &mut count);
+    assert!(count > 0);
+}
+
+#[test]
+fn print_program() {
+    let program: Program = term(
+        "[
+            crate core {
+                trait Foo<ty T> where T: Bar<Self> { }
+                trait Bar<ty T> { }
+                impl<ty U, lt a> Foo<&a U> for Vec<U> where U: Bar<Vec<U>>, for<ty X> X: Bar<U> { }
+                struct Vec<ty T> { }
+                fn f<ty T, const N: usize>(T, [T; N]) -> () { trusted }
+            }
+        ]",
+    );
+    assert_round_trip(&program);
+    expect_test::expect![[r#"
+        [
+            crate core {
+                trait Foo <ty T0> where T0 : Bar <Self> { }
+                trait Bar <ty T1> { }
+                impl <ty T2, lt L3> Foo <&'L3 T2> for Vec<T2> where
+                    T2 : Bar <Vec<T2>>,
+                    for <ty T4> T4 : Bar <T2>,
+                { }
+                struct Vec <ty T5> { }
+                fn f <ty T6, const C7> (T6, [T6; C7]) -> () where type_of_const C7 is usize { trusted }
+            },
+        ]"#]]
+    .assert_eq(&print(&program).text);
+}
+
+/// Free variables are given names, which are reported alongside the text.
+#[test]
+fn print_free_variables() {
+    let binder: formality_types::grammar::Binder<formality_types::grammar::Ty> =
+        term("<ty T, lt a> &a Vec<T>");
+    let (_, ty) = binder.open();
+    let printed = print(&ty);
+    expect_test::expect!["&'L0 Vec<T1>"].assert_eq(&printed.text);
+    assert_eq!(printed.free_variables.len(), 2);
+    assert_round_trip(&ty);
+}
+
+/// Invented names skip identifiers that the term uses, such as an ADT named `T0`.
+#[test]
+fn print_avoids_identifiers() {
+    let binder: formality_types::grammar::Binder<formality_types::grammar::Ty> =
+        term("<ty X> (T0, T2, X)");
+    expect_test::expect!["<ty T1> (T0, T2, T1)"].assert_eq(&print(&binder).text);
+    assert_round_trip(&binder);
+
+    let (_, ty) = binder.open();
+    expect_test::expect!["(T0, T2, T1)"].assert_eq(&print(&ty).text);
+    assert_round_trip(&ty);
+}
+
+/// Debug output uses the names variables were written with, adding a suffix to
+/// a bound variable whose name is already used by a free variable.
+#[test]
+fn debug_source_names() {
+    use formality_types::grammar::{Binder, Ty};
+
+    let binder: Binder<Ty> = term("<ty T, lt a> &a Vec<T>");
+    expect_test::expect!["<ty T, lt a> &^a Vec<^T>"].assert_eq(&format!("{binder:?}"));
+
+    let (_, ty) = binder.open();
+    let (vars, t) = term::<Binder<Ty>>("<ty T> T").open();
+    let rebound = Binder::new(vars, vec![ty, t]);
+    expect_test::expect!["<ty T_1> [&^a Vec<^T>, ^T_1]"].assert_eq(&format!("{rebound:?}"));
+}
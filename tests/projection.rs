@@ -24,28 +24,32 @@ fn normalize_basic() {
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
-                            ?ty_2,
+                            !T,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_2 => <Vec<!ty_1> as Iterator>::Item,
+                        ?U => <Vec<!T> as Iterator>::Item,
                     },
+                    ambiguity: {},
                 },
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
-                            ?ty_2,
+                            !T,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_2 => !ty_1,
+                        ?U => !T,
                     },
+                    ambiguity: {},
                 },
             },
         )
@@ -61,12 +65,14 @@ fn normalize_basic() {
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
+                            !T,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {},
+                    ambiguity: {},
                 },
             },
         )
@@ -82,12 +88,14 @@ fn normalize_basic() {
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
+                            !T,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {},
+                    ambiguity: {},
                 },
             },
         )
@@ -103,15 +111,17 @@ fn normalize_basic() {
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
-                            ?ty_2,
+                            !T,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_2 => <!ty_1 as Iterator>::Item,
+                        ?U => <!T as Iterator>::Item,
                     },
+                    ambiguity: {},
                 },
             },
         )
@@ -127,12 +137,14 @@ fn normalize_basic() {
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
+                            !T,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {},
+                    ambiguity: {},
                 },
             },
         )
@@ -148,30 +160,34 @@ fn normalize_basic() {
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
-                            ?ty_2,
+                            !T,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_2 => !ty_1,
+                        ?U => !T,
                     },
+                    ambiguity: {},
                 },
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
+                            !T,
                             ?ty_3,
-                            ?ty_2,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_2 => Vec<<!ty_1 as Iterator>::Item>,
-                        ?ty_3 => <!ty_1 as Iterator>::Item,
+                        ?U => Vec<<!T as Iterator>::Item>,
+                        ?ty_3 => <!T as Iterator>::Item,
                     },
+                    ambiguity: {},
                 },
             },
         )
@@ -214,28 +230,32 @@ fn normalize_into_iterator() {
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
-                            ?ty_2,
+                            !T,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_2 => <Vec<!ty_1> as IntoIterator>::Item,
+                        ?U => <Vec<!T> as IntoIterator>::Item,
                     },
+                    ambiguity: {},
                 },
                 Constraints {
                     env: Env {
                         variables: [
-                            !ty_1,
-                            ?ty_2,
+                            !T,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_2 => !ty_1,
+                        ?U => !T,
                     },
+                    ambiguity: {},
                 },
             },
         )
@@ -268,26 +288,30 @@ fn projection_equality() {
                 Constraints {
                     env: Env {
                         variables: [
-                            ?ty_1,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_1 => u32,
+                        ?U => u32,
                     },
+                    ambiguity: {},
                 },
                 Constraints {
                     env: Env {
                         variables: [
-                            ?ty_1,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_1 => <S as Trait1>::Type,
+                        ?U => <S as Trait1>::Type,
                     },
+                    ambiguity: {},
                 },
             },
         )
@@ -303,26 +327,30 @@ fn projection_equality() {
                 Constraints {
                     env: Env {
                         variables: [
-                            ?ty_1,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_1 => u32,
+                        ?U => u32,
                     },
+                    ambiguity: {},
                 },
                 Constraints {
                     env: Env {
                         variables: [
-                            ?ty_1,
+                            ?U,
                         ],
                         coherence_mode: false,
+                        candidate_preference: All,
                     },
                     known_true: true,
                     substitution: {
-                        ?ty_1 => <S as Trait1>::Type,
+                        ?U => <S as Trait1>::Type,
                     },
+                    ambiguity: {},
                 },
             },
         )
@@ -0,0 +1,37 @@
+//! `for` and `for_all` introduce quantifiers, but they are not keywords:
+//! they can still name traits and ADTs where that is not ambiguous.
+
+use formality_rust::grammar::{Program, WhereBound};
+use formality_types::grammar::Ty;
+use formality_types::rust::{term, try_term};
+
+#[test]
+fn for_all_as_adt_name() {
+    let ty: Ty = term("for_all(<ty T> Vec<T>)");
+    expect_test::expect!["for_all(<ty T> Vec<^T>)"].assert_eq(&format!("{ty:?}"));
+
+    let ty: Ty = term("for_all");
+    expect_test::expect!["for_all"].assert_eq(&format!("{ty:?}"));
+
+    let ty: Ty = term("for_all<u32>");
+    expect_test::expect!["for_all<u32>"].assert_eq(&format!("{ty:?}"));
+}
+
+#[test]
+fn for_as_trait_name() {
+    let bound: WhereBound = term("for<> 'static");
+    expect_test::expect!["for static"].assert_eq(&format!("{bound:?}"));
+
+    let bound: WhereBound = term("for static");
+    expect_test::expect!["for static"].assert_eq(&format!("{bound:?}"));
+
+    let bound: WhereBound = term("for<u32>");
+    expect_test::expect!["for <u32>"].assert_eq(&format!("{bound:?}"));
+
+    let bound: WhereBound = term("for");
+    expect_test::expect!["for"].assert_eq(&format!("{bound:?}"));
+
+    assert!(
+        try_term::<Program>("[crate foo { trait for { } trait Bar where u32: for { } }]").is_ok()
+    );
+}
@@ -0,0 +1,68 @@
+//! Rust-style spellings of generics and lifetimes mean the same as the explicit forms.
+
+use a_mir_formality::test_program_ok;
+use formality_core::test;
+use formality_rust::grammar::{Program, WhereClause};
+use formality_types::grammar::Ty;
+use formality_types::rust::term;
+
+#[track_caller]
+fn assert_same<T: formality_types::rust::Term>(rust_style: &str, explicit: &str) {
+    let a: T = term(rust_style);
+    let b: T = term(explicit);
+    assert_eq!(a, b, "`{rust_style}` and `{explicit}` parse differently");
+}
+
+#[test]
+fn lifetimes() {
+    assert_same::<Ty>("&'static u32", "&static u32");
+    assert_same::<Ty>("&'static mut u32", "&mut static u32");
+    assert_same::<WhereClause>(
+        "for<'a, 'b> &'a mut &'b u32: Foo<'b>",
+        "for<lt a, lt b> &mut a &b u32: Foo<b>",
+    );
+
+    // `T: a` could be a trait bound as well as an outlives bound; `T: 'a` can't.
+    let wc: WhereClause = term("for<'a> u32: 'a");
+    expect_test::expect!["for <lt a> u32 : ^a"].assert_eq(&format!("{wc:?}"));
+}
+
+#[test]
+fn implicit_kinds() {
+    assert_same::<Program>(
+        "[crate foo {
+            struct Pair<'a, T, const N: usize> { field: &'a [T; N] }
+            fn one_lt_arg<'a, T>(&'a T) -> () { trusted }
+        }]",
+        "[crate foo {
+            struct Pair<lt a, ty T, const N: usize> { field: &a [T; N] }
+            fn one_lt_arg<lt a, ty T>(&a T) -> () { trusted }
+        }]",
+    );
+}
+
+#[test]
+fn lifetime_name_follows_tick() {
+    let err = formality_types::rust::try_term::<Ty>("&' a u32").unwrap_err();
+    expect_test::expect![[r#"
+        expected a lifetime name after `'`
+         --> <input>:1:4
+          |
+        1 | &' a u32
+          |    ^"#]]
+    .assert_eq(&err.to_string());
+}
+
+#[test]
+fn check_rust_style_program() {
+    test_program_ok(
+        "[
+            crate core {
+                trait Foo<'a, T> { }
+                trait Bar<'a> { }
+                impl<'a, T> Foo<'a, T> for u32 where for<'b> T: Bar<'b> { }
+            }
+        ]",
+    )
+    .unwrap();
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use a_mir_formality::test_where_clause;
+use formality_core::serde::{de::DeserializeOwned, Serialize};
+use formality_prove::{prove_with_proof_trees, Constraints, Decls};
+use formality_rust::grammar::Program;
+use formality_types::grammar::{Binder, Ty, Wc};
+use formality_types::rust::{term, try_term};
+
+/// Serializes `value` to JSON and deserializes it back, returning the JSON.
+fn json_round_trip<T>(value: &T) -> String
+where
+    T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let json = serde_json::to_string(value).unwrap();
+    let parsed: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(&parsed, value, "JSON was `{json}`");
+    json
+}
+
+/// Every program in the UI tests, and the declarations the solver derives from it,
+/// survive a trip through JSON.
+#[test]
+fn ui_programs_round_trip() {
+    fn visit(dir: &Path, count: &mut usize) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                visit(&path, count);
+            } else if path.extension().is_some_and(|ext| ext == "🔬") {
+                let text = std::fs::read_to_string(&path).unwrap();
+                // Some UI tests are about programs that do not parse.
+                if let Ok(program) = try_term::<Program>(&text) {
+                    json_round_trip(&program);
+                    json_round_trip(&program.to_prove_decls());
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    let mut count = 0;
+    visit(Path::new("tests/ui"), &mut count);
+    assert!(count > 0);
+}
+
+#[test]
+fn binder_json() {
+    let binder: Binder<Ty> = term("<ty T, lt a> &a Vec<T>");
+    let json = serde_json::to_string_pretty(&binder).unwrap();
+    expect_test::expect_file!["serde/binder.json"].assert_eq(&json);
+    assert_eq!(serde_json::from_str::<Binder<Ty>>(&json).unwrap(), binder);
+}
+
+#[test]
+fn constraints_round_trip() {
+    let constraints = test_where_clause(
+        "[
+            crate test {
+                trait Iterator {
+                    type Item : [];
+                }
+
+                struct Vec<ty T> {}
+
+                impl<ty T> Iterator for Vec<T> {
+                    type Item = T;
+                }
+            }
+        ]",
+        "forall<ty T> exists<ty U> {} => { <Vec<T> as Iterator>::Item = U }",
+    )
+    .unwrap();
+    assert!(!constraints.is_empty());
+    json_round_trip(&constraints);
+}
+
+#[test]
+fn proof_trees_serialize() {
+    let decls: Decls =
+        term::<Program>("[crate core { trait Debug {} impl Debug for u32 {} }]").to_prove_decls();
+    let goal: Wc = term("Debug(u32)");
+    let results = prove_with_proof_trees(decls, (), (), goal);
+    assert_eq!(results.len(), 1);
+    let (constraints, tree): &(Constraints, _) = results.first().unwrap();
+    json_round_trip(constraints);
+
+    let json: serde_json::Value = serde_json::to_value(tree).unwrap();
+    assert_eq!(json["judgment"], "prove");
+    assert!(!json["children"].as_array().unwrap().is_empty());
+}
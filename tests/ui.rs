@@ -7,6 +7,10 @@ fn main() -> Result<()> {
     // Prevent ui_test from trying to query `formality` for the host platform name
     config.host = Some("non of ya bizniz".into());
     config.program.program = "target/debug/a-mir-formality".into();
+    // Backtraces contain paths and line numbers that vary between machines and revisions.
+    for var in ["RUST_BACKTRACE", "RUST_LIB_BACKTRACE"] {
+        config.program.envs.push((var.into(), None));
+    }
     config.mode = Mode::Fail {
         require_patterns: false,
     };